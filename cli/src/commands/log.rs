@@ -1,10 +1,28 @@
 use crate::ipc::{send_request, IpcRequest};
 
-pub async fn run(socket_path: &str, card_id: &str, message: &str) -> Result<String, String> {
+pub async fn run(
+    socket_path: &str,
+    card_id: &str,
+    message: &str,
+    level: &str,
+    data: Option<&str>,
+) -> Result<String, String> {
+    let data = match data {
+        Some(data) => {
+            let value: serde_json::Value =
+                serde_json::from_str(data).map_err(|e| format!("Invalid --data JSON: {e}"))?;
+            if !value.is_object() {
+                return Err("--data must be a JSON object".to_string());
+            }
+            Some(value)
+        }
+        None => None,
+    };
+
     let request = IpcRequest {
         command: "log".to_string(),
         card_id: card_id.to_string(),
-        payload: serde_json::json!({ "message": message }),
+        payload: serde_json::json!({ "message": message, "level": level, "data": data }),
     };
 
     let response = send_request(socket_path, request).await?;
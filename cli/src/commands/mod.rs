@@ -5,4 +5,5 @@ pub mod get_parent;
 pub mod log;
 pub mod question;
 pub mod resolve_question;
+pub mod search;
 pub mod status;
@@ -1,23 +1,42 @@
-use crate::ipc::{send_request, IpcRequest};
+use crate::ipc::{send_request, send_waiting_request, IpcRequest};
 
-pub async fn run(socket_path: &str, card_id: &str, question: &str) -> Result<String, String> {
+pub async fn run(
+    socket_path: &str,
+    card_id: &str,
+    question: &str,
+    wait: bool,
+    timeout: Option<u64>,
+) -> Result<String, String> {
     let request = IpcRequest {
         command: "question".to_string(),
         card_id: card_id.to_string(),
-        payload: serde_json::json!({ "question": question }),
+        payload: serde_json::json!({ "question": question, "wait": wait, "timeout_secs": timeout }),
     };
 
-    let response = send_request(socket_path, request).await?;
-
-    if response.ok {
-        let id = response
-            .data
-            .as_ref()
-            .and_then(|d| d.get("id"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("unknown");
-        Ok(format!("Question created: {id}"))
+    let response = if wait {
+        send_waiting_request(socket_path, request).await?
     } else {
-        Err(response.error.unwrap_or_else(|| "Unknown error".to_string()))
+        send_request(socket_path, request).await?
+    };
+
+    if !response.ok {
+        return Err(response.error.unwrap_or_else(|| "Unknown error".to_string()));
+    }
+
+    let data = response.data.as_ref();
+    let id = data
+        .and_then(|d| d.get("id"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+
+    if !wait {
+        return Ok(format!("Question created: {id}"));
+    }
+
+    // The answer is all the agent needs; a question resolved without one
+    // still unblocks it.
+    match data.and_then(|d| d.get("resolution")).and_then(|v| v.as_str()) {
+        Some(resolution) => Ok(resolution.to_string()),
+        None => Ok(format!("Question {id} was resolved without an answer")),
     }
 }
@@ -0,0 +1,29 @@
+use crate::ipc::{send_request, IpcRequest};
+
+pub async fn run(
+    socket_path: &str,
+    card_id: &str,
+    query: &str,
+    limit: Option<u32>,
+) -> Result<String, String> {
+    let mut payload = serde_json::json!({ "query": query });
+    if let Some(limit) = limit {
+        payload["limit"] = serde_json::json!(limit);
+    }
+
+    let request = IpcRequest {
+        command: "search".to_string(),
+        card_id: card_id.to_string(),
+        payload,
+    };
+
+    let response = send_request(socket_path, request).await?;
+
+    if response.ok {
+        let data = response.data.unwrap_or(serde_json::json!([]));
+        serde_json::to_string_pretty(&data)
+            .map_err(|e| format!("Failed to format response: {e}"))
+    } else {
+        Err(response.error.unwrap_or_else(|| "Unknown error".to_string()))
+    }
+}
@@ -18,6 +18,17 @@ pub struct IpcResponse {
 }
 
 pub async fn send_request(socket_path: &str, request: IpcRequest) -> Result<IpcResponse, String> {
+    exchange(socket_path, request, false).await
+}
+
+/// Like [`send_request`], for requests the server holds open until something
+/// happens. The write half stays open so the server can tell when the CLI
+/// goes away and give up waiting.
+pub async fn send_waiting_request(socket_path: &str, request: IpcRequest) -> Result<IpcResponse, String> {
+    exchange(socket_path, request, true).await
+}
+
+async fn exchange(socket_path: &str, request: IpcRequest, hold_open: bool) -> Result<IpcResponse, String> {
     if !Path::new(socket_path).exists() {
         return Err(format!(
             "Socket not found at {socket_path}. Is the Maestro app running?"
@@ -39,10 +50,12 @@ pub async fn send_request(socket_path: &str, request: IpcRequest) -> Result<IpcR
         .await
         .map_err(|e| format!("Failed to send request: {e}"))?;
 
-    writer
-        .shutdown()
-        .await
-        .map_err(|e| format!("Failed to shutdown write half: {e}"))?;
+    if !hold_open {
+        writer
+            .shutdown()
+            .await
+            .map_err(|e| format!("Failed to shutdown write half: {e}"))?;
+    }
 
     let mut buf_reader = BufReader::new(reader);
     let mut response_line = String::new();
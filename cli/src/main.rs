@@ -16,6 +16,12 @@ enum Commands {
     Question {
         /// The question text
         question: String,
+        /// Block until the user answers, then print the answer
+        #[arg(long)]
+        wait: bool,
+        /// Give up waiting after this many seconds (default: 3600)
+        #[arg(long, requires = "wait", value_name = "SECS")]
+        timeout: Option<u64>,
     },
     /// Resolve an open question
     ResolveQuestion {
@@ -44,6 +50,12 @@ enum Commands {
     Log {
         /// The log message
         message: String,
+        /// Severity: debug, info, warn or error
+        #[arg(long, default_value = "info", value_parser = ["debug", "info", "warn", "error"])]
+        level: String,
+        /// Structured fields to store with the note, as a JSON object
+        #[arg(long)]
+        data: Option<String>,
     },
     /// Get the current card's details as JSON
     GetCard,
@@ -51,6 +63,14 @@ enum Commands {
     GetArtifacts,
     /// Get the parent card's details as JSON
     GetParent,
+    /// Search the project's cards, artifacts, questions and messages
+    Search {
+        /// Words to look for
+        query: String,
+        /// Maximum number of results (default 20)
+        #[arg(long)]
+        limit: Option<u32>,
+    },
 }
 
 fn env_var(name: &str) -> Result<String, String> {
@@ -81,9 +101,11 @@ async fn main() {
     };
 
     let result = match cli.command {
-        Commands::Question { question } => {
-            commands::question::run(&socket_path, &card_id, &question).await
-        }
+        Commands::Question {
+            question,
+            wait,
+            timeout,
+        } => commands::question::run(&socket_path, &card_id, &question, wait, timeout).await,
         Commands::ResolveQuestion { id, resolution } => {
             commands::resolve_question::run(&socket_path, &card_id, &id, resolution.as_deref())
                 .await
@@ -94,12 +116,17 @@ async fn main() {
         Commands::SetStatus { status } => {
             commands::status::run(&socket_path, &card_id, &status).await
         }
-        Commands::Log { message } => {
-            commands::log::run(&socket_path, &card_id, &message).await
-        }
+        Commands::Log {
+            message,
+            level,
+            data,
+        } => commands::log::run(&socket_path, &card_id, &message, &level, data.as_deref()).await,
         Commands::GetCard => commands::get_card::run(&socket_path, &card_id).await,
         Commands::GetArtifacts => commands::get_artifacts::run(&socket_path, &card_id).await,
         Commands::GetParent => commands::get_parent::run(&socket_path, &card_id).await,
+        Commands::Search { query, limit } => {
+            commands::search::run(&socket_path, &card_id, &query, limit).await
+        }
     };
 
     match result {
@@ -0,0 +1,139 @@
+//! A stand-in agent CLI for exercising the executor end to end. It follows a
+//! scenario file instead of talking to a model:
+//!
+//! ```json
+//! [
+//!   { "stdout": "session: abc123" },
+//!   { "question": "Which database should I use?" },
+//!   "echo_line",
+//!   { "sleep_ms": 200 },
+//!   { "exit": 3 }
+//! ]
+//! ```
+//!
+//! Launch it with `--scenario <path>`. Anything else on the command line, such
+//! as the prompt Maestro passes, is ignored, except `--resume <id>` which is
+//! announced on stdout as `resumed <id>` before the scenario starts.
+
+use std::io::Write;
+#[cfg(unix)]
+use std::io::{BufRead, BufReader};
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Step {
+    /// Prints a line to stdout.
+    Stdout(String),
+    /// Prints a line to stderr.
+    Stderr(String),
+    /// Reads a line from stdin and prints it back as `echo: <line>`.
+    EchoLine,
+    SleepMs(u64),
+    /// Asks an open question through the Maestro socket, like
+    /// `maestro question`.
+    Question(String),
+    /// Sends an arbitrary request to the Maestro socket and prints the
+    /// response.
+    Ipc {
+        command: String,
+        #[serde(default)]
+        payload: serde_json::Value,
+    },
+    Exit(i32),
+}
+
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("mock-agent: {e}");
+        std::process::exit(101);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut scenario_path = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--scenario" => scenario_path = args.next(),
+            "--resume" => {
+                let session = args.next().unwrap_or_default();
+                say(&format!("resumed {session}"));
+            }
+            _ => {}
+        }
+    }
+
+    let path = scenario_path.ok_or("missing --scenario <path>")?;
+    let contents =
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read scenario {path}: {e}"))?;
+    let steps: Vec<Step> =
+        serde_json::from_str(&contents).map_err(|e| format!("Failed to parse scenario {path}: {e}"))?;
+
+    for step in steps {
+        match step {
+            Step::Stdout(line) => say(&line),
+            Step::Stderr(line) => eprintln!("{line}"),
+            Step::EchoLine => {
+                let mut line = String::new();
+                std::io::stdin()
+                    .read_line(&mut line)
+                    .map_err(|e| format!("Failed to read stdin: {e}"))?;
+                say(&format!("echo: {}", line.trim_end()));
+            }
+            Step::SleepMs(ms) => std::thread::sleep(Duration::from_millis(ms)),
+            Step::Question(question) => {
+                let response = ipc("question", serde_json::json!({ "question": question }))?;
+                say(&response.to_string());
+            }
+            Step::Ipc { command, payload } => {
+                let response = ipc(&command, payload)?;
+                say(&response.to_string());
+            }
+            Step::Exit(code) => std::process::exit(code),
+        }
+    }
+    Ok(())
+}
+
+fn say(line: &str) {
+    let mut stdout = std::io::stdout().lock();
+    let _ = writeln!(stdout, "{line}");
+    let _ = stdout.flush();
+}
+
+/// One request/response round trip over the socket `maestro` talks to.
+#[cfg(unix)]
+fn ipc(command: &str, payload: serde_json::Value) -> Result<serde_json::Value, String> {
+    let socket = std::env::var("MAESTRO_SOCKET").map_err(|_| "MAESTRO_SOCKET not set")?;
+    let card_id = std::env::var("MAESTRO_CARD_ID").map_err(|_| "MAESTRO_CARD_ID not set")?;
+
+    let mut stream =
+        UnixStream::connect(&socket).map_err(|e| format!("Failed to connect to Maestro socket: {e}"))?;
+    let request = serde_json::json!({ "command": command, "card_id": card_id, "payload": payload });
+    writeln!(stream, "{request}").map_err(|e| format!("Failed to send request: {e}"))?;
+    // Like `maestro question --wait`, a waiting request keeps its write half
+    // open; closing it would tell the server the agent gave up.
+    if !request["payload"]["wait"].as_bool().unwrap_or(false) {
+        stream
+            .shutdown(std::net::Shutdown::Write)
+            .map_err(|e| format!("Failed to shutdown write half: {e}"))?;
+    }
+
+    let mut line = String::new();
+    BufReader::new(stream)
+        .read_line(&mut line)
+        .map_err(|e| format!("Failed to read response: {e}"))?;
+    serde_json::from_str(&line).map_err(|e| format!("Failed to parse response: {e}"))
+}
+
+/// The Maestro socket is a Unix domain socket, so there's nothing to talk to
+/// elsewhere.
+#[cfg(not(unix))]
+fn ipc(_command: &str, _payload: serde_json::Value) -> Result<serde_json::Value, String> {
+    Err("The Maestro socket is only available on Unix".to_string())
+}
@@ -0,0 +1,399 @@
+//! Drives the executor end to end against the mock agent: launch, output
+//! streaming, the lifecycle monitor's status updates, stop, resume and IPC.
+//! The executor's log-file stdio and the IPC socket are Unix-only.
+#![cfg(unix)]
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use maestro_lib::commands::agent::{
+    get_workspace_inner, launch_agent_inner, resume_agent_inner, send_agent_input_inner, stop_agent_inner,
+    AgentWorkspace,
+};
+use maestro_lib::commands::cards::{create_card_inner, CardInput};
+use maestro_lib::commands::config::ConfigState;
+use maestro_lib::commands::history::Actor;
+use maestro_lib::commands::projects::create_project_inner;
+use maestro_lib::commands::questions::{list_questions_inner, resolve_question_inner};
+use maestro_lib::executor::lifecycle::AgentExitEvent;
+use maestro_lib::executor::monitor::is_same_process;
+use maestro_lib::executor::{AgentEvent, AgentRegistry, EventBus, MaestroEvent};
+use maestro_lib::ipc::server::IpcServer;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::broadcast;
+
+const MOCK_AGENT: &str = env!("CARGO_BIN_EXE_mock-agent");
+const TIMEOUT: Duration = Duration::from_secs(10);
+const DRAIN: Duration = Duration::from_millis(500);
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+/// A project with one card, whose only agent profile runs the mock agent
+/// with `scenario.json` from the temp dir.
+struct Harness {
+    dir: tempfile::TempDir,
+    config: Arc<ConfigState>,
+    registry: Arc<AgentRegistry>,
+    bus: Arc<EventBus>,
+    project_id: String,
+    card_id: String,
+    status_id: String,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("config.toml");
+        let config = format!(
+            r#"
+[storage]
+base_path = "{base}"
+
+[defaults]
+agent = "mock"
+
+[agents.mock]
+binary = "{MOCK_AGENT}"
+flags = ["--scenario", "{scenario}"]
+session_id_pattern = "session: (\\S+)"
+"#,
+            base = dir.path().display(),
+            scenario = dir.path().join("scenario.json").display(),
+        );
+        std::fs::write(&config_path, config).unwrap();
+
+        let config = Arc::new(ConfigState::load_from(config_path).unwrap());
+        let project = create_project_inner(&config, "Mock").unwrap();
+        let input = CardInput {
+            title: "Try the mock".to_string(),
+            ..Default::default()
+        };
+        let card = create_card_inner(&config, &project.id, input, &Actor::User).unwrap();
+
+        Self {
+            dir,
+            config,
+            registry: Arc::new(AgentRegistry::new()),
+            bus: Arc::new(EventBus::new()),
+            project_id: project.id,
+            card_id: card.id,
+            status_id: card.status_id,
+        }
+    }
+
+    fn scenario(&self, steps: serde_json::Value) {
+        std::fs::write(self.dir.path().join("scenario.json"), steps.to_string()).unwrap();
+    }
+
+    async fn launch(&self) -> AgentWorkspace {
+        launch_agent_inner(
+            None,
+            Some(Arc::clone(&self.bus)),
+            &self.config,
+            &self.registry,
+            &self.project_id,
+            &self.card_id,
+            &self.status_id,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap()
+    }
+
+    fn workspace(&self, workspace_id: &str) -> AgentWorkspace {
+        get_workspace_inner(&self.config, &self.project_id, workspace_id).unwrap()
+    }
+}
+
+/// Collects a workspace's stdout until it exits. Output is tailed from log
+/// files, so the last lines can arrive after the exit event; those are picked
+/// up until the stream has been quiet for a moment.
+async fn run_to_exit(
+    rx: &mut broadcast::Receiver<AgentEvent>,
+    workspace_id: &str,
+) -> (AgentExitEvent, Vec<String>) {
+    let mut stdout = Vec::new();
+    let mut exit = None;
+    loop {
+        let wait = if exit.is_some() { DRAIN } else { TIMEOUT };
+        let event = match tokio::time::timeout(wait, rx.recv()).await {
+            Ok(event) => event.unwrap(),
+            Err(_) if exit.is_some() => break,
+            Err(_) => panic!("agent did not exit"),
+        };
+        match event {
+            AgentEvent::Output(e) if e.workspace_id == workspace_id && e.stream == "stdout" => stdout.push(e.line),
+            AgentEvent::Exit(e) if e.workspace_id == workspace_id => exit = Some(e),
+            _ => {}
+        }
+    }
+    (exit.unwrap(), stdout)
+}
+
+async fn wait_for_line(rx: &mut broadcast::Receiver<AgentEvent>, workspace_id: &str, line: &str) {
+    loop {
+        match tokio::time::timeout(TIMEOUT, rx.recv()).await.unwrap().unwrap() {
+            AgentEvent::Output(e) if e.workspace_id == workspace_id && e.line == line => return,
+            AgentEvent::Exit(e) if e.workspace_id == workspace_id => panic!("agent exited before printing {line:?}"),
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn test_launch_streams_output_and_completes() {
+    let h = Harness::new();
+    h.scenario(serde_json::json!([{ "stdout": "hello" }, { "stderr": "warming up" }, { "stdout": "done" }]));
+
+    block_on(async {
+        let mut rx = h.bus.subscribe();
+        let workspace = h.launch().await;
+        assert_eq!(workspace.status, "running");
+
+        let (exit, stdout) = run_to_exit(&mut rx, &workspace.id).await;
+        assert_eq!(exit.status, "completed");
+        assert_eq!(exit.exit_code, Some(0));
+        assert_eq!(stdout, vec!["hello", "done"]);
+
+        let workspace = h.workspace(&workspace.id);
+        assert_eq!(workspace.status, "completed");
+        assert!(workspace.completed_at.is_some());
+        assert!(!h.registry.has(&workspace.id));
+    });
+}
+
+#[test]
+fn test_nonzero_exit_marks_workspace_failed() {
+    let h = Harness::new();
+    h.scenario(serde_json::json!([{ "stderr": "boom" }, { "exit": 3 }]));
+
+    block_on(async {
+        let mut rx = h.bus.subscribe();
+        let workspace = h.launch().await;
+
+        let (exit, _) = run_to_exit(&mut rx, &workspace.id).await;
+        assert_eq!(exit.status, "failed");
+        assert_eq!(exit.exit_code, Some(3));
+        assert_eq!(h.workspace(&workspace.id).status, "failed");
+    });
+}
+
+#[test]
+fn test_input_reaches_agent() {
+    let h = Harness::new();
+    h.scenario(serde_json::json!(["echo_line"]));
+
+    block_on(async {
+        let mut rx = h.bus.subscribe();
+        let workspace = h.launch().await;
+        send_agent_input_inner(&h.registry, &workspace.id, "ping").await.unwrap();
+
+        let (exit, stdout) = run_to_exit(&mut rx, &workspace.id).await;
+        assert_eq!(exit.status, "completed");
+        assert_eq!(stdout, vec!["echo: ping"]);
+    });
+}
+
+#[test]
+fn test_stop_kills_running_agent() {
+    let h = Harness::new();
+    h.scenario(serde_json::json!([{ "stdout": "ready" }, { "sleep_ms": 30000 }]));
+
+    block_on(async {
+        let mut rx = h.bus.subscribe();
+        let workspace = h.launch().await;
+        let pid = workspace.pid.unwrap() as u32;
+        wait_for_line(&mut rx, &workspace.id, "ready").await;
+
+        let stopped = stop_agent_inner(&h.config, &h.registry, &h.project_id, &workspace.id)
+            .await
+            .unwrap();
+        assert_eq!(stopped.status, "failed");
+        assert_eq!(stopped.exit_reason.as_deref(), Some("Stopped"));
+
+        let (exit, _) = run_to_exit(&mut rx, &workspace.id).await;
+        assert_eq!(exit.exit_code, None);
+        assert!(!is_same_process(pid, None));
+        // The monitor leaves the state recorded by the stop alone.
+        assert_eq!(h.workspace(&workspace.id).exit_reason.as_deref(), Some("Stopped"));
+    });
+}
+
+#[test]
+fn test_resume_continues_recorded_session() {
+    let h = Harness::new();
+    h.scenario(serde_json::json!([{ "stdout": "session: abc123" }]));
+
+    block_on(async {
+        let mut rx = h.bus.subscribe();
+        let first = h.launch().await;
+        run_to_exit(&mut rx, &first.id).await;
+
+        let deadline = tokio::time::Instant::now() + TIMEOUT;
+        while h.workspace(&first.id).session_id.is_none() {
+            assert!(tokio::time::Instant::now() < deadline, "session id was never recorded");
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        h.scenario(serde_json::json!([{ "stdout": "picking up where I left off" }]));
+        let resumed = resume_agent_inner(
+            None,
+            Some(Arc::clone(&h.bus)),
+            &h.config,
+            &h.registry,
+            &h.project_id,
+            &first.id,
+            &h.card_id,
+        )
+        .await
+        .unwrap();
+        assert_ne!(resumed.id, first.id);
+        assert_eq!(resumed.session_id.as_deref(), Some("abc123"));
+
+        let (exit, stdout) = run_to_exit(&mut rx, &resumed.id).await;
+        assert_eq!(exit.status, "completed");
+        assert_eq!(stdout, vec!["resumed abc123", "picking up where I left off"]);
+    });
+}
+
+#[test]
+fn test_agent_question_round_trips_over_ipc() {
+    let h = Harness::new();
+    h.scenario(serde_json::json!([{ "question": "Which database should I use?" }]));
+
+    block_on(async {
+        let ipc = IpcServer::new();
+        ipc.start(
+            None,
+            Some(Arc::clone(&h.bus)),
+            Arc::clone(&h.config),
+            Arc::clone(&h.registry),
+            h.project_id.clone(),
+        )
+        .await
+        .unwrap();
+
+        let mut rx = h.bus.subscribe();
+        let workspace = h.launch().await;
+        let (exit, stdout) = run_to_exit(&mut rx, &workspace.id).await;
+        ipc.stop_project(&h.project_id).await;
+
+        assert_eq!(exit.status, "completed");
+        let response: serde_json::Value = serde_json::from_str(&stdout[0]).unwrap();
+        assert_eq!(response["ok"], true);
+
+        let questions = list_questions_inner(&h.config, &h.project_id, &h.card_id).unwrap();
+        assert_eq!(questions.len(), 1);
+        assert_eq!(questions[0].question, "Which database should I use?");
+        assert_eq!(questions[0].source, "agent");
+        assert_eq!(response["data"]["id"], questions[0].id.as_str());
+    });
+}
+
+#[test]
+fn test_waiting_question_pauses_agent_until_answered() {
+    let h = Harness::new();
+    h.scenario(serde_json::json!([
+        { "ipc": { "command": "question", "payload": { "question": "Postgres or SQLite?", "wait": true } } },
+        { "ipc": { "command": "question", "payload": { "question": "Anything else?", "wait": true, "timeout_secs": 0 } } }
+    ]));
+
+    block_on(async {
+        let ipc = IpcServer::new();
+        ipc.start(
+            None,
+            Some(Arc::clone(&h.bus)),
+            Arc::clone(&h.config),
+            Arc::clone(&h.registry),
+            h.project_id.clone(),
+        )
+        .await
+        .unwrap();
+
+        let mut rx = h.bus.subscribe();
+        let workspace = h.launch().await;
+
+        let question = tokio::time::timeout(TIMEOUT, async {
+            loop {
+                let questions = list_questions_inner(&h.config, &h.project_id, &h.card_id).unwrap();
+                if let (Some(question), "paused") = (questions.first(), h.workspace(&workspace.id).status.as_str()) {
+                    break question.clone();
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("agent did not pause on its question");
+
+        resolve_question_inner(&h.config, &h.project_id, &question.id, Some("SQLite".to_string()), "user").unwrap();
+        h.bus.emit_maestro(MaestroEvent::QuestionsChanged {
+            project_id: h.project_id.clone(),
+        });
+        let (exit, stdout) = run_to_exit(&mut rx, &workspace.id).await;
+        ipc.stop_project(&h.project_id).await;
+
+        assert_eq!(exit.status, "completed");
+        let answered: serde_json::Value = serde_json::from_str(&stdout[0]).unwrap();
+        assert_eq!(answered["data"]["resolution"], "SQLite");
+        let timed_out: serde_json::Value = serde_json::from_str(&stdout[1]).unwrap();
+        assert_eq!(timed_out["ok"], false);
+        assert!(timed_out["error"].as_str().unwrap().starts_with("Timed out"));
+    });
+}
+
+#[test]
+fn test_waiting_question_resumes_agent_when_client_disconnects() {
+    let h = Harness::new();
+    h.scenario(serde_json::json!([{ "sleep_ms": 10000 }]));
+
+    block_on(async {
+        let ipc = IpcServer::new();
+        let socket = ipc
+            .start(
+                None,
+                Some(Arc::clone(&h.bus)),
+                Arc::clone(&h.config),
+                Arc::clone(&h.registry),
+                h.project_id.clone(),
+            )
+            .await
+            .unwrap();
+        let workspace = h.launch().await;
+        let status_becomes = |status: &'static str| {
+            let workspace_id = workspace.id.clone();
+            let h = &h;
+            async move {
+                tokio::time::timeout(TIMEOUT, async {
+                    while h.workspace(&workspace_id).status != status {
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                    }
+                })
+                .await
+                .unwrap_or_else(|_| panic!("workspace never became {status}"));
+            }
+        };
+
+        let mut client = tokio::net::UnixStream::connect(&socket).await.unwrap();
+        let request = serde_json::json!({
+            "command": "question",
+            "card_id": h.card_id,
+            "payload": { "question": "Still there?", "wait": true },
+        });
+        client.write_all(format!("{request}\n").as_bytes()).await.unwrap();
+        status_becomes("paused").await;
+
+        drop(client);
+        status_becomes("running").await;
+
+        stop_agent_inner(&h.config, &h.registry, &h.project_id, &workspace.id).await.unwrap();
+        ipc.stop_project(&h.project_id).await;
+    });
+}
@@ -0,0 +1,40 @@
+//! Runs Maestro without the desktop window: the board is served over HTTP
+//! and agents keep running on this machine.
+//!
+//! ```text
+//! maestro-server [--config <path>] [--static-dir <path>]
+//! ```
+
+use std::path::PathBuf;
+
+use maestro_lib::headless::{run_server, ServerOptions};
+
+#[tokio::main]
+async fn main() {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("maestro-server: {e}");
+            eprintln!("usage: maestro-server [--config <path>] [--static-dir <path>]");
+            std::process::exit(2);
+        }
+    };
+    if let Err(e) = run_server(options).await {
+        eprintln!("maestro-server: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn parse_args() -> Result<ServerOptions, String> {
+    let mut options = ServerOptions::default();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().map(PathBuf::from).ok_or(format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--config" => options.config_path = Some(value()?),
+            "--static-dir" => options.static_dir = Some(value()?),
+            _ => return Err(format!("unknown argument {arg}")),
+        }
+    }
+    Ok(options)
+}
@@ -1,19 +1,46 @@
+#[cfg(unix)]
+use std::io::SeekFrom;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, State};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::process::Child;
+use tokio::sync::broadcast;
+use tokio::time::Duration;
 
 use crate::commands::config::ConfigState;
+use crate::config::global::{ConcurrencyConfig, GlobalConfig};
+use crate::config::resolution::resolve_agent_config;
+use crate::commands::dependencies::{unblocked_dependents, unfinished_blockers};
 use crate::commands::projects::open_project_db;
-use crate::executor::context::{assemble_context, CardInfo};
-use crate::executor::lifecycle::{start_lifecycle_monitor_inner, stop_agent_process};
-use crate::executor::spawn::spawn_agent;
-use crate::executor::stream::{start_stderr_streaming_inner, start_stdin_forwarding, start_stdout_streaming_inner};
-use crate::executor::{AgentHandle, AgentRegistry, EventBus, MaestroEvent};
+use crate::commands::statuses::parse_auto_run;
+use crate::executor::context::{assemble_context, resolve_adapter, AgentContext, CardInfo};
+use crate::executor::lifecycle::{start_lifecycle_monitor_inner, stop_agent_process, MonitoredAgent};
+#[cfg(unix)]
+use crate::executor::logs::tail_log;
+use crate::executor::logs::WorkspaceLogs;
+use crate::executor::monitor::process_start_time;
+use crate::executor::parser::parser_for;
+#[cfg(unix)]
+use crate::executor::process_group::signal_group;
+#[cfg(unix)]
+use crate::executor::pty::{start_pty_input, start_pty_streaming, PtyMaster, PtyPair};
+use crate::executor::pty::PtyControl;
+#[cfg(unix)]
+use crate::executor::spawn::spawn_agent_pty;
+use crate::executor::spawn::{spawn_agent, SpawnedAgent};
+use crate::executor::transcript::start_transcript;
+use crate::executor::stream::{
+    start_stderr_streaming_inner, start_stdin_forwarding, start_stdout_streaming_inner, SessionCapture,
+};
+use crate::executor::{AgentEvent, AgentHandle, AgentRegistry, EventBus, MaestroEvent, SlotReservation};
 use crate::fs::worktrees as worktree_fs;
 use crate::ipc::server::IpcServer;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct AgentWorkspace {
     pub id: String,
     pub card_id: String,
@@ -26,6 +53,7 @@ pub struct AgentWorkspace {
     pub review_count: i32,
     pub attached_at: String,
     pub completed_at: Option<String>,
+    pub exit_reason: Option<String>,
 }
 
 fn row_to_workspace(row: &rusqlite::Row) -> Result<AgentWorkspace, rusqlite::Error> {
@@ -41,12 +69,13 @@ fn row_to_workspace(row: &rusqlite::Row) -> Result<AgentWorkspace, rusqlite::Err
         review_count: row.get(8)?,
         attached_at: row.get(9)?,
         completed_at: row.get(10)?,
+        exit_reason: row.get(11)?,
     })
 }
 
 const WORKSPACE_SELECT: &str = "\
     SELECT id, card_id, agent_type, status, session_id, pid, worktree_path, \
-           branch_name, review_count, attached_at, completed_at \
+           branch_name, review_count, attached_at, completed_at, exit_reason \
     FROM agent_workspaces";
 
 fn collect_artifact_contents(artifacts_dir: &std::path::Path) -> Vec<(String, String)> {
@@ -72,18 +101,98 @@ fn collect_artifact_contents(artifacts_dir: &std::path::Path) -> Vec<(String, St
     contents
 }
 
-pub async fn launch_agent_inner(
-    app: Option<AppHandle>,
-    event_bus: Option<Arc<EventBus>>,
+/// Everything needed to spawn an agent for a card, resolved from the card,
+/// its project and status at the time of launch.
+struct LaunchPlan {
+    base_path: PathBuf,
+    project_id: String,
+    card_id: String,
+    agent_ctx: AgentContext,
+    worktree_path: Option<String>,
+}
+
+struct AgentProcess {
+    child: Child,
+    pid: u32,
+    start_time: Option<u64>,
+    io: AgentIo,
+    session: SessionCapture,
+}
+
+impl AgentProcess {
+    /// Kills an agent whose launch failed after it was spawned, so it doesn't
+    /// keep running with no workspace row or registry entry.
+    fn abandon(mut self) {
+        kill_spawned(&mut self.child, self.pid);
+    }
+}
+
+fn kill_spawned(child: &mut Child, pid: u32) {
+    #[cfg(unix)]
+    signal_group(pid, libc::SIGKILL);
+    #[cfg(not(unix))]
+    let _ = pid;
+    let _ = child.start_kill();
+}
+
+enum AgentIo {
+    Streams {
+        stdout: Box<dyn AsyncRead + Unpin + Send>,
+        stderr: Box<dyn AsyncRead + Unpin + Send>,
+        stdin: Box<dyn AsyncWrite + Unpin + Send>,
+    },
+    #[cfg(unix)]
+    Pty(PtyMaster),
+}
+
+/// Points the project's agent config for a status group at `agent`, so a
+/// status's auto-run profile takes precedence over every other level. The
+/// model and instructions that would otherwise apply to the group are kept.
+fn override_status_agent(
+    global: &GlobalConfig,
+    project_config: &mut serde_json::Value,
+    group: &str,
+    agent: &str,
+) {
+    let resolved = resolve_agent_config(global, project_config, group);
+    if !project_config.is_object() {
+        *project_config = serde_json::json!({});
+    }
+    let status = project_config
+        .as_object_mut()
+        .unwrap()
+        .entry("status")
+        .or_insert_with(|| serde_json::json!({}));
+    if !status.is_object() {
+        *status = serde_json::json!({});
+    }
+    let group_config = status
+        .as_object_mut()
+        .unwrap()
+        .entry(group.to_lowercase())
+        .or_insert_with(|| serde_json::json!({}));
+    if !group_config.is_object() {
+        *group_config = serde_json::json!({});
+    }
+    let group_config = group_config.as_object_mut().unwrap();
+    group_config.insert("agent".to_string(), serde_json::Value::String(agent.to_string()));
+    if let Some(model) = resolved.model {
+        group_config.insert("model".to_string(), serde_json::Value::String(model));
+    }
+    if let Some(instructions) = resolved.instructions {
+        group_config.insert("instructions".to_string(), serde_json::Value::String(instructions));
+    }
+}
+
+fn prepare_launch(
     config: &ConfigState,
-    registry: &Arc<AgentRegistry>,
     project_id: &str,
     card_id: &str,
     status_id: &str,
+    workspace_id: &str,
     worktree_path: Option<String>,
-    branch_name: Option<String>,
     repo_path: Option<String>,
-) -> Result<AgentWorkspace, String> {
+) -> Result<LaunchPlan, String> {
     let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
     let db = open_project_db(&base_path, project_id)?;
 
@@ -119,17 +228,24 @@ pub async fn launch_agent_inner(
                 )
                 .map_err(|e| format!("Project not found: {e}"))?;
 
-            let project_agent_config: serde_json::Value =
+            let mut project_agent_config: serde_json::Value =
                 serde_json::from_str(&agent_config_json).unwrap_or_default();
 
-            let (group, prompts_json): (String, String) = conn
+            let (group, prompts_json, auto_run_json): (String, String, Option<String>) = conn
                 .query_row(
-                    "SELECT \"group\", status_prompts FROM statuses WHERE id = ?1",
+                    "SELECT \"group\", status_prompts, auto_run FROM statuses WHERE id = ?1",
                     rusqlite::params![status_id],
-                    |row| Ok((row.get(0)?, row.get(1)?)),
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
                 )
                 .map_err(|e| format!("Status not found: {e}"))?;
 
+            if let Some(agent) = parse_auto_run(auto_run_json).and_then(|p| p.agent) {
+                config.with_config(|c| {
+                    override_status_agent(c, &mut project_agent_config, &group, &agent);
+                    Ok(())
+                })?;
+            }
+
             let status_prompts: Vec<String> = serde_json::from_str(&prompts_json).unwrap_or_default();
 
             Ok((title, description, parent_title, parent_description, project_agent_config, group, status_prompts))
@@ -150,32 +266,37 @@ pub async fn launch_agent_inner(
         None
     };
 
-    let working_dir = if let Some(ref rp) = repo_path {
-        std::path::PathBuf::from(rp)
-    } else if let Some(ref wt) = worktree_path {
-        std::path::PathBuf::from(wt)
-    } else {
-        base_path
-            .join("projects")
-            .join(project_id)
-            .join("artifacts")
-            .join(card_id)
-    };
-
-    let db_worktree_path = if let (Some(ref rp), Some(ref wt_name)) = (&repo_path, &worktree_name) {
-        Some(worktree_fs::claude_worktree_path(rp, wt_name).to_string_lossy().to_string())
-    } else {
-        worktree_path.clone()
-    };
-
-    let working_dir_str = working_dir.to_string_lossy().to_string();
-
+    let adapter = config.with_config(|c| resolve_adapter(c, &project_agent_config, &status_group))?;
     let artifacts_dir = base_path
         .join("projects")
         .join(project_id)
         .join("artifacts")
         .join(card_id);
 
+    // Agents that can't create their own worktree are started inside one
+    // created here, next to the worktrees made for auto-run.
+    let (working_dir, db_worktree_path) = match (&repo_path, &worktree_name) {
+        (Some(rp), Some(wt_name)) => match adapter.worktree_path(rp, wt_name) {
+            Some(path) => (std::path::PathBuf::from(rp), Some(path.to_string_lossy().to_string())),
+            None => {
+                let slug = worktree_fs::branch_slug_from_title(&card_title);
+                let path = worktree_fs::worktree_path(&base_path, project_id, card_id, &slug);
+                if !worktree_fs::worktree_exists(&path) {
+                    let branch = worktree_fs::generate_branch_name(card_id, &card_title);
+                    worktree_fs::create_worktree(rp, &path, &branch)?;
+                }
+                let path_str = path.to_string_lossy().to_string();
+                (path, Some(path_str))
+            }
+        },
+        _ => match worktree_path {
+            Some(ref wt) => (std::path::PathBuf::from(wt), worktree_path.clone()),
+            None => (artifacts_dir.clone(), None),
+        },
+    };
+
+    let working_dir_str = working_dir.to_string_lossy().to_string();
+
     let artifact_contents = if is_implementation || worktree_path.is_some() {
         collect_artifact_contents(&artifacts_dir)
     } else {
@@ -200,48 +321,168 @@ pub async fn launch_agent_inner(
             socket_path_str.as_deref(),
             worktree_name.as_deref(),
             &status_prompts,
+            None,
+            &WorkspaceLogs::new(&base_path, project_id, workspace_id).dir,
         )
     })?;
 
-    let mut spawned = spawn_agent(&agent_ctx)?;
+    Ok(LaunchPlan {
+        base_path,
+        project_id: project_id.to_string(),
+        card_id: card_id.to_string(),
+        agent_ctx,
+        worktree_path: db_worktree_path,
+    })
+}
 
-    let stdout = spawned.child.stdout.take()
-        .ok_or_else(|| "Failed to capture stdout".to_string())?;
-    let stderr = spawned.child.stderr.take()
-        .ok_or_else(|| "Failed to capture stderr".to_string())?;
-    let stdin = spawned.child.stdin.take()
-        .ok_or_else(|| "Failed to capture stdin".to_string())?;
+/// Spawns the agent for `plan`. Everything that can fail is set up first;
+/// past the spawn, a failure kills the agent again.
+fn spawn_plan(plan: &LaunchPlan, workspace_id: &str) -> Result<AgentProcess, String> {
+    let session = SessionCapture::new(
+        plan.base_path.clone(),
+        &plan.project_id,
+        plan.agent_ctx.session_id_pattern.as_deref(),
+    )?;
+
+    let (spawned, io) = spawn_io(plan, workspace_id)?;
+
+    Ok(AgentProcess {
+        start_time: process_start_time(spawned.pid),
+        child: spawned.child,
+        pid: spawned.pid,
+        io,
+        session,
+    })
+}
 
-    let workspace_id = uuid::Uuid::new_v4().to_string();
-    let now = chrono::Utc::now().to_rfc3339();
-    let pid = spawned.pid;
+/// Spawns the agent on a pseudo-terminal, or with its stdio routed through
+/// log files so it outlives the app.
+#[cfg(unix)]
+fn spawn_io(plan: &LaunchPlan, workspace_id: &str) -> Result<(SpawnedAgent, AgentIo), String> {
+    if plan.agent_ctx.pty {
+        let pty = PtyPair::open()?;
+        let mut spawned = spawn_agent_pty(&plan.agent_ctx, &pty)?;
+        let master = pty
+            .into_master()
+            .inspect_err(|_| kill_spawned(&mut spawned.child, spawned.pid))?;
+        return Ok((spawned, AgentIo::Pty(master)));
+    }
 
-    let workspace = db.with_conn(|conn| {
-        conn.execute(
-            "INSERT INTO agent_workspaces (id, card_id, agent_type, status, pid, worktree_path, branch_name, attached_at) \
-             VALUES (?1, ?2, ?3, 'running', ?4, ?5, ?6, ?7)",
-            rusqlite::params![workspace_id, card_id, agent_ctx.binary, pid as i64, db_worktree_path, branch_name, now],
-        )
-        .map_err(|e| format!("Failed to create workspace: {e}"))?;
+    let logs = WorkspaceLogs::new(&plan.base_path, &plan.project_id, workspace_id);
+    let stdio = logs.create()?;
+    let stdin = logs.open_sender()?;
+    let spawned = spawn_agent(&plan.agent_ctx, stdio)?;
+    let start_time = process_start_time(spawned.pid);
+    let io = AgentIo::Streams {
+        stdout: Box::new(tail_log(logs.stdout.clone(), spawned.pid, start_time, SeekFrom::Start(0))),
+        stderr: Box::new(tail_log(logs.stderr.clone(), spawned.pid, start_time, SeekFrom::Start(0))),
+        stdin: Box::new(stdin),
+    };
+    Ok((spawned, io))
+}
 
-        conn.query_row(
-            &format!("{WORKSPACE_SELECT} WHERE id = ?1"),
-            rusqlite::params![workspace_id],
-            row_to_workspace,
-        )
-        .map_err(|e| format!("Failed to read workspace: {e}"))
-    })?;
+/// Spawns the agent on plain pipes. Without FIFOs or pseudo-terminals, it
+/// can't be reattached after a restart and `pty` profiles get no terminal.
+#[cfg(not(unix))]
+fn spawn_io(plan: &LaunchPlan, _workspace_id: &str) -> Result<(SpawnedAgent, AgentIo), String> {
+    let mut spawned = spawn_agent(
+        &plan.agent_ctx,
+        (std::process::Stdio::piped(), std::process::Stdio::piped(), std::process::Stdio::piped()),
+    )?;
+    let (Some(stdin), Some(stdout), Some(stderr)) =
+        (spawned.child.stdin.take(), spawned.child.stdout.take(), spawned.child.stderr.take())
+    else {
+        kill_spawned(&mut spawned.child, spawned.pid);
+        return Err("Failed to capture agent stdio".to_string());
+    };
+    let io = AgentIo::Streams {
+        stdout: Box::new(stdout),
+        stderr: Box::new(stderr),
+        stdin: Box::new(stdin),
+    };
+    Ok((spawned, io))
+}
 
-    start_stdout_streaming_inner(app.clone(), event_bus.clone(), workspace_id.clone(), stdout);
-    start_stderr_streaming_inner(app.clone(), event_bus.clone(), workspace_id.clone(), stderr);
+/// Wires a spawned agent's output, input and exit handling up to the
+/// workspace row, which must already exist.
+fn attach_agent_process(
+    app: Option<AppHandle>,
+    event_bus: Option<Arc<EventBus>>,
+    registry: &Arc<AgentRegistry>,
+    plan: &LaunchPlan,
+    workspace_id: &str,
+    process: AgentProcess,
+) {
+    let session = process.session;
+    let transcript = match start_transcript(
+        &plan.base_path,
+        &plan.project_id,
+        &plan.card_id,
+        &plan.agent_ctx.binary,
+        event_bus.clone(),
+    ) {
+        Ok(sink) => Some(sink),
+        Err(e) => {
+            eprintln!("[agent] Failed to start transcript for {workspace_id}: {e}");
+            None
+        }
+    };
 
     let (stdin_tx, stdin_rx) = tokio::sync::mpsc::channel::<String>(64);
-    start_stdin_forwarding(stdin, stdin_rx);
+    let prompt = plan.agent_ctx.stdin_prompt.then(|| plan.agent_ctx.system_prompt.clone());
+    let pty = match process.io {
+        AgentIo::Streams { stdout, stderr, stdin } => {
+            start_stdout_streaming_inner(
+                app.clone(),
+                event_bus.clone(),
+                workspace_id.to_string(),
+                stdout,
+                Some(session.clone()),
+                Some(parser_for(plan.agent_ctx.output_format)),
+                transcript.clone(),
+            );
+            start_stderr_streaming_inner(
+                app.clone(),
+                event_bus.clone(),
+                workspace_id.to_string(),
+                stderr,
+                Some(session),
+                transcript.clone(),
+            );
+            start_stdin_forwarding(stdin, stdin_rx, prompt, transcript);
+            None
+        }
+        #[cfg(unix)]
+        AgentIo::Pty(master) => {
+            // The plain-text copy of the terminal gets the same session-id
+            // capture, parsing and transcript as piped stdout.
+            let (text_tx, text_rx) = tokio::io::duplex(64 * 1024);
+            start_pty_streaming(app.clone(), event_bus.clone(), workspace_id.to_string(), master.clone(), text_tx);
+            start_stdout_streaming_inner(
+                app.clone(),
+                event_bus.clone(),
+                workspace_id.to_string(),
+                text_rx,
+                Some(session),
+                Some(parser_for(plan.agent_ctx.output_format)),
+                transcript.clone(),
+            );
+            let (input_tx, input_rx) = tokio::sync::mpsc::channel::<Vec<u8>>(256);
+            // Typed as raw input, which keeps it out of the transcript.
+            if let Some(prompt) = prompt {
+                let _ = input_tx.try_send(format!("{prompt}\r").into_bytes());
+            }
+            start_pty_input(master.clone(), stdin_rx, input_rx, transcript);
+            Some(PtyControl { master, input_tx })
+        }
+    };
 
     let handle = AgentHandle {
-        workspace_id: workspace_id.clone(),
+        workspace_id: workspace_id.to_string(),
+        project_id: plan.project_id.clone(),
         stdin_tx,
-        pid,
+        pid: process.pid,
+        pty,
     };
     registry.insert(handle);
 
@@ -249,11 +490,111 @@ pub async fn launch_agent_inner(
         app,
         event_bus,
         Arc::clone(registry),
-        spawned.child,
-        workspace_id,
-        project_id.to_string(),
-        base_path,
+        process.child,
+        MonitoredAgent {
+            workspace_id: workspace_id.to_string(),
+            project_id: plan.project_id.clone(),
+            base_path: plan.base_path.clone(),
+            limits: plan.agent_ctx.limits,
+        },
     );
+}
+
+/// Claims a concurrency slot for a launch that is due now. Returns `None`
+/// when the limits are reached or a queued launch could take the free slot,
+/// in which case the launch has to queue behind it.
+fn reserve_slot<'a>(
+    config: &ConfigState,
+    registry: &'a AgentRegistry,
+    base_path: &std::path::Path,
+    project_id: &str,
+    workspace_id: &str,
+) -> Result<Option<SlotReservation<'a>>, String> {
+    let limits = config.with_config(|c| Ok(c.concurrency.clone()))?;
+    if next_queued_launch(base_path, &limits, registry).is_some() {
+        return Ok(None);
+    }
+    Ok(registry.reserve(workspace_id, project_id, &limits))
+}
+
+pub async fn launch_agent_inner(
+    app: Option<AppHandle>,
+    event_bus: Option<Arc<EventBus>>,
+    config: &ConfigState,
+    registry: &Arc<AgentRegistry>,
+    project_id: &str,
+    card_id: &str,
+    status_id: &str,
+    worktree_path: Option<String>,
+    branch_name: Option<String>,
+    repo_path: Option<String>,
+) -> Result<AgentWorkspace, String> {
+    // Checked before planning, which may already create the worktree.
+    let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
+    let db = open_project_db(&base_path, project_id)?;
+    let blockers = db.with_conn(|conn| unfinished_blockers(conn, card_id))?;
+    if !blockers.is_empty() {
+        return Err(format!("Card is blocked by unfinished cards: {}", blockers.join(", ")));
+    }
+    let workspace_id = uuid::Uuid::new_v4().to_string();
+    let plan = prepare_launch(
+        config,
+        project_id,
+        card_id,
+        status_id,
+        &workspace_id,
+        worktree_path.clone(),
+        repo_path.clone(),
+    )?;
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let Some(_slot) = reserve_slot(config, registry, &base_path, project_id, &workspace_id)? else {
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO agent_workspaces (id, card_id, agent_type, status, worktree_path, branch_name, attached_at) \
+                 VALUES (?1, ?2, ?3, 'queued', ?4, ?5, ?6)",
+                rusqlite::params![workspace_id, card_id, plan.agent_ctx.binary, plan.worktree_path, branch_name, now],
+            )
+            .map_err(|e| format!("Failed to create workspace: {e}"))?;
+
+            conn.execute(
+                "INSERT INTO launch_queue (workspace_id, card_id, status_id, worktree_path, branch_name, repo_path, enqueued_at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                rusqlite::params![workspace_id, card_id, status_id, worktree_path, branch_name, repo_path, now],
+            )
+            .map_err(|e| format!("Failed to queue launch: {e}"))
+        })?;
+        start_queued_launches_inner(app, event_bus, config, registry)?;
+        return get_workspace_inner(config, project_id, &workspace_id);
+    };
+
+    let process = spawn_plan(&plan, &workspace_id)?;
+    let pid = process.pid;
+
+    let workspace = db.with_conn(|conn| {
+        conn.execute(
+            "INSERT INTO agent_workspaces (id, card_id, agent_type, status, pid, pid_start_time, worktree_path, branch_name, attached_at) \
+             VALUES (?1, ?2, ?3, 'running', ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![workspace_id, card_id, plan.agent_ctx.binary, pid as i64, process.start_time.map(|t| t as i64), plan.worktree_path, branch_name, now],
+        )
+        .map_err(|e| format!("Failed to create workspace: {e}"))?;
+
+        conn.query_row(
+            &format!("{WORKSPACE_SELECT} WHERE id = ?1"),
+            rusqlite::params![workspace_id],
+            row_to_workspace,
+        )
+        .map_err(|e| format!("Failed to read workspace: {e}"))
+    });
+    let workspace = match workspace {
+        Ok(workspace) => workspace,
+        Err(e) => {
+            process.abandon();
+            return Err(e);
+        }
+    };
+
+    attach_agent_process(app, event_bus, registry, &plan, &workspace_id, process);
 
     Ok(workspace)
 }
@@ -261,7 +602,7 @@ pub async fn launch_agent_inner(
 #[tauri::command]
 pub async fn launch_agent(
     app: AppHandle,
-    config: State<'_, ConfigState>,
+    config: State<'_, Arc<ConfigState>>,
     event_bus: State<'_, Arc<EventBus>>,
     registry: State<'_, Arc<AgentRegistry>>,
     project_id: String,
@@ -289,6 +630,153 @@ pub async fn launch_agent(
     Ok(result)
 }
 
+/// Applies the auto-run policy of the status a card is currently in.
+/// Returns `None` when the status has no policy or the card already has an
+/// agent running or queued.
+pub async fn auto_run_card_inner(
+    app: Option<AppHandle>,
+    event_bus: Option<Arc<EventBus>>,
+    config: &ConfigState,
+    registry: &Arc<AgentRegistry>,
+    project_id: &str,
+    card_id: &str,
+) -> Result<Option<AgentWorkspace>, String> {
+    let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
+    let db = open_project_db(&base_path, project_id)?;
+
+    let plan = db.with_conn(|conn| {
+        let (status_id, title, auto_run_json): (String, String, Option<String>) = conn
+            .query_row(
+                "SELECT c.status_id, c.title, s.auto_run FROM cards c \
+                 JOIN statuses s ON s.id = c.status_id \
+                 WHERE c.id = ?1 AND c.project_id = ?2",
+                rusqlite::params![card_id, project_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .map_err(|e| format!("Card not found: {e}"))?;
+
+        let Some(policy) = parse_auto_run(auto_run_json) else {
+            return Ok(None);
+        };
+
+        let active: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM agent_workspaces WHERE card_id = ?1 AND status IN ('queued', 'running', 'paused')",
+                rusqlite::params![card_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to check running agents: {e}"))?;
+        // Blocked cards wait; finishing their last blocker auto-runs them.
+        if active || !unfinished_blockers(conn, card_id)?.is_empty() {
+            return Ok(None);
+        }
+
+        let directory: Option<String> = match (&policy.directory_id, policy.create_worktree) {
+            (Some(directory_id), _) => Some(
+                conn.query_row(
+                    "SELECT path FROM linked_directories WHERE id = ?1 AND project_id = ?2",
+                    rusqlite::params![directory_id, project_id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| format!("Linked directory not found: {e}"))?,
+            ),
+            (None, true) => Some(
+                conn.query_row(
+                    "SELECT path FROM linked_directories WHERE project_id = ?1 AND is_repo = 1 \
+                     ORDER BY created_at LIMIT 1",
+                    rusqlite::params![project_id],
+                    |row| row.get(0),
+                )
+                .map_err(|_| "Auto-run needs a linked repository to create a worktree".to_string())?,
+            ),
+            (None, false) => None,
+        };
+
+        Ok(Some((status_id, title, policy.create_worktree, directory)))
+    })?;
+
+    let Some((status_id, title, create_worktree, directory)) = plan else {
+        return Ok(None);
+    };
+
+    let (worktree_path, branch_name, repo_path) = if create_worktree {
+        (None, Some(worktree_fs::generate_branch_name(card_id, &title)), directory)
+    } else {
+        (directory, None, None)
+    };
+
+    launch_agent_inner(
+        app,
+        event_bus,
+        config,
+        registry,
+        project_id,
+        card_id,
+        &status_id,
+        worktree_path,
+        branch_name,
+        repo_path,
+    )
+    .await
+    .map(Some)
+}
+
+/// Runs a card's auto-run policy after it changed status, and those of any
+/// cards it unblocked by completing.
+pub async fn auto_run_card(
+    app: Option<AppHandle>,
+    event_bus: Option<Arc<EventBus>>,
+    config: &ConfigState,
+    registry: &Arc<AgentRegistry>,
+    project_id: &str,
+    card_id: &str,
+) {
+    let mut card_ids = vec![card_id.to_string()];
+    match dependents_ready_to_run(config, project_id, card_id) {
+        Ok(dependents) => card_ids.extend(dependents),
+        Err(e) => eprintln!("[auto-run] Card {card_id}: {e}"),
+    }
+
+    for card_id in &card_ids {
+        match auto_run_card_inner(app.clone(), event_bus.clone(), config, registry, project_id, card_id).await {
+            Ok(Some(_)) => {
+                if let Some(bus) = &event_bus {
+                    bus.emit_maestro(MaestroEvent::WorkspacesChanged {
+                        project_id: project_id.to_string(),
+                    });
+                }
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("[auto-run] Card {card_id}: {e}"),
+        }
+    }
+}
+
+/// Cards that were waiting on `card_id` and can run now that it's done.
+fn dependents_ready_to_run(
+    config: &ConfigState,
+    project_id: &str,
+    card_id: &str,
+) -> Result<Vec<String>, String> {
+    let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
+    let db = open_project_db(&base_path, project_id)?;
+    db.with_conn(|conn| unblocked_dependents(conn, card_id))
+}
+
+/// Runs [`auto_run_card`] in the background, for callers that aren't async.
+pub fn spawn_auto_run(
+    app: Option<AppHandle>,
+    event_bus: Option<Arc<EventBus>>,
+    config: Arc<ConfigState>,
+    registry: Arc<AgentRegistry>,
+    project_id: String,
+    card_id: String,
+) {
+    tauri::async_runtime::spawn(async move {
+        auto_run_card(app, event_bus, &config, &registry, &project_id, &card_id).await;
+    });
+}
+
 pub async fn send_agent_input_inner(
     registry: &Arc<AgentRegistry>,
     workspace_id: &str,
@@ -312,26 +800,97 @@ pub async fn send_agent_input(
     send_agent_input_inner(&registry, &workspace_id, &text).await
 }
 
+fn agent_pty(registry: &AgentRegistry, workspace_id: &str) -> Result<PtyControl, String> {
+    if !registry.has(workspace_id) {
+        return Err(format!("No running agent for workspace {workspace_id}"));
+    }
+    registry
+        .get_pty(workspace_id)
+        .ok_or_else(|| format!("Agent for workspace {workspace_id} is not running on a terminal"))
+}
+
+/// Sends raw bytes (keystrokes, escape sequences) to a PTY agent.
+pub async fn write_agent_terminal_inner(
+    registry: &Arc<AgentRegistry>,
+    workspace_id: &str,
+    data: Vec<u8>,
+) -> Result<(), String> {
+    agent_pty(registry, workspace_id)?
+        .input_tx
+        .send(data)
+        .await
+        .map_err(|e| format!("Failed to send input: {e}"))
+}
+
+#[tauri::command]
+pub async fn write_agent_terminal(
+    registry: State<'_, Arc<AgentRegistry>>,
+    workspace_id: String,
+    data: Vec<u8>,
+) -> Result<(), String> {
+    write_agent_terminal_inner(&registry, &workspace_id, data).await
+}
+
+pub fn resize_agent_terminal_inner(
+    registry: &Arc<AgentRegistry>,
+    workspace_id: &str,
+    cols: u16,
+    rows: u16,
+) -> Result<(), String> {
+    agent_pty(registry, workspace_id)?.master.resize(cols, rows)
+}
+
+#[tauri::command]
+pub fn resize_agent_terminal(
+    registry: State<'_, Arc<AgentRegistry>>,
+    workspace_id: String,
+    cols: u16,
+    rows: u16,
+) -> Result<(), String> {
+    resize_agent_terminal_inner(&registry, &workspace_id, cols, rows)
+}
+
 pub async fn stop_agent_inner(
     config: &ConfigState,
     registry: &Arc<AgentRegistry>,
     project_id: &str,
     workspace_id: &str,
 ) -> Result<AgentWorkspace, String> {
-    let handle = registry
-        .remove(workspace_id)
-        .ok_or_else(|| format!("No running agent for workspace {workspace_id}"))?;
-
-    stop_agent_process(handle.pid).await?;
-
+    if registry.project_of(workspace_id).is_some_and(|owner| owner != project_id) {
+        return Err(format!("No running agent for workspace {workspace_id} in project {project_id}"));
+    }
     let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
     let db = open_project_db(&base_path, project_id)?;
 
+    let reason = match registry.remove(workspace_id) {
+        Some(handle) => {
+            let report = stop_agent_process(handle.pid).await?;
+            match report.survivors.len() {
+                0 => "Stopped".to_string(),
+                n => format!("Stopped; killed {n} leftover process(es)"),
+            }
+        }
+        None => {
+            // A queued launch has no process yet; cancelling it just drops it from the queue.
+            let dequeued = db.with_conn(|conn| {
+                conn.execute(
+                    "DELETE FROM launch_queue WHERE workspace_id = ?1",
+                    rusqlite::params![workspace_id],
+                )
+                .map_err(|e| format!("Failed to cancel queued launch: {e}"))
+            })?;
+            if dequeued == 0 {
+                return Err(format!("No running agent for workspace {workspace_id}"));
+            }
+            "Cancelled before launch".to_string()
+        }
+    };
+
     let completed_at = chrono::Utc::now().to_rfc3339();
     db.with_conn(|conn| {
         conn.execute(
-            "UPDATE agent_workspaces SET status = 'failed', completed_at = ?1 WHERE id = ?2",
-            rusqlite::params![completed_at, workspace_id],
+            "UPDATE agent_workspaces SET status = 'failed', exit_reason = ?1, completed_at = ?2 WHERE id = ?3",
+            rusqlite::params![reason, completed_at, workspace_id],
         )
         .map_err(|e| format!("Failed to update workspace: {e}"))?;
 
@@ -346,7 +905,7 @@ pub async fn stop_agent_inner(
 
 #[tauri::command]
 pub async fn stop_agent(
-    config: State<'_, ConfigState>,
+    config: State<'_, Arc<ConfigState>>,
     event_bus: State<'_, Arc<EventBus>>,
     registry: State<'_, Arc<AgentRegistry>>,
     project_id: String,
@@ -385,7 +944,7 @@ pub fn list_workspaces_inner(
 
 #[tauri::command]
 pub fn list_workspaces(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_id: String,
     card_id: String,
 ) -> Result<Vec<AgentWorkspace>, String> {
@@ -412,42 +971,25 @@ pub fn get_workspace_inner(
 
 #[tauri::command]
 pub fn get_workspace(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_id: String,
     workspace_id: String,
 ) -> Result<AgentWorkspace, String> {
     get_workspace_inner(&config, &project_id, &workspace_id)
 }
 
-pub async fn resume_agent_inner(
-    app: Option<AppHandle>,
-    event_bus: Option<Arc<EventBus>>,
+/// Builds the plan for resuming `session_id` as `workspace_id`.
+fn prepare_resume(
     config: &ConfigState,
-    registry: &Arc<AgentRegistry>,
     project_id: &str,
-    workspace_id: &str,
     card_id: &str,
-) -> Result<AgentWorkspace, String> {
+    session_id: &str,
+    workspace_id: &str,
+    worktree_path: Option<String>,
+) -> Result<LaunchPlan, String> {
     let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
     let db = open_project_db(&base_path, project_id)?;
 
-    let (session_id, old_worktree_path, old_branch_name) = db.with_conn(|conn| {
-        conn.query_row(
-            "SELECT session_id, worktree_path, branch_name FROM agent_workspaces WHERE id = ?1",
-            rusqlite::params![workspace_id],
-            |row| {
-                Ok((
-                    row.get::<_, Option<String>>(0)?,
-                    row.get::<_, Option<String>>(1)?,
-                    row.get::<_, Option<String>>(2)?,
-                ))
-            },
-        )
-        .map_err(|e| format!("Workspace not found: {e}"))
-    })?;
-
-    let session_id = session_id.ok_or_else(|| "No session_id to resume".to_string())?;
-
     let (card_title, card_description, parent_title, parent_description, project_agent_config) =
         db.with_conn(|conn| {
             let (title, description, parent_id): (String, String, Option<String>) = conn
@@ -494,14 +1036,14 @@ pub async fn resume_agent_inner(
         parent_description,
     };
 
-    let is_implementation = old_worktree_path.is_some();
+    let is_implementation = worktree_path.is_some();
     let worktree_name = if is_implementation {
         Some(worktree_fs::worktree_name_from_card(card_id, &card_title))
     } else {
         None
     };
 
-    let working_dir = if let Some(ref wt) = old_worktree_path {
+    let working_dir = if let Some(ref wt) = worktree_path {
         std::path::PathBuf::from(wt)
     } else {
         base_path
@@ -532,7 +1074,7 @@ pub async fn resume_agent_inner(
         None
     };
 
-    let mut agent_ctx = config.with_config(|c| {
+    let agent_ctx = config.with_config(|c| {
         assemble_context(
             c,
             &project_agent_config,
@@ -543,47 +1085,81 @@ pub async fn resume_agent_inner(
             socket_path_str.as_deref(),
             worktree_name.as_deref(),
             &[],
+            Some(session_id),
+            &WorkspaceLogs::new(&base_path, project_id, workspace_id).dir,
         )
     })?;
 
-    // Replace --print <prompt> with --resume <session_id>
-    if let Some(pos) = agent_ctx.args.iter().position(|a| a == "--print") {
-        // Remove --print and its value
-        agent_ctx.args.remove(pos); // --print
-        if pos < agent_ctx.args.len() {
-            agent_ctx.args.remove(pos); // the prompt value
-        }
-    }
-    agent_ctx.args.push("--resume".to_string());
-    agent_ctx.args.push(session_id);
-
-    let mut spawned = spawn_agent(&agent_ctx)?;
-
-    let stdout = spawned
-        .child
-        .stdout
-        .take()
-        .ok_or_else(|| "Failed to capture stdout".to_string())?;
-    let stderr = spawned
-        .child
-        .stderr
-        .take()
-        .ok_or_else(|| "Failed to capture stderr".to_string())?;
-    let stdin = spawned
-        .child
-        .stdin
-        .take()
-        .ok_or_else(|| "Failed to capture stdin".to_string())?;
+    Ok(LaunchPlan {
+        base_path,
+        project_id: project_id.to_string(),
+        card_id: card_id.to_string(),
+        agent_ctx,
+        worktree_path,
+    })
+}
+
+pub async fn resume_agent_inner(
+    app: Option<AppHandle>,
+    event_bus: Option<Arc<EventBus>>,
+    config: &ConfigState,
+    registry: &Arc<AgentRegistry>,
+    project_id: &str,
+    workspace_id: &str,
+    card_id: &str,
+) -> Result<AgentWorkspace, String> {
+    let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
+    let db = open_project_db(&base_path, project_id)?;
+
+    let (session_id, old_worktree_path, old_branch_name) = db.with_conn(|conn| {
+        conn.query_row(
+            "SELECT session_id, worktree_path, branch_name FROM agent_workspaces WHERE id = ?1",
+            rusqlite::params![workspace_id],
+            |row| {
+                Ok((
+                    row.get::<_, Option<String>>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                ))
+            },
+        )
+        .map_err(|e| format!("Workspace not found: {e}"))
+    })?;
+
+    let session_id = session_id.ok_or_else(|| "No session_id to resume".to_string())?;
+    let new_workspace_id = uuid::Uuid::new_v4().to_string();
+
+    let plan = prepare_resume(config, project_id, card_id, &session_id, &new_workspace_id, old_worktree_path)?;
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let Some(_slot) = reserve_slot(config, registry, &base_path, project_id, &new_workspace_id)? else {
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO agent_workspaces (id, card_id, agent_type, status, session_id, worktree_path, branch_name, attached_at) \
+                 VALUES (?1, ?2, ?3, 'queued', ?4, ?5, ?6, ?7)",
+                rusqlite::params![new_workspace_id, card_id, plan.agent_ctx.binary, session_id, plan.worktree_path, old_branch_name, now],
+            )
+            .map_err(|e| format!("Failed to create workspace: {e}"))?;
+
+            conn.execute(
+                "INSERT INTO launch_queue (workspace_id, card_id, status_id, worktree_path, branch_name, enqueued_at) \
+                 SELECT ?1, id, status_id, ?2, ?3, ?4 FROM cards WHERE id = ?5",
+                rusqlite::params![new_workspace_id, plan.worktree_path, old_branch_name, now, card_id],
+            )
+            .map_err(|e| format!("Failed to queue resume: {e}"))
+        })?;
+        start_queued_launches_inner(app, event_bus, config, registry)?;
+        return get_workspace_inner(config, project_id, &new_workspace_id);
+    };
 
-    let new_workspace_id = uuid::Uuid::new_v4().to_string();
-    let now = chrono::Utc::now().to_rfc3339();
-    let pid = spawned.pid;
+    let process = spawn_plan(&plan, &new_workspace_id)?;
+    let pid = process.pid;
 
     let workspace = db.with_conn(|conn| {
         conn.execute(
-            "INSERT INTO agent_workspaces (id, card_id, agent_type, status, pid, worktree_path, branch_name, attached_at) \
-             VALUES (?1, ?2, ?3, 'running', ?4, ?5, ?6, ?7)",
-            rusqlite::params![new_workspace_id, card_id, agent_ctx.binary, pid as i64, old_worktree_path, old_branch_name, now],
+            "INSERT INTO agent_workspaces (id, card_id, agent_type, status, session_id, pid, pid_start_time, worktree_path, branch_name, attached_at) \
+             VALUES (?1, ?2, ?3, 'running', ?4, ?5, ?6, ?7, ?8, ?9)",
+            rusqlite::params![new_workspace_id, card_id, plan.agent_ctx.binary, session_id, pid as i64, process.start_time.map(|t| t as i64), plan.worktree_path, old_branch_name, now],
         )
         .map_err(|e| format!("Failed to create workspace: {e}"))?;
 
@@ -593,30 +1169,16 @@ pub async fn resume_agent_inner(
             row_to_workspace,
         )
         .map_err(|e| format!("Failed to read workspace: {e}"))
-    })?;
-
-    start_stdout_streaming_inner(app.clone(), event_bus.clone(), new_workspace_id.clone(), stdout);
-    start_stderr_streaming_inner(app.clone(), event_bus.clone(), new_workspace_id.clone(), stderr);
-
-    let (stdin_tx, stdin_rx) = tokio::sync::mpsc::channel::<String>(64);
-    start_stdin_forwarding(stdin, stdin_rx);
-
-    let handle = AgentHandle {
-        workspace_id: new_workspace_id.clone(),
-        stdin_tx,
-        pid,
+    });
+    let workspace = match workspace {
+        Ok(workspace) => workspace,
+        Err(e) => {
+            process.abandon();
+            return Err(e);
+        }
     };
-    registry.insert(handle);
 
-    start_lifecycle_monitor_inner(
-        app,
-        event_bus,
-        Arc::clone(registry),
-        spawned.child,
-        new_workspace_id,
-        project_id.to_string(),
-        base_path,
-    );
+    attach_agent_process(app, event_bus, registry, &plan, &new_workspace_id, process);
 
     Ok(workspace)
 }
@@ -624,7 +1186,7 @@ pub async fn resume_agent_inner(
 #[tauri::command]
 pub async fn resume_agent(
     app: AppHandle,
-    config: State<'_, ConfigState>,
+    config: State<'_, Arc<ConfigState>>,
     event_bus: State<'_, Arc<EventBus>>,
     registry: State<'_, Arc<AgentRegistry>>,
     project_id: String,
@@ -681,7 +1243,7 @@ pub fn list_running_workspaces_inner(
         let running = db.with_conn(|conn| {
             let mut stmt = conn
                 .prepare(&format!(
-                    "{WORKSPACE_SELECT} WHERE status = 'running'"
+                    "{WORKSPACE_SELECT} WHERE status IN ('running', 'paused')"
                 ))
                 .map_err(|e| format!("{e}"))?;
 
@@ -703,7 +1265,7 @@ pub fn list_running_workspaces_inner(
 
 #[tauri::command]
 pub fn list_running_workspaces(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
 ) -> Result<Vec<AgentWorkspace>, String> {
     list_running_workspaces_inner(&config)
 }
@@ -712,14 +1274,30 @@ pub async fn stop_all_agents_inner(
     config: &ConfigState,
     registry: &Arc<AgentRegistry>,
 ) -> Result<(), String> {
-    let pids = registry.all_pids();
+    let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
+    let completed_at = chrono::Utc::now().to_rfc3339();
+
+    // Drop pending launches first so exiting agents don't make room for them.
+    for project_id in queue_project_ids(&base_path) {
+        if let Ok(db) = open_project_db(&base_path, &project_id) {
+            let _ = db.with_conn(|conn| {
+                conn.execute_batch("DELETE FROM launch_queue")
+                    .map_err(|e| format!("{e}"))?;
+                conn.execute(
+                    "UPDATE agent_workspaces SET status = 'failed', completed_at = ?1 WHERE status = 'queued'",
+                    rusqlite::params![completed_at],
+                )
+                .map_err(|e| format!("{e}"))?;
+                Ok(())
+            });
+        }
+    }
 
-    for pid in &pids {
-        let _ = stop_agent_process(*pid).await;
+    for handle in registry.drain() {
+        let _ = stop_agent_process(handle.pid).await;
     }
 
     // Mark all running workspaces as failed across all projects
-    let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
     let projects_dir = base_path.join("projects");
     if !projects_dir.exists() {
         return Ok(());
@@ -730,8 +1308,6 @@ pub async fn stop_all_agents_inner(
         Err(_) => return Ok(()),
     };
 
-    let completed_at = chrono::Utc::now().to_rfc3339();
-
     for entry in entries.flatten() {
         let path = entry.path();
         if !path.is_dir() {
@@ -750,7 +1326,8 @@ pub async fn stop_all_agents_inner(
         if let Ok(db) = open_project_db(&base_path, &project_id) {
             let _ = db.with_conn(|conn| {
                 conn.execute(
-                    "UPDATE agent_workspaces SET status = 'failed', completed_at = ?1 WHERE status = 'running'",
+                    "UPDATE agent_workspaces SET status = 'failed', exit_reason = 'Stopped', completed_at = ?1 \
+                     WHERE status IN ('running', 'paused')",
                     rusqlite::params![completed_at],
                 )
                 .map_err(|e| format!("{e}"))?;
@@ -764,7 +1341,7 @@ pub async fn stop_all_agents_inner(
 
 #[tauri::command]
 pub async fn stop_all_agents(
-    config: State<'_, ConfigState>,
+    config: State<'_, Arc<ConfigState>>,
     event_bus: State<'_, Arc<EventBus>>,
     registry: State<'_, Arc<AgentRegistry>>,
 ) -> Result<(), String> {
@@ -773,25 +1350,325 @@ pub async fn stop_all_agents(
     Ok(())
 }
 
+#[derive(Debug, Clone)]
+struct QueuedLaunch {
+    project_id: String,
+    workspace_id: String,
+    card_id: String,
+    status_id: String,
+    worktree_path: Option<String>,
+    repo_path: Option<String>,
+    /// Set when the queued launch resumes an earlier session.
+    session_id: Option<String>,
+    priority: i64,
+    enqueued_at: String,
+}
+
+impl QueuedLaunch {
+    fn runs_before(&self, other: &QueuedLaunch) -> bool {
+        self.priority > other.priority
+            || (self.priority == other.priority && self.enqueued_at < other.enqueued_at)
+    }
+}
+
+/// IDs of projects that have a database, i.e. that could hold queued launches.
+fn queue_project_ids(base_path: &std::path::Path) -> Vec<String> {
+    let entries = match std::fs::read_dir(base_path.join("projects")) {
+        Ok(e) => e,
+        Err(_) => return Vec::new(),
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join("db.sqlite").exists())
+        .filter_map(|path| path.file_name().and_then(|n| n.to_str()).map(str::to_string))
+        .collect()
+}
+
+/// Picks the highest-priority, oldest queued launch among projects that are
+/// under their concurrency limit.
+fn next_queued_launch(
+    base_path: &std::path::Path,
+    limits: &ConcurrencyConfig,
+    registry: &AgentRegistry,
+) -> Option<QueuedLaunch> {
+    let mut best: Option<QueuedLaunch> = None;
+
+    for project_id in queue_project_ids(base_path) {
+        if !limits.allows(
+            &project_id,
+            registry.running_count(),
+            registry.running_count_for_project(&project_id),
+        ) {
+            continue;
+        }
+        let db = match open_project_db(base_path, &project_id) {
+            Ok(db) => db,
+            Err(_) => continue,
+        };
+        let head = db.with_conn(|conn| {
+            let result = conn.query_row(
+                "SELECT q.workspace_id, q.card_id, q.status_id, q.worktree_path, q.repo_path, w.session_id, q.priority, q.enqueued_at \
+                 FROM launch_queue q JOIN agent_workspaces w ON w.id = q.workspace_id \
+                 ORDER BY q.priority DESC, q.enqueued_at LIMIT 1",
+                [],
+                |row| {
+                    Ok(QueuedLaunch {
+                        project_id: project_id.clone(),
+                        workspace_id: row.get(0)?,
+                        card_id: row.get(1)?,
+                        status_id: row.get(2)?,
+                        worktree_path: row.get(3)?,
+                        repo_path: row.get(4)?,
+                        session_id: row.get(5)?,
+                        priority: row.get(6)?,
+                        enqueued_at: row.get(7)?,
+                    })
+                },
+            );
+            match result {
+                Ok(entry) => Ok(Some(entry)),
+                Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+                Err(e) => Err(format!("Failed to read launch queue: {e}")),
+            }
+        });
+
+        if let Ok(Some(head)) = head {
+            let better = match &best {
+                Some(current) => head.runs_before(current),
+                None => true,
+            };
+            if better {
+                best = Some(head);
+            }
+        }
+    }
+
+    best
+}
+
+fn start_queued_launch(
+    app: Option<AppHandle>,
+    event_bus: Option<Arc<EventBus>>,
+    config: &ConfigState,
+    registry: &Arc<AgentRegistry>,
+    entry: &QueuedLaunch,
+) -> Result<AgentWorkspace, String> {
+    let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
+    let db = open_project_db(&base_path, &entry.project_id)?;
+    let plan = match &entry.session_id {
+        Some(session_id) => prepare_resume(
+            config,
+            &entry.project_id,
+            &entry.card_id,
+            session_id,
+            &entry.workspace_id,
+            entry.worktree_path.clone(),
+        )?,
+        None => {
+            // The card may have gained a blocker while it waited.
+            let blockers = db.with_conn(|conn| unfinished_blockers(conn, &entry.card_id))?;
+            if !blockers.is_empty() {
+                return Err(format!("Card is blocked by unfinished cards: {}", blockers.join(", ")));
+            }
+            prepare_launch(
+                config,
+                &entry.project_id,
+                &entry.card_id,
+                &entry.status_id,
+                &entry.workspace_id,
+                entry.worktree_path.clone(),
+                entry.repo_path.clone(),
+            )?
+        }
+    };
+    let process = spawn_plan(&plan, &entry.workspace_id)?;
+    let now = chrono::Utc::now().to_rfc3339();
+
+    let workspace = db.with_conn(|conn| {
+        conn.execute(
+            "UPDATE agent_workspaces SET status = 'running', agent_type = ?1, pid = ?2, pid_start_time = ?3, worktree_path = ?4, attached_at = ?5 \
+             WHERE id = ?6",
+            rusqlite::params![plan.agent_ctx.binary, process.pid as i64, process.start_time.map(|t| t as i64), plan.worktree_path, now, entry.workspace_id],
+        )
+        .map_err(|e| format!("Failed to update workspace: {e}"))?;
+
+        conn.query_row(
+            &format!("{WORKSPACE_SELECT} WHERE id = ?1"),
+            rusqlite::params![entry.workspace_id],
+            row_to_workspace,
+        )
+        .map_err(|e| format!("Failed to read workspace: {e}"))
+    });
+    let workspace = match workspace {
+        Ok(workspace) => workspace,
+        Err(e) => {
+            process.abandon();
+            return Err(e);
+        }
+    };
+
+    attach_agent_process(app, event_bus, registry, &plan, &entry.workspace_id, process);
+
+    Ok(workspace)
+}
+
+/// Starts queued launches, best first, until the queues are empty or the
+/// concurrency limits are reached.
+pub fn start_queued_launches_inner(
+    app: Option<AppHandle>,
+    event_bus: Option<Arc<EventBus>>,
+    config: &ConfigState,
+    registry: &Arc<AgentRegistry>,
+) -> Result<Vec<AgentWorkspace>, String> {
+    let (base_path, limits) = config.with_config(|c| Ok((c.resolve_base_path(), c.concurrency.clone())))?;
+    let mut started = Vec::new();
+
+    while let Some(entry) = next_queued_launch(&base_path, &limits, registry) {
+        let Some(_slot) = registry.reserve(&entry.workspace_id, &entry.project_id, &limits) else {
+            break;
+        };
+        let db = open_project_db(&base_path, &entry.project_id)?;
+        let dequeued = db.with_conn(|conn| {
+            conn.execute(
+                "DELETE FROM launch_queue WHERE workspace_id = ?1",
+                rusqlite::params![entry.workspace_id],
+            )
+            .map_err(|e| format!("Failed to dequeue launch: {e}"))
+        })?;
+        if dequeued == 0 {
+            // Another caller started it first.
+            continue;
+        }
+
+        match start_queued_launch(app.clone(), event_bus.clone(), config, registry, &entry) {
+            Ok(workspace) => started.push(workspace),
+            Err(e) => {
+                eprintln!("[queue] Failed to start queued workspace {}: {e}", entry.workspace_id);
+                let completed_at = chrono::Utc::now().to_rfc3339();
+                let _ = db.with_conn(|conn| {
+                    conn.execute(
+                        "UPDATE agent_workspaces SET status = 'failed', exit_reason = ?1, completed_at = ?2 WHERE id = ?3",
+                        rusqlite::params![e, completed_at, entry.workspace_id],
+                    )
+                    .map_err(|e| format!("{e}"))
+                });
+            }
+        }
+
+        if let Some(ref bus) = event_bus {
+            bus.emit_maestro(MaestroEvent::WorkspacesChanged {
+                project_id: entry.project_id.clone(),
+            });
+        }
+    }
+
+    Ok(started)
+}
+
+/// Starts queued launches whenever a running agent exits. A periodic sweep
+/// covers exits the bus dropped and agents that died without one.
+pub fn start_launch_queue_worker(
+    app: Option<AppHandle>,
+    event_bus: Arc<EventBus>,
+    config: Arc<ConfigState>,
+    registry: Arc<AgentRegistry>,
+) {
+    tauri::async_runtime::spawn(async move {
+        let mut rx = event_bus.subscribe();
+        let mut sweep = tokio::time::interval(Duration::from_secs(15));
+        loop {
+            tokio::select! {
+                event = rx.recv() => match event {
+                    Ok(AgentEvent::Exit(_)) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Ok(_) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                _ = sweep.tick() => {}
+            }
+
+            if let Err(e) = start_queued_launches_inner(
+                app.clone(),
+                Some(Arc::clone(&event_bus)),
+                &config,
+                &registry,
+            ) {
+                eprintln!("[queue] {e}");
+            }
+        }
+    });
+}
+
+pub fn set_launch_priority_inner(
+    config: &ConfigState,
+    project_id: &str,
+    workspace_id: &str,
+    priority: i64,
+) -> Result<(), String> {
+    let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
+    let db = open_project_db(&base_path, project_id)?;
+
+    let updated = db.with_conn(|conn| {
+        conn.execute(
+            "UPDATE launch_queue SET priority = ?1 WHERE workspace_id = ?2",
+            rusqlite::params![priority, workspace_id],
+        )
+        .map_err(|e| format!("Failed to update priority: {e}"))
+    })?;
+
+    if updated == 0 {
+        return Err(format!("Workspace {workspace_id} is not queued"));
+    }
+    Ok(())
+}
+
 #[tauri::command]
-pub fn archive_card_workspaces(
-    config: State<ConfigState>,
+pub fn set_launch_priority(
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
-    card_id: String,
+    workspace_id: String,
+    priority: i64,
+) -> Result<(), String> {
+    set_launch_priority_inner(&config, &project_id, &workspace_id, priority)?;
+    event_bus.emit_maestro(MaestroEvent::WorkspacesChanged {
+        project_id: project_id.clone(),
+    });
+    Ok(())
+}
+
+pub fn archive_card_workspaces_inner(
+    config: &ConfigState,
+    project_id: &str,
+    card_id: &str,
 ) -> Result<(), String> {
     let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
-    let db = open_project_db(&base_path, &project_id)?;
+    let db = open_project_db(&base_path, project_id)?;
     let completed_at = chrono::Utc::now().to_rfc3339();
 
     db.with_conn(|conn| {
+        conn.execute(
+            "DELETE FROM launch_queue WHERE card_id = ?1",
+            rusqlite::params![card_id],
+        )
+        .map_err(|e| format!("Failed to cancel queued launches: {e}"))?;
         conn.execute(
             "UPDATE agent_workspaces SET status = 'completed', completed_at = ?1 WHERE card_id = ?2 AND status NOT IN ('completed', 'failed')",
             rusqlite::params![completed_at, card_id],
         )
         .map_err(|e| format!("Failed to archive workspaces: {e}"))?;
         Ok(())
-    })?;
+    })
+}
+
+#[tauri::command]
+pub fn archive_card_workspaces(
+    config: State<Arc<ConfigState>>,
+    event_bus: State<Arc<EventBus>>,
+    project_id: String,
+    card_id: String,
+) -> Result<(), String> {
+    archive_card_workspaces_inner(&config, &project_id, &card_id)?;
     event_bus.emit_maestro(MaestroEvent::WorkspacesChanged {
         project_id: project_id.clone(),
     });
@@ -803,6 +1680,7 @@ mod tests {
     use super::*;
     use crate::commands::projects::seed_default_statuses;
     use crate::db::DbConnection;
+    use crate::fixtures::{self, block_on};
 
     fn setup_test_db() -> (DbConnection, String) {
         let dir = tempfile::tempdir().unwrap();
@@ -911,4 +1789,309 @@ mod tests {
         })
         .unwrap();
     }
+
+    fn queue_test_config(max_agents: Option<usize>) -> (ConfigState, tempfile::TempDir) {
+        let (config, dir) = fixtures::config_state();
+        config.update(|c| c.concurrency.max_agents = max_agents).unwrap();
+        (config, dir)
+    }
+
+    fn seed_queue_project(config: &ConfigState) -> (String, String, String) {
+        let base_path = config.with_config(|c| Ok(c.resolve_base_path())).unwrap();
+        let project_id = uuid::Uuid::new_v4().to_string();
+        let db = fixtures::create_project_db(&base_path, &project_id);
+        db.with_conn(|conn| {
+            let card_id = insert_card(conn, &project_id);
+            Ok((project_id.clone(), card_id, fixtures::status_id(conn, &project_id, "Backlog")))
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_launch_is_queued_at_capacity() {
+        let (config, _dir) = queue_test_config(Some(0));
+        let (project_id, card_id, status_id) = seed_queue_project(&config);
+        let registry = Arc::new(AgentRegistry::new());
+
+        let workspace = block_on(launch_agent_inner(
+            None, None, &config, &registry, &project_id, &card_id, &status_id, None, None, None,
+        ))
+        .unwrap();
+
+        assert_eq!(workspace.status, "queued");
+        assert_eq!(workspace.pid, None);
+        assert_eq!(registry.running_count(), 0);
+
+        let base_path = config.with_config(|c| Ok(c.resolve_base_path())).unwrap();
+        let limits = config.with_config(|c| Ok(c.concurrency.clone())).unwrap();
+        assert!(next_queued_launch(&base_path, &limits, &registry).is_none());
+
+        let open = crate::config::global::ConcurrencyConfig::default();
+        let next = next_queued_launch(&base_path, &open, &registry).unwrap();
+        assert_eq!(next.workspace_id, workspace.id);
+    }
+
+    #[test]
+    fn test_failed_queued_launch_records_reason() {
+        let (config, _dir) = queue_test_config(Some(0));
+        let (project_id, card_id, status_id) = seed_queue_project(&config);
+        let registry = Arc::new(AgentRegistry::new());
+
+        let queued = block_on(launch_agent_inner(
+            None, None, &config, &registry, &project_id, &card_id, &status_id, None, None, None,
+        ))
+        .unwrap();
+        config
+            .update(|c| {
+                c.concurrency.max_agents = None;
+                for profile in c.agents.values_mut() {
+                    profile.binary = "/nonexistent/maestro-agent".to_string();
+                }
+            })
+            .unwrap();
+
+        // Spawning registers its pipes with the runtime.
+        let started = block_on(async { start_queued_launches_inner(None, None, &config, &registry) }).unwrap();
+        assert!(started.is_empty());
+        let workspace = get_workspace_inner(&config, &project_id, &queued.id).unwrap();
+        assert_eq!(workspace.status, "failed");
+        assert!(workspace.exit_reason.is_some_and(|reason| !reason.is_empty()));
+        assert_eq!(registry.running_count(), 0);
+    }
+
+    #[test]
+    fn test_launch_does_not_jump_queued_launches() {
+        let (config, _dir) = queue_test_config(Some(0));
+        let (project_id, card_id, status_id) = seed_queue_project(&config);
+        let registry = Arc::new(AgentRegistry::new());
+        let queued = block_on(launch_agent_inner(
+            None, None, &config, &registry, &project_id, &card_id, &status_id, None, None, None,
+        ))
+        .unwrap();
+
+        config.update(|c| c.concurrency.max_agents = Some(1)).unwrap();
+        let base_path = config.with_config(|c| Ok(c.resolve_base_path())).unwrap();
+        assert!(reserve_slot(&config, &registry, &base_path, &project_id, "new-launch").unwrap().is_none());
+        assert_eq!(registry.running_count(), 0);
+
+        let db = open_project_db(&base_path, &project_id).unwrap();
+        db.with_conn(|conn| {
+            conn.execute("DELETE FROM launch_queue WHERE workspace_id = ?1", rusqlite::params![queued.id])
+                .map_err(|e| format!("{e}"))
+        })
+        .unwrap();
+        let slot = reserve_slot(&config, &registry, &base_path, &project_id, "new-launch").unwrap();
+        assert!(slot.is_some());
+        assert_eq!(registry.running_count(), 1);
+    }
+
+    #[test]
+    fn test_resume_is_queued_at_capacity() {
+        let (config, _dir) = queue_test_config(Some(0));
+        let (project_id, card_id, _) = seed_queue_project(&config);
+        let registry = Arc::new(AgentRegistry::new());
+        let base_path = config.with_config(|c| Ok(c.resolve_base_path())).unwrap();
+        let db = open_project_db(&base_path, &project_id).unwrap();
+        let finished_id = uuid::Uuid::new_v4().to_string();
+        db.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO agent_workspaces (id, card_id, agent_type, status, session_id, attached_at) \
+                 VALUES (?1, ?2, 'claude', 'completed', 'sess-1', ?3)",
+                rusqlite::params![finished_id, card_id, chrono::Utc::now().to_rfc3339()],
+            )
+            .map_err(|e| format!("{e}"))
+        })
+        .unwrap();
+
+        let resumed = block_on(resume_agent_inner(
+            None, None, &config, &registry, &project_id, &finished_id, &card_id,
+        ))
+        .unwrap();
+        assert_eq!(resumed.status, "queued");
+        assert_eq!(resumed.session_id.as_deref(), Some("sess-1"));
+
+        let open = crate::config::global::ConcurrencyConfig::default();
+        let next = next_queued_launch(&base_path, &open, &registry).unwrap();
+        assert_eq!(next.workspace_id, resumed.id);
+        assert_eq!(next.session_id.as_deref(), Some("sess-1"));
+    }
+
+    #[test]
+    fn test_queue_orders_by_priority_then_age() {
+        let (config, _dir) = queue_test_config(Some(0));
+        let (project_id, card_id, status_id) = seed_queue_project(&config);
+        let registry = Arc::new(AgentRegistry::new());
+
+        let first = block_on(launch_agent_inner(
+            None, None, &config, &registry, &project_id, &card_id, &status_id, None, None, None,
+        ))
+        .unwrap();
+        let second = block_on(launch_agent_inner(
+            None, None, &config, &registry, &project_id, &card_id, &status_id, None, None, None,
+        ))
+        .unwrap();
+
+        let base_path = config.with_config(|c| Ok(c.resolve_base_path())).unwrap();
+        let open = crate::config::global::ConcurrencyConfig::default();
+        assert_eq!(next_queued_launch(&base_path, &open, &registry).unwrap().workspace_id, first.id);
+
+        set_launch_priority_inner(&config, &project_id, &second.id, 5).unwrap();
+        assert_eq!(next_queued_launch(&base_path, &open, &registry).unwrap().workspace_id, second.id);
+
+        assert!(set_launch_priority_inner(&config, &project_id, "missing", 1).is_err());
+    }
+
+    #[test]
+    fn test_stop_cancels_queued_launch() {
+        let (config, _dir) = queue_test_config(Some(0));
+        let (project_id, card_id, status_id) = seed_queue_project(&config);
+        let registry = Arc::new(AgentRegistry::new());
+
+        let queued = block_on(launch_agent_inner(
+            None, None, &config, &registry, &project_id, &card_id, &status_id, None, None, None,
+        ))
+        .unwrap();
+        let stopped = block_on(stop_agent_inner(&config, &registry, &project_id, &queued.id)).unwrap();
+        assert_eq!(stopped.status, "failed");
+
+        let base_path = config.with_config(|c| Ok(c.resolve_base_path())).unwrap();
+        let open = crate::config::global::ConcurrencyConfig::default();
+        assert!(next_queued_launch(&base_path, &open, &registry).is_none());
+        assert!(block_on(stop_agent_inner(&config, &registry, &project_id, &queued.id)).is_err());
+    }
+
+    #[test]
+    fn test_auto_run_launches_once_per_status_entry() {
+        let (config, _dir) = queue_test_config(Some(0));
+        let (project_id, card_id, status_id) = seed_queue_project(&config);
+        let registry = Arc::new(AgentRegistry::new());
+
+        let none = block_on(auto_run_card_inner(None, None, &config, &registry, &project_id, &card_id)).unwrap();
+        assert!(none.is_none());
+
+        crate::commands::statuses::set_status_auto_run_inner(
+            &config,
+            &project_id,
+            &status_id,
+            Some(crate::commands::statuses::AutoRunPolicy {
+                agent: Some("codex".to_string()),
+                create_worktree: false,
+                directory_id: None,
+            }),
+        )
+        .unwrap();
+
+        let workspace = block_on(auto_run_card_inner(None, None, &config, &registry, &project_id, &card_id))
+            .unwrap()
+            .unwrap();
+        assert_eq!(workspace.status, "queued");
+        assert_eq!(workspace.agent_type, "codex");
+
+        let again = block_on(auto_run_card_inner(None, None, &config, &registry, &project_id, &card_id)).unwrap();
+        assert!(again.is_none());
+    }
+
+    #[test]
+    fn test_blocked_card_cannot_launch() {
+        let (config, _dir) = queue_test_config(Some(0));
+        let (project_id, card_id, status_id) = seed_queue_project(&config);
+        let registry = Arc::new(AgentRegistry::new());
+        let base_path = config.with_config(|c| Ok(c.resolve_base_path())).unwrap();
+        let blocker = open_project_db(&base_path, &project_id)
+            .unwrap()
+            .with_conn(|conn| Ok(insert_card(conn, &project_id)))
+            .unwrap();
+        crate::commands::dependencies::add_card_dependency_inner(&config, &project_id, &card_id, &blocker)
+            .unwrap();
+
+        let err = block_on(launch_agent_inner(
+            None, None, &config, &registry, &project_id, &card_id, &status_id, None, None, None,
+        ))
+        .unwrap_err();
+        assert!(err.contains("blocked"));
+
+        crate::commands::statuses::set_status_auto_run_inner(
+            &config,
+            &project_id,
+            &status_id,
+            Some(crate::commands::statuses::AutoRunPolicy {
+                agent: None,
+                create_worktree: false,
+                directory_id: None,
+            }),
+        )
+        .unwrap();
+        let waiting = block_on(auto_run_card_inner(None, None, &config, &registry, &project_id, &card_id)).unwrap();
+        assert!(waiting.is_none());
+    }
+
+    #[test]
+    fn test_queued_launch_rechecks_blockers() {
+        let (config, _dir) = queue_test_config(Some(0));
+        let (project_id, card_id, status_id) = seed_queue_project(&config);
+        let registry = Arc::new(AgentRegistry::new());
+
+        let queued = block_on(launch_agent_inner(
+            None, None, &config, &registry, &project_id, &card_id, &status_id, None, None, None,
+        ))
+        .unwrap();
+        let base_path = config.with_config(|c| Ok(c.resolve_base_path())).unwrap();
+        let blocker = open_project_db(&base_path, &project_id)
+            .unwrap()
+            .with_conn(|conn| Ok(insert_card(conn, &project_id)))
+            .unwrap();
+        crate::commands::dependencies::add_card_dependency_inner(&config, &project_id, &card_id, &blocker)
+            .unwrap();
+        config.update(|c| c.concurrency.max_agents = None).unwrap();
+
+        let started = block_on(async { start_queued_launches_inner(None, None, &config, &registry) }).unwrap();
+        assert!(started.is_empty());
+        let workspace = get_workspace_inner(&config, &project_id, &queued.id).unwrap();
+        assert_eq!(workspace.status, "failed");
+        assert!(workspace.exit_reason.unwrap().contains("blocked"));
+        assert_eq!(workspace.pid, None);
+    }
+
+    #[test]
+    fn test_auto_run_worktree_requires_linked_repo() {
+        let (config, _dir) = queue_test_config(Some(0));
+        let (project_id, card_id, status_id) = seed_queue_project(&config);
+        let registry = Arc::new(AgentRegistry::new());
+
+        crate::commands::statuses::set_status_auto_run_inner(
+            &config,
+            &project_id,
+            &status_id,
+            Some(crate::commands::statuses::AutoRunPolicy {
+                agent: None,
+                create_worktree: true,
+                directory_id: None,
+            }),
+        )
+        .unwrap();
+
+        let result = block_on(auto_run_card_inner(None, None, &config, &registry, &project_id, &card_id));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_status_agent_override_keeps_model_and_instructions() {
+        let mut global = GlobalConfig::default();
+        global.defaults.status.insert(
+            "started".to_string(),
+            crate::config::global::StatusGroupConfig {
+                agent: Some("claude-code".to_string()),
+                model: Some("sonnet".to_string()),
+                instructions: Some("Keep commits small".to_string()),
+            },
+        );
+        let mut project_config = serde_json::json!({});
+
+        override_status_agent(&global, &mut project_config, "Started", "codex");
+
+        let resolved = resolve_agent_config(&global, &project_config, "Started");
+        assert_eq!(resolved.agent, "codex");
+        assert_eq!(resolved.model.as_deref(), Some("sonnet"));
+        assert_eq!(resolved.instructions.as_deref(), Some("Keep commits small"));
+    }
 }
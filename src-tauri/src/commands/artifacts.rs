@@ -1,18 +1,20 @@
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
 use crate::commands::config::ConfigState;
 use crate::commands::projects::open_project_db;
+use crate::commands::search::index_artifact_content;
 use crate::executor::{EventBus, MaestroEvent};
 use crate::fs::artifacts::{
     delete_artifact_file, ensure_artifact_dir, name_to_slug, read_artifact_file,
     write_artifact_file,
 };
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct Artifact {
     pub id: String,
     pub card_id: String,
@@ -116,6 +118,7 @@ pub fn create_artifact_inner(
             rusqlite::params![id, card_id, name, relative_path, created_by, now, now],
         )
         .map_err(|e| format!("Failed to create artifact: {e}"))?;
+        index_artifact_content(conn, &id, content)?;
 
         conn.query_row(
             &format!("{ARTIFACT_SELECT} WHERE id = ?1"),
@@ -128,7 +131,7 @@ pub fn create_artifact_inner(
 
 #[tauri::command]
 pub fn create_artifact(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     card_id: String,
@@ -167,7 +170,7 @@ pub fn read_artifact_inner(
 
 #[tauri::command]
 pub fn read_artifact(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_id: String,
     id: String,
 ) -> Result<String, String> {
@@ -201,6 +204,7 @@ pub fn update_artifact_inner(
             rusqlite::params![now, id],
         )
         .map_err(|e| format!("Failed to update artifact: {e}"))?;
+        index_artifact_content(conn, id, content)?;
 
         conn.query_row(
             &format!("{ARTIFACT_SELECT} WHERE id = ?1"),
@@ -213,7 +217,7 @@ pub fn update_artifact_inner(
 
 #[tauri::command]
 pub fn update_artifact(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     id: String,
@@ -263,7 +267,7 @@ pub fn delete_artifact_inner(
 
 #[tauri::command]
 pub fn delete_artifact(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     id: String,
@@ -301,7 +305,7 @@ pub fn list_artifacts_inner(
 
 #[tauri::command]
 pub fn list_artifacts(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_id: String,
     card_id: String,
 ) -> Result<Vec<Artifact>, String> {
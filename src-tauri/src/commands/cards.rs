@@ -1,13 +1,18 @@
 use std::sync::Arc;
 
+use rusqlite::OptionalExtension;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 
+use crate::commands::agent::spawn_auto_run;
 use crate::commands::config::ConfigState;
+use crate::commands::history::{record_card_event, status_value, Actor};
 use crate::commands::projects::open_project_db;
-use crate::executor::{EventBus, MaestroEvent};
+use crate::executor::logs::WorkspaceLogs;
+use crate::executor::{AgentRegistry, EventBus, MaestroEvent};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct CardWithStatus {
     pub id: String,
     pub project_id: String,
@@ -21,9 +26,11 @@ pub struct CardWithStatus {
     pub updated_at: String,
     pub status_name: String,
     pub status_group: String,
+    /// Whether any card this one depends on isn't Completed yet.
+    pub blocked: bool,
 }
 
-fn row_to_card(row: &rusqlite::Row) -> Result<CardWithStatus, rusqlite::Error> {
+pub(crate) fn row_to_card(row: &rusqlite::Row) -> Result<CardWithStatus, rusqlite::Error> {
     let labels_json: String = row.get(6)?;
     let labels: Vec<String> =
         serde_json::from_str(&labels_json).unwrap_or_default();
@@ -40,24 +47,44 @@ fn row_to_card(row: &rusqlite::Row) -> Result<CardWithStatus, rusqlite::Error> {
         updated_at: row.get(9)?,
         status_name: row.get(10)?,
         status_group: row.get(11)?,
+        blocked: row.get(12)?,
     })
 }
 
-const CARD_SELECT: &str = "\
+pub(crate) const CARD_SELECT: &str = "\
     SELECT c.id, c.project_id, c.parent_id, c.status_id, c.title, c.description, \
            c.labels, c.sort_order, c.created_at, c.updated_at, \
-           s.name AS status_name, s.\"group\" AS status_group \
+           s.name AS status_name, s.\"group\" AS status_group, \
+           EXISTS (SELECT 1 FROM card_dependencies d \
+                   JOIN cards b ON b.id = d.depends_on_id \
+                   JOIN statuses bs ON bs.id = b.status_id \
+                   WHERE d.card_id = c.id AND bs.\"group\" != 'Completed') AS blocked \
     FROM cards c JOIN statuses s ON c.status_id = s.id";
 
+/// Fields of a card to create. Without a status it goes to the default
+/// Backlog status.
+#[derive(Debug, Default, Deserialize)]
+pub struct CardInput {
+    pub title: String,
+    pub description: Option<String>,
+    pub labels: Option<Vec<String>>,
+    pub parent_id: Option<String>,
+    pub status_id: Option<String>,
+}
+
 pub fn create_card_inner(
     config: &ConfigState,
     project_id: &str,
-    title: &str,
-    description: Option<String>,
-    labels: Option<Vec<String>>,
-    parent_id: Option<String>,
-    status_id: Option<String>,
+    card: CardInput,
+    actor: &Actor,
 ) -> Result<CardWithStatus, String> {
+    let CardInput {
+        title,
+        description,
+        labels,
+        parent_id,
+        status_id,
+    } = card;
     let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
     let db = open_project_db(&base_path, project_id)?;
 
@@ -115,12 +142,31 @@ pub fn create_card_inner(
             .map_err(|e| format!("Failed to serialize labels: {e}"))?;
         let sort_order = max_order + 1;
 
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to begin transaction: {e}"))?;
         conn.execute(
             "INSERT INTO cards (id, project_id, parent_id, status_id, title, description, labels, sort_order, created_at, updated_at) \
              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             rusqlite::params![id, project_id, parent_id, resolved_status_id, title, desc, labels_json, sort_order, now, now],
         )
         .map_err(|e| format!("Failed to create card: {e}"))?;
+        record_card_event(
+            conn,
+            project_id,
+            &id,
+            actor,
+            "created",
+            None,
+            Some(serde_json::json!({
+                "title": title,
+                "description": desc,
+                "labels": labels_vec,
+                "status_id": resolved_status_id,
+            })),
+        )?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit transaction: {e}"))?;
 
         let card = conn
             .query_row(
@@ -136,7 +182,7 @@ pub fn create_card_inner(
 
 #[tauri::command]
 pub fn create_card(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     title: String,
@@ -145,7 +191,14 @@ pub fn create_card(
     parent_id: Option<String>,
     status_id: Option<String>,
 ) -> Result<CardWithStatus, String> {
-    let result = create_card_inner(&config, &project_id, &title, description, labels, parent_id, status_id)?;
+    let card = CardInput {
+        title,
+        description,
+        labels,
+        parent_id,
+        status_id,
+    };
+    let result = create_card_inner(&config, &project_id, card, &Actor::User)?;
     event_bus.emit_maestro(MaestroEvent::CardsChanged {
         project_id: project_id.clone(),
     });
@@ -172,7 +225,7 @@ pub fn get_card_inner(
 
 #[tauri::command]
 pub fn get_card(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_id: String,
     id: String,
 ) -> Result<CardWithStatus, String> {
@@ -186,6 +239,7 @@ pub fn update_card_inner(
     title: Option<String>,
     description: Option<String>,
     labels: Option<Vec<String>>,
+    actor: &Actor,
 ) -> Result<CardWithStatus, String> {
     let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
     let db = open_project_db(&base_path, project_id)?;
@@ -205,21 +259,54 @@ pub fn update_card_inner(
             )
             .map_err(|e| format!("Card not found: {e}"))?;
 
-        let new_title = title.unwrap_or(existing.0);
-        let new_description = description.unwrap_or(existing.1);
+        let new_title = title.unwrap_or_else(|| existing.0.clone());
+        let new_description = description.unwrap_or_else(|| existing.1.clone());
         let new_labels_json = match labels {
             Some(l) => serde_json::to_string(&l)
                 .map_err(|e| format!("Failed to serialize labels: {e}"))?,
-            None => existing.2,
+            None => existing.2.clone(),
         };
         let now = chrono::Utc::now().to_rfc3339();
 
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to begin transaction: {e}"))?;
         conn.execute(
             "UPDATE cards SET title = ?1, description = ?2, labels = ?3, updated_at = ?4 WHERE id = ?5",
             rusqlite::params![new_title, new_description, new_labels_json, now, id],
         )
         .map_err(|e| format!("Failed to update card: {e}"))?;
 
+        // Only the fields that actually changed.
+        let mut old_value = serde_json::Map::new();
+        let mut new_value = serde_json::Map::new();
+        if new_title != existing.0 {
+            old_value.insert("title".into(), existing.0.into());
+            new_value.insert("title".into(), new_title.into());
+        }
+        if new_description != existing.1 {
+            old_value.insert("description".into(), existing.1.into());
+            new_value.insert("description".into(), new_description.into());
+        }
+        if new_labels_json != existing.2 {
+            let labels = |json: &str| serde_json::from_str(json).unwrap_or_default();
+            old_value.insert("labels".into(), labels(&existing.2));
+            new_value.insert("labels".into(), labels(&new_labels_json));
+        }
+        if !new_value.is_empty() {
+            record_card_event(
+                conn,
+                project_id,
+                id,
+                actor,
+                "updated",
+                Some(old_value.into()),
+                Some(new_value.into()),
+            )?;
+        }
+        tx.commit()
+            .map_err(|e| format!("Failed to commit transaction: {e}"))?;
+
         conn.query_row(
             &format!("{CARD_SELECT} WHERE c.id = ?1"),
             rusqlite::params![id],
@@ -231,7 +318,7 @@ pub fn update_card_inner(
 
 #[tauri::command]
 pub fn update_card(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     id: String,
@@ -239,7 +326,7 @@ pub fn update_card(
     description: Option<String>,
     labels: Option<Vec<String>>,
 ) -> Result<CardWithStatus, String> {
-    let result = update_card_inner(&config, &project_id, &id, title, description, labels)?;
+    let result = update_card_inner(&config, &project_id, &id, title, description, labels, &Actor::User)?;
     event_bus.emit_maestro(MaestroEvent::CardsChanged {
         project_id: project_id.clone(),
     });
@@ -250,41 +337,74 @@ pub fn delete_card_inner(
     config: &ConfigState,
     project_id: &str,
     id: &str,
+    actor: &Actor,
 ) -> Result<(), String> {
     let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
     let db = open_project_db(&base_path, project_id)?;
 
     db.with_conn(|conn| {
-        let exists: bool = conn
+        let title: Option<String> = conn
             .query_row(
-                "SELECT COUNT(*) > 0 FROM cards WHERE id = ?1 AND project_id = ?2",
+                "SELECT title FROM cards WHERE id = ?1 AND project_id = ?2",
                 rusqlite::params![id, project_id],
                 |row| row.get(0),
             )
+            .optional()
             .map_err(|e| format!("Failed to check card: {e}"))?;
 
-        if !exists {
+        let Some(title) = title else {
             return Err("Card not found".to_string());
-        }
+        };
+
+        // Workspaces go with the card and its sub-cards, and their logs with them.
+        let workspace_ids = conn
+            .prepare(
+                "WITH RECURSIVE doomed(id) AS ( \
+                   SELECT ?1 UNION SELECT c.id FROM cards c JOIN doomed d ON c.parent_id = d.id \
+                 ) \
+                 SELECT w.id FROM agent_workspaces w JOIN doomed d ON w.card_id = d.id",
+            )
+            .and_then(|mut stmt| {
+                stmt.query_map(rusqlite::params![id], |row| row.get::<_, String>(0))?
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(|e| format!("Failed to list card workspaces: {e}"))?;
 
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to begin transaction: {e}"))?;
         conn.execute(
             "DELETE FROM cards WHERE id = ?1",
             rusqlite::params![id],
         )
         .map_err(|e| format!("Failed to delete card: {e}"))?;
-
+        record_card_event(
+            conn,
+            project_id,
+            id,
+            actor,
+            "deleted",
+            Some(serde_json::json!({ "title": title })),
+            None,
+        )?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit transaction: {e}"))?;
+
+        for workspace_id in workspace_ids {
+            WorkspaceLogs::new(&base_path, project_id, &workspace_id).remove();
+        }
         Ok(())
     })
 }
 
 #[tauri::command]
 pub fn delete_card(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     id: String,
 ) -> Result<(), String> {
-    delete_card_inner(&config, &project_id, &id)?;
+    delete_card_inner(&config, &project_id, &id, &Actor::User)?;
     event_bus.emit_maestro(MaestroEvent::CardsChanged {
         project_id: project_id.clone(),
     });
@@ -316,7 +436,7 @@ pub fn list_cards_inner(
 
 #[tauri::command]
 pub fn list_cards(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_id: String,
 ) -> Result<Vec<CardWithStatus>, String> {
     list_cards_inner(&config, &project_id)
@@ -350,7 +470,7 @@ pub fn list_sub_cards_inner(
 
 #[tauri::command]
 pub fn list_sub_cards(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_id: String,
     parent_id: String,
 ) -> Result<Vec<CardWithStatus>, String> {
@@ -363,6 +483,7 @@ pub fn move_card_inner(
     id: &str,
     target_status_id: &str,
     target_sort_order: i32,
+    actor: &Actor,
 ) -> Result<CardWithStatus, String> {
     let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
     let db = open_project_db(&base_path, project_id)?;
@@ -431,6 +552,18 @@ pub fn move_card_inner(
             )
             .map_err(|e| format!("Failed to move card: {e}"))?;
 
+            if target_status_id != old_status_id {
+                record_card_event(
+                    conn,
+                    project_id,
+                    id,
+                    actor,
+                    "moved",
+                    Some(status_value(conn, &old_status_id)),
+                    Some(status_value(conn, target_status_id)),
+                )?;
+            }
+
             conn.query_row(
                 &format!("{CARD_SELECT} WHERE c.id = ?1"),
                 rusqlite::params![id],
@@ -455,17 +588,29 @@ pub fn move_card_inner(
 
 #[tauri::command]
 pub fn move_card(
-    config: State<ConfigState>,
+    app: AppHandle,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     id: String,
     target_status_id: String,
     target_sort_order: i32,
 ) -> Result<CardWithStatus, String> {
-    let result = move_card_inner(&config, &project_id, &id, &target_status_id, target_sort_order)?;
+    let previous_status_id = get_card_inner(&config, &project_id, &id)?.status_id;
+    let result = move_card_inner(&config, &project_id, &id, &target_status_id, target_sort_order, &Actor::User)?;
     event_bus.emit_maestro(MaestroEvent::CardsChanged {
         project_id: project_id.clone(),
     });
+    if result.status_id != previous_status_id {
+        spawn_auto_run(
+            Some(app.clone()),
+            Some(Arc::clone(&event_bus)),
+            Arc::clone(&config),
+            Arc::clone(&app.state::<Arc<AgentRegistry>>()),
+            project_id,
+            id,
+        );
+    }
     Ok(result)
 }
 
@@ -540,7 +685,7 @@ pub fn reorder_cards_inner(
 
 #[tauri::command]
 pub fn reorder_cards(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     status_id: String,
@@ -4,9 +4,13 @@ use std::sync::{Arc, Mutex};
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
-use crate::config::global::{default_config_path, AgentProfile, GlobalConfig, StatusGroupConfig};
+use crate::config::global::{
+    default_config_path, AdapterKind, AdapterTemplate, AgentLimits, AgentProfile, GlobalConfig, OutputFormat,
+    StatusGroupConfig,
+};
 use crate::config::resolution::resolve_agent_config;
 use crate::executor::{EventBus, MaestroEvent};
+use crate::http::tls;
 use std::collections::HashMap;
 
 pub struct ConfigState {
@@ -16,7 +20,10 @@ pub struct ConfigState {
 
 impl ConfigState {
     pub fn load() -> Result<Self, String> {
-        let config_path = default_config_path();
+        Self::load_from(default_config_path())
+    }
+
+    pub fn load_from(config_path: PathBuf) -> Result<Self, String> {
         let config = GlobalConfig::load(&config_path)?;
         Ok(Self {
             config: Mutex::new(config),
@@ -64,6 +71,12 @@ pub struct AgentProfileResponse {
     pub flags: Vec<String>,
     pub custom_command: Option<String>,
     pub env_vars: Option<HashMap<String, String>>,
+    pub session_id_pattern: Option<String>,
+    pub output_format: OutputFormat,
+    pub limits: AgentLimits,
+    pub pty: bool,
+    pub adapter: Option<AdapterKind>,
+    pub template: Option<AdapterTemplate>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -84,6 +97,12 @@ pub fn get_global_config_inner(config: &ConfigState) -> Result<GlobalConfigRespo
                 flags: profile.flags.clone(),
                 custom_command: profile.custom_command.clone(),
                 env_vars: profile.env_vars.clone(),
+                session_id_pattern: profile.session_id_pattern.clone(),
+                output_format: profile.output_format,
+                limits: profile.limits,
+                pty: profile.pty,
+                adapter: profile.adapter,
+                template: profile.template.clone(),
             })
             .collect();
 
@@ -97,7 +116,7 @@ pub fn get_global_config_inner(config: &ConfigState) -> Result<GlobalConfigRespo
 }
 
 #[tauri::command]
-pub fn get_global_config(config: State<ConfigState>) -> Result<GlobalConfigResponse, String> {
+pub fn get_global_config(config: State<Arc<ConfigState>>) -> Result<GlobalConfigResponse, String> {
     get_global_config_inner(&config)
 }
 
@@ -110,7 +129,7 @@ pub fn set_last_project_inner(config: &ConfigState, project_id: &str) -> Result<
 
 #[tauri::command]
 pub fn set_last_project(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
 ) -> Result<(), String> {
@@ -136,7 +155,7 @@ pub fn resolve_config_inner(
 
 #[tauri::command]
 pub fn resolve_config(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_agent_config: serde_json::Value,
     status_group: String,
 ) -> Result<ResolvedAgentConfigResponse, String> {
@@ -151,6 +170,9 @@ pub struct HttpServerConfigResponse {
     pub auth_token: String,
     pub requires_auth: bool,
     pub server_url: String,
+    pub tls_enabled: bool,
+    /// SHA-256 fingerprint of the TLS certificate, for pairing devices.
+    pub tls_fingerprint: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -158,6 +180,7 @@ pub struct HttpServerConfigUpdate {
     pub enabled: Option<bool>,
     pub port: Option<u16>,
     pub bind_address: Option<String>,
+    pub tls_enabled: Option<bool>,
 }
 
 pub fn get_http_server_config_inner(config: &ConfigState) -> Result<HttpServerConfigResponse, String> {
@@ -169,7 +192,15 @@ pub fn get_http_server_config_inner(config: &ConfigState) -> Result<HttpServerCo
         } else {
             &http.bind_address
         };
-        let server_url = format!("http://{}:{}", display_address, http.port);
+        let server_url = format!("{}://{}:{}", http.scheme(), display_address, http.port);
+        let tls_fingerprint = if http.tls.enabled {
+            tls::cert_paths(&http.tls, &c.resolve_base_path())
+                .ok()
+                .and_then(|(cert_path, _)| std::fs::read(cert_path).ok())
+                .and_then(|pem| tls::fingerprint(&pem).ok())
+        } else {
+            None
+        };
 
         Ok(HttpServerConfigResponse {
             enabled: true,
@@ -178,12 +209,14 @@ pub fn get_http_server_config_inner(config: &ConfigState) -> Result<HttpServerCo
             auth_token: http.auth_token.clone(),
             requires_auth: http.requires_auth(),
             server_url,
+            tls_enabled: http.tls.enabled,
+            tls_fingerprint,
         })
     })
 }
 
 #[tauri::command]
-pub fn get_http_server_config(config: State<ConfigState>) -> Result<HttpServerConfigResponse, String> {
+pub fn get_http_server_config(config: State<Arc<ConfigState>>) -> Result<HttpServerConfigResponse, String> {
     get_http_server_config_inner(&config)
 }
 
@@ -191,21 +224,31 @@ pub fn update_http_server_config_inner(
     config: &ConfigState,
     update: HttpServerConfigUpdate,
 ) -> Result<HttpServerConfigResponse, String> {
-    config.update(|c| {
-        if let Some(port) = update.port {
-            c.http_server.port = port;
-        }
-        if let Some(bind_address) = update.bind_address.clone() {
-            c.http_server.bind_address = bind_address;
-        }
-        c.http_server.ensure_auth_token();
-    })?;
+    let (mut http, base_path) = config.with_config(|c| Ok((c.http_server.clone(), c.resolve_base_path())))?;
+    if let Some(port) = update.port {
+        http.port = port;
+    }
+    if let Some(bind_address) = update.bind_address {
+        http.bind_address = bind_address;
+    }
+    if let Some(tls_enabled) = update.tls_enabled {
+        http.tls.enabled = tls_enabled;
+    }
+    http.ensure_auth_token();
+    // Create the certificate before saving, so a bad cert or key path leaves
+    // TLS off instead of breaking every later server start, and so its
+    // fingerprint can be shown before the server restarts with TLS.
+    if http.tls.enabled {
+        let hosts = tls::self_signed_hosts(&http.bind_address, get_local_ip_address());
+        tls::load_or_create(&http.tls, &base_path, hosts)?;
+    }
+    config.update(|c| c.http_server = http)?;
     get_http_server_config_inner(config)
 }
 
 #[tauri::command]
 pub fn update_http_server_config(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     update: HttpServerConfigUpdate,
 ) -> Result<HttpServerConfigResponse, String> {
@@ -226,7 +269,7 @@ pub fn regenerate_auth_token_inner(config: &ConfigState) -> Result<String, Strin
 
 #[tauri::command]
 pub fn regenerate_auth_token(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
 ) -> Result<String, String> {
     let token = regenerate_auth_token_inner(&config)?;
@@ -257,7 +300,7 @@ pub fn update_global_config_inner(
 
 #[tauri::command]
 pub fn update_global_config(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     update: GlobalConfigUpdate,
 ) -> Result<GlobalConfigResponse, String> {
@@ -273,12 +316,32 @@ pub struct AgentProfileInput {
     pub flags: Vec<String>,
     pub custom_command: Option<String>,
     pub env_vars: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub session_id_pattern: Option<String>,
+    #[serde(default)]
+    pub output_format: OutputFormat,
+    #[serde(default)]
+    pub limits: AgentLimits,
+    #[serde(default)]
+    pub pty: bool,
+    #[serde(default)]
+    pub adapter: Option<AdapterKind>,
+    #[serde(default)]
+    pub template: Option<AdapterTemplate>,
+}
+
+fn validate_session_id_pattern(pattern: Option<&str>) -> Result<(), String> {
+    if let Some(p) = pattern {
+        regex::Regex::new(p).map_err(|e| format!("Invalid session_id_pattern: {e}"))?;
+    }
+    Ok(())
 }
 
 pub fn create_agent_profile_inner(
     config: &ConfigState,
     profile: AgentProfileInput,
 ) -> Result<GlobalConfigResponse, String> {
+    validate_session_id_pattern(profile.session_id_pattern.as_deref())?;
     config.update(|c| {
         if c.agents.contains_key(&profile.name) {
             return;
@@ -290,6 +353,12 @@ pub fn create_agent_profile_inner(
                 flags: profile.flags,
                 custom_command: profile.custom_command,
                 env_vars: profile.env_vars,
+                session_id_pattern: profile.session_id_pattern,
+                output_format: profile.output_format,
+                limits: profile.limits,
+                pty: profile.pty,
+                adapter: profile.adapter,
+                template: profile.template,
             },
         );
     })?;
@@ -298,7 +367,7 @@ pub fn create_agent_profile_inner(
 
 #[tauri::command]
 pub fn create_agent_profile(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     profile: AgentProfileInput,
 ) -> Result<GlobalConfigResponse, String> {
@@ -312,6 +381,7 @@ pub fn update_agent_profile_inner(
     name: &str,
     profile: AgentProfileInput,
 ) -> Result<GlobalConfigResponse, String> {
+    validate_session_id_pattern(profile.session_id_pattern.as_deref())?;
     config.update(|c| {
         if !c.agents.contains_key(name) {
             return;
@@ -324,6 +394,12 @@ pub fn update_agent_profile_inner(
                 flags: profile.flags,
                 custom_command: profile.custom_command,
                 env_vars: profile.env_vars,
+                session_id_pattern: profile.session_id_pattern,
+                output_format: profile.output_format,
+                limits: profile.limits,
+                pty: profile.pty,
+                adapter: profile.adapter,
+                template: profile.template,
             },
         );
     })?;
@@ -332,7 +408,7 @@ pub fn update_agent_profile_inner(
 
 #[tauri::command]
 pub fn update_agent_profile(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     name: String,
     profile: AgentProfileInput,
@@ -354,7 +430,7 @@ pub fn delete_agent_profile_inner(
 
 #[tauri::command]
 pub fn delete_agent_profile(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     name: String,
 ) -> Result<GlobalConfigResponse, String> {
@@ -400,7 +476,7 @@ pub fn get_status_group_defaults_inner(
 
 #[tauri::command]
 pub fn get_status_group_defaults(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
 ) -> Result<StatusGroupDefaultsResponse, String> {
     get_status_group_defaults_inner(&config)
 }
@@ -426,7 +502,7 @@ pub fn update_status_group_defaults_inner(
 
 #[tauri::command]
 pub fn update_status_group_defaults(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     status_group: String,
     group_config: StatusGroupConfigInput,
@@ -436,7 +512,7 @@ pub fn update_status_group_defaults(
     Ok(result)
 }
 
-fn get_local_ip_address() -> Option<String> {
+pub(crate) fn get_local_ip_address() -> Option<String> {
     use std::net::UdpSocket;
     let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
     socket.connect("8.8.8.8:80").ok()?;
@@ -455,6 +531,7 @@ pub fn get_local_ip() -> Result<String, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::config_state;
 
     #[test]
     fn test_config_state_load_and_read() {
@@ -491,4 +568,29 @@ mod tests {
         let reloaded = GlobalConfig::load(&config_path).unwrap();
         assert_eq!(reloaded.defaults.last_project_id, "test-123");
     }
+
+    #[test]
+    fn test_tls_stays_off_when_certificate_fails_to_load() {
+        let (config, dir) = config_state();
+        let missing = dir.path().join("missing.pem").to_string_lossy().to_string();
+        config
+            .update(|c| {
+                c.http_server.tls.cert_path = Some(missing.clone());
+                c.http_server.tls.key_path = Some(missing.clone());
+            })
+            .unwrap();
+
+        let update = HttpServerConfigUpdate {
+            enabled: None,
+            port: Some(4000),
+            bind_address: None,
+            tls_enabled: Some(true),
+        };
+        assert!(update_http_server_config_inner(&config, update).is_err());
+
+        let saved = ConfigState::load_from(dir.path().join("config.toml")).unwrap();
+        let http = saved.with_config(|c| Ok(c.http_server.clone())).unwrap();
+        assert!(!http.tls.enabled);
+        assert_ne!(http.port, 4000);
+    }
 }
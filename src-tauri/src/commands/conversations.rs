@@ -96,7 +96,7 @@ pub fn create_conversation_inner(
 
 #[tauri::command]
 pub fn create_conversation(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     card_id: String,
@@ -135,7 +135,7 @@ pub fn list_conversations_inner(
 
 #[tauri::command]
 pub fn list_conversations(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_id: String,
     card_id: String,
 ) -> Result<Vec<Conversation>, String> {
@@ -190,7 +190,7 @@ pub fn create_message_inner(
 
 #[tauri::command]
 pub fn create_message(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     conversation_id: String,
@@ -215,7 +215,7 @@ pub fn list_messages_inner(
     db.with_conn(|conn| {
         let mut stmt = conn
             .prepare(&format!(
-                "{MESSAGE_SELECT} WHERE conversation_id = ?1 ORDER BY timestamp"
+                "{MESSAGE_SELECT} WHERE conversation_id = ?1 ORDER BY timestamp, rowid"
             ))
             .map_err(|e| format!("Failed to prepare query: {e}"))?;
 
@@ -230,7 +230,7 @@ pub fn list_messages_inner(
 
 #[tauri::command]
 pub fn list_messages(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_id: String,
     conversation_id: String,
 ) -> Result<Vec<ConversationMessage>, String> {
@@ -263,7 +263,7 @@ pub fn count_conversation_messages_inner(
 
 #[tauri::command]
 pub fn count_conversation_messages(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_id: String,
     conversation_ids: Vec<String>,
 ) -> Result<Vec<(String, i32)>, String> {
@@ -376,7 +376,7 @@ mod tests {
 
             let mut stmt = conn
                 .prepare(&format!(
-                    "{MESSAGE_SELECT} WHERE conversation_id = ?1 ORDER BY timestamp"
+                    "{MESSAGE_SELECT} WHERE conversation_id = ?1 ORDER BY timestamp, rowid"
                 ))
                 .unwrap();
             let msgs: Vec<ConversationMessage> = stmt
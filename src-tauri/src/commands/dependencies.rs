@@ -0,0 +1,330 @@
+use std::sync::Arc;
+
+use rusqlite::Connection;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::commands::cards::{row_to_card, CardWithStatus, CARD_SELECT};
+use crate::commands::config::ConfigState;
+use crate::commands::projects::open_project_db;
+use crate::executor::{EventBus, MaestroEvent};
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct CardDependencies {
+    /// Cards this card waits on.
+    pub blocked_by: Vec<CardWithStatus>,
+    /// Cards waiting on this card.
+    pub blocks: Vec<CardWithStatus>,
+}
+
+fn card_exists(conn: &Connection, project_id: &str, card_id: &str) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT COUNT(*) > 0 FROM cards WHERE id = ?1 AND project_id = ?2",
+        rusqlite::params![card_id, project_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Failed to verify card: {e}"))
+}
+
+/// Whether `from` already depends on `to`, directly or transitively.
+fn depends_on(conn: &Connection, from: &str, to: &str) -> Result<bool, String> {
+    conn.query_row(
+        "WITH RECURSIVE reachable(id) AS ( \
+           SELECT ?1 \
+           UNION \
+           SELECT d.depends_on_id FROM card_dependencies d JOIN reachable r ON d.card_id = r.id \
+         ) \
+         SELECT COUNT(*) > 0 FROM reachable WHERE id = ?2",
+        rusqlite::params![from, to],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Failed to check for dependency cycle: {e}"))
+}
+
+fn query_cards(conn: &Connection, filter: &str, card_id: &str) -> Result<Vec<CardWithStatus>, String> {
+    let mut stmt = conn
+        .prepare(&format!("{CARD_SELECT} WHERE {filter} ORDER BY c.title"))
+        .map_err(|e| format!("Failed to prepare query: {e}"))?;
+
+    let rows = stmt
+        .query_map(rusqlite::params![card_id], row_to_card)
+        .map_err(|e| format!("Failed to query cards: {e}"))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read card row: {e}"))
+}
+
+fn query_dependencies(conn: &Connection, card_id: &str) -> Result<CardDependencies, String> {
+    Ok(CardDependencies {
+        blocked_by: query_cards(
+            conn,
+            "c.id IN (SELECT depends_on_id FROM card_dependencies WHERE card_id = ?1)",
+            card_id,
+        )?,
+        blocks: query_cards(
+            conn,
+            "c.id IN (SELECT card_id FROM card_dependencies WHERE depends_on_id = ?1)",
+            card_id,
+        )?,
+    })
+}
+
+/// Titles of the cards `card_id` depends on that aren't Completed yet.
+pub(crate) fn unfinished_blockers(conn: &Connection, card_id: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT b.title FROM card_dependencies d \
+             JOIN cards b ON b.id = d.depends_on_id \
+             JOIN statuses s ON s.id = b.status_id \
+             WHERE d.card_id = ?1 AND s.\"group\" != 'Completed' \
+             ORDER BY b.title",
+        )
+        .map_err(|e| format!("Failed to prepare query: {e}"))?;
+
+    let rows = stmt
+        .query_map(rusqlite::params![card_id], |row| row.get(0))
+        .map_err(|e| format!("Failed to query blockers: {e}"))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read blocker row: {e}"))
+}
+
+/// Cards waiting on `card_id` that have nothing left blocking them. Empty
+/// unless `card_id` itself is Completed.
+pub(crate) fn unblocked_dependents(conn: &Connection, card_id: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT d.card_id FROM card_dependencies d \
+             JOIN cards c ON c.id = d.depends_on_id \
+             JOIN statuses s ON s.id = c.status_id \
+             WHERE d.depends_on_id = ?1 AND s.\"group\" = 'Completed' \
+               AND NOT EXISTS ( \
+                 SELECT 1 FROM card_dependencies o \
+                 JOIN cards b ON b.id = o.depends_on_id \
+                 JOIN statuses bs ON bs.id = b.status_id \
+                 WHERE o.card_id = d.card_id AND bs.\"group\" != 'Completed' \
+               )",
+        )
+        .map_err(|e| format!("Failed to prepare query: {e}"))?;
+
+    let rows = stmt
+        .query_map(rusqlite::params![card_id], |row| row.get(0))
+        .map_err(|e| format!("Failed to query dependents: {e}"))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read dependent row: {e}"))
+}
+
+fn add_dependency(
+    conn: &Connection,
+    project_id: &str,
+    card_id: &str,
+    depends_on_id: &str,
+) -> Result<(), String> {
+    if card_id == depends_on_id {
+        return Err("A card cannot depend on itself".to_string());
+    }
+    for id in [card_id, depends_on_id] {
+        if !card_exists(conn, project_id, id)? {
+            return Err(format!("Card {id} not found in project"));
+        }
+    }
+    if depends_on(conn, depends_on_id, card_id)? {
+        return Err("Adding this dependency would create a cycle".to_string());
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT OR IGNORE INTO card_dependencies (card_id, depends_on_id, created_at) VALUES (?1, ?2, ?3)",
+        rusqlite::params![card_id, depends_on_id, now],
+    )
+    .map_err(|e| format!("Failed to add dependency: {e}"))?;
+    Ok(())
+}
+
+pub fn add_card_dependency_inner(
+    config: &ConfigState,
+    project_id: &str,
+    card_id: &str,
+    depends_on_id: &str,
+) -> Result<CardDependencies, String> {
+    let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
+    let db = open_project_db(&base_path, project_id)?;
+
+    db.with_conn(|conn| {
+        add_dependency(conn, project_id, card_id, depends_on_id)?;
+        query_dependencies(conn, card_id)
+    })
+}
+
+#[tauri::command]
+pub fn add_card_dependency(
+    config: State<Arc<ConfigState>>,
+    event_bus: State<Arc<EventBus>>,
+    project_id: String,
+    card_id: String,
+    depends_on_id: String,
+) -> Result<CardDependencies, String> {
+    let result = add_card_dependency_inner(&config, &project_id, &card_id, &depends_on_id)?;
+    event_bus.emit_maestro(MaestroEvent::CardsChanged {
+        project_id: project_id.clone(),
+    });
+    Ok(result)
+}
+
+pub fn remove_card_dependency_inner(
+    config: &ConfigState,
+    project_id: &str,
+    card_id: &str,
+    depends_on_id: &str,
+) -> Result<CardDependencies, String> {
+    let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
+    let db = open_project_db(&base_path, project_id)?;
+
+    db.with_conn(|conn| {
+        if !card_exists(conn, project_id, card_id)? {
+            return Err("Card not found".to_string());
+        }
+        let removed = conn
+            .execute(
+                "DELETE FROM card_dependencies WHERE card_id = ?1 AND depends_on_id = ?2",
+                rusqlite::params![card_id, depends_on_id],
+            )
+            .map_err(|e| format!("Failed to remove dependency: {e}"))?;
+        if removed == 0 {
+            return Err(format!("Card {card_id} does not depend on {depends_on_id}"));
+        }
+        query_dependencies(conn, card_id)
+    })
+}
+
+#[tauri::command]
+pub fn remove_card_dependency(
+    config: State<Arc<ConfigState>>,
+    event_bus: State<Arc<EventBus>>,
+    project_id: String,
+    card_id: String,
+    depends_on_id: String,
+) -> Result<CardDependencies, String> {
+    let result = remove_card_dependency_inner(&config, &project_id, &card_id, &depends_on_id)?;
+    event_bus.emit_maestro(MaestroEvent::CardsChanged {
+        project_id: project_id.clone(),
+    });
+    Ok(result)
+}
+
+pub fn list_card_dependencies_inner(
+    config: &ConfigState,
+    project_id: &str,
+    card_id: &str,
+) -> Result<CardDependencies, String> {
+    let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
+    let db = open_project_db(&base_path, project_id)?;
+
+    db.with_conn(|conn| {
+        if !card_exists(conn, project_id, card_id)? {
+            return Err("Card not found".to_string());
+        }
+        query_dependencies(conn, card_id)
+    })
+}
+
+#[tauri::command]
+pub fn list_card_dependencies(
+    config: State<Arc<ConfigState>>,
+    project_id: String,
+    card_id: String,
+) -> Result<CardDependencies, String> {
+    list_card_dependencies_inner(&config, &project_id, &card_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{insert_card, setup_test_db, status_id};
+
+    fn complete(conn: &Connection, project_id: &str, card_id: &str) {
+        conn.execute(
+            "UPDATE cards SET status_id = ?1 WHERE id = ?2",
+            rusqlite::params![status_id(conn, project_id, "Completed"), card_id],
+        )
+        .unwrap();
+    }
+
+    fn is_blocked(conn: &Connection, card_id: &str) -> bool {
+        conn.query_row(&format!("{CARD_SELECT} WHERE c.id = ?1"), rusqlite::params![card_id], row_to_card)
+            .unwrap()
+            .blocked
+    }
+
+    #[test]
+    fn test_rejects_self_and_cyclic_dependencies() {
+        let (_dir, db, project_id) = setup_test_db();
+        db.with_conn(|conn| {
+            let a = insert_card(conn, &project_id, "A", "");
+            let b = insert_card(conn, &project_id, "B", "");
+            let c = insert_card(conn, &project_id, "C", "");
+
+            assert!(add_dependency(conn, &project_id, &a, &a).is_err());
+            add_dependency(conn, &project_id, &b, &a)?;
+            add_dependency(conn, &project_id, &c, &b)?;
+            assert!(add_dependency(conn, &project_id, &a, &c).is_err());
+            assert!(add_dependency(conn, &project_id, &a, "missing").is_err());
+            // Adding an existing edge again is a no-op.
+            add_dependency(conn, &project_id, &c, &b)?;
+
+            let deps = query_dependencies(conn, &b)?;
+            assert_eq!(deps.blocked_by.len(), 1);
+            assert_eq!(deps.blocked_by[0].id, a);
+            assert_eq!(deps.blocks.len(), 1);
+            assert_eq!(deps.blocks[0].id, c);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_blocked_until_every_blocker_completes() {
+        let (_dir, db, project_id) = setup_test_db();
+        db.with_conn(|conn| {
+            let a = insert_card(conn, &project_id, "A", "");
+            let b = insert_card(conn, &project_id, "B", "");
+            let c = insert_card(conn, &project_id, "C", "");
+            add_dependency(conn, &project_id, &c, &a)?;
+            add_dependency(conn, &project_id, &c, &b)?;
+
+            assert!(is_blocked(conn, &c));
+            assert!(!is_blocked(conn, &a));
+            assert_eq!(unfinished_blockers(conn, &c)?, vec!["A", "B"]);
+
+            complete(conn, &project_id, &a);
+            assert!(is_blocked(conn, &c));
+            assert_eq!(unfinished_blockers(conn, &c)?, vec!["B"]);
+            assert!(unblocked_dependents(conn, &a)?.is_empty());
+
+            complete(conn, &project_id, &b);
+            assert!(!is_blocked(conn, &c));
+            assert_eq!(unblocked_dependents(conn, &b)?, vec![c.clone()]);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_deleting_a_card_removes_its_edges() {
+        let (_dir, db, project_id) = setup_test_db();
+        db.with_conn(|conn| {
+            let a = insert_card(conn, &project_id, "A", "");
+            let b = insert_card(conn, &project_id, "B", "");
+            add_dependency(conn, &project_id, &b, &a)?;
+
+            conn.execute("DELETE FROM cards WHERE id = ?1", rusqlite::params![a])
+                .map_err(|e| format!("{e}"))?;
+            assert!(!is_blocked(conn, &b));
+            assert!(query_dependencies(conn, &b)?.blocked_by.is_empty());
+            Ok(())
+        })
+        .unwrap();
+    }
+}
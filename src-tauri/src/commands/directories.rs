@@ -100,7 +100,7 @@ pub fn add_linked_directory_inner(
 
 #[tauri::command]
 pub fn add_linked_directory(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     path: String,
@@ -139,7 +139,7 @@ pub fn remove_linked_directory_inner(
 
 #[tauri::command]
 pub fn remove_linked_directory(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     id: String,
@@ -178,7 +178,7 @@ pub fn list_linked_directories_inner(
 
 #[tauri::command]
 pub fn list_linked_directories(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_id: String,
 ) -> Result<Vec<LinkedDirectory>, String> {
     list_linked_directories_inner(&config, &project_id)
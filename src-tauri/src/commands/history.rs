@@ -0,0 +1,300 @@
+use std::sync::Arc;
+
+use rusqlite::Connection;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::State;
+
+use crate::commands::config::ConfigState;
+use crate::commands::projects::open_project_db;
+
+/// Who made a change to a card.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Actor {
+    /// The desktop app, or an HTTP caller using the primary auth token.
+    User,
+    /// An agent, through the `maestro` CLI. `None` when no workspace is
+    /// running for the card.
+    Agent { workspace_id: Option<String> },
+    /// An HTTP caller using a named API token.
+    Token { id: String, name: String },
+}
+
+impl Actor {
+    fn columns(&self) -> (&'static str, Option<&str>, Option<&str>) {
+        match self {
+            Actor::User => ("user", None, None),
+            Actor::Agent { workspace_id } => ("agent", workspace_id.as_deref(), None),
+            Actor::Token { id, name } => ("token", Some(id), Some(name)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct CardEvent {
+    pub id: i64,
+    pub card_id: String,
+    /// `created`, `updated`, `moved`, `deleted`, `sent_back`, `approved` or
+    /// `log`.
+    pub event_type: String,
+    /// `user`, `agent` or `token`.
+    pub actor_type: String,
+    /// The agent workspace or API token id.
+    pub actor_id: Option<String>,
+    /// The API token's name.
+    pub actor_name: Option<String>,
+    pub old_value: Option<Value>,
+    pub new_value: Option<Value>,
+    pub created_at: String,
+}
+
+fn row_to_event(row: &rusqlite::Row) -> Result<CardEvent, rusqlite::Error> {
+    let json = |text: Option<String>| text.and_then(|t| serde_json::from_str(&t).ok());
+    Ok(CardEvent {
+        id: row.get(0)?,
+        card_id: row.get(1)?,
+        event_type: row.get(2)?,
+        actor_type: row.get(3)?,
+        actor_id: row.get(4)?,
+        actor_name: row.get(5)?,
+        old_value: json(row.get(6)?),
+        new_value: json(row.get(7)?),
+        created_at: row.get(8)?,
+    })
+}
+
+pub(crate) fn record_card_event(
+    conn: &Connection,
+    project_id: &str,
+    card_id: &str,
+    actor: &Actor,
+    event_type: &str,
+    old_value: Option<Value>,
+    new_value: Option<Value>,
+) -> Result<(), String> {
+    let (actor_type, actor_id, actor_name) = actor.columns();
+    let now = chrono::Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO card_events (project_id, card_id, event_type, actor_type, actor_id, actor_name, old_value, new_value, created_at) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        rusqlite::params![
+            project_id,
+            card_id,
+            event_type,
+            actor_type,
+            actor_id,
+            actor_name,
+            old_value.map(|v| v.to_string()),
+            new_value.map(|v| v.to_string()),
+            now,
+        ],
+    )
+    .map_err(|e| format!("Failed to record card event: {e}"))?;
+    Ok(())
+}
+
+/// A status as it appears in an event: its id and, for readability, its name.
+pub(crate) fn status_value(conn: &Connection, status_id: &str) -> Value {
+    let name: Option<String> = conn
+        .query_row(
+            "SELECT name FROM statuses WHERE id = ?1",
+            rusqlite::params![status_id],
+            |row| row.get(0),
+        )
+        .ok();
+    serde_json::json!({ "status_id": status_id, "status": name })
+}
+
+/// The card's running agent workspace, which is who a `maestro` CLI call
+/// comes from. An agent waiting on a question is paused but still live.
+pub(crate) fn running_workspace_id(conn: &Connection, card_id: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT id FROM agent_workspaces WHERE card_id = ?1 AND status IN ('running', 'paused') \
+         ORDER BY attached_at DESC LIMIT 1",
+        rusqlite::params![card_id],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+pub(crate) fn agent_actor(conn: &Connection, card_id: &str) -> Actor {
+    Actor::Agent {
+        workspace_id: running_workspace_id(conn, card_id),
+    }
+}
+
+pub fn get_card_history_inner(
+    config: &ConfigState,
+    project_id: &str,
+    card_id: &str,
+) -> Result<Vec<CardEvent>, String> {
+    let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
+    let db = open_project_db(&base_path, project_id)?;
+
+    db.with_conn(|conn| {
+        let mut stmt = conn
+            .prepare(
+                "SELECT id, card_id, event_type, actor_type, actor_id, actor_name, old_value, new_value, created_at \
+                 FROM card_events WHERE card_id = ?1 AND project_id = ?2 ORDER BY id",
+            )
+            .map_err(|e| format!("Failed to prepare query: {e}"))?;
+
+        let rows = stmt
+            .query_map(rusqlite::params![card_id, project_id], row_to_event)
+            .map_err(|e| format!("Failed to query card history: {e}"))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to read card event: {e}"))
+    })
+}
+
+#[tauri::command]
+pub fn get_card_history(
+    config: State<Arc<ConfigState>>,
+    project_id: String,
+    card_id: String,
+) -> Result<Vec<CardEvent>, String> {
+    get_card_history_inner(&config, &project_id, &card_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cards::{
+        create_card_inner, delete_card_inner, move_card_inner, update_card_inner, CardInput,
+    };
+    use crate::commands::projects::create_project_inner;
+    use crate::commands::review::approve_card_inner;
+    use crate::fixtures::{config_state, status_id};
+
+    fn card_input(title: &str) -> CardInput {
+        CardInput {
+            title: title.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_card_mutations_are_recorded_with_actor() {
+        let (config, _dir) = config_state();
+        let project_id = create_project_inner(&config, "Test").unwrap().id;
+        let token = Actor::Token {
+            id: "t1".to_string(),
+            name: "ci".to_string(),
+        };
+
+        let card = create_card_inner(&config, &project_id, card_input("Draft"), &Actor::User).unwrap();
+        update_card_inner(&config, &project_id, &card.id, Some("Final".to_string()), None, None, &token).unwrap();
+        // Nothing changed, so nothing to record.
+        update_card_inner(&config, &project_id, &card.id, Some("Final".to_string()), None, None, &token).unwrap();
+        let base_path = config.with_config(|c| Ok(c.resolve_base_path())).unwrap();
+        let (started, completed) = open_project_db(&base_path, &project_id)
+            .unwrap()
+            .with_conn(|conn| Ok((status_id(conn, &project_id, "Started"), status_id(conn, &project_id, "Completed"))))
+            .unwrap();
+        move_card_inner(&config, &project_id, &card.id, &started, 0, &Actor::User).unwrap();
+        let agent = Actor::Agent {
+            workspace_id: Some("w1".to_string()),
+        };
+        approve_card_inner(&config, &project_id, &card.id, &completed, &agent).unwrap();
+
+        let history = get_card_history_inner(&config, &project_id, &card.id).unwrap();
+        let types: Vec<&str> = history.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(types, vec!["created", "updated", "moved", "approved"]);
+
+        let updated = &history[1];
+        assert_eq!(updated.actor_type, "token");
+        assert_eq!(updated.actor_id.as_deref(), Some("t1"));
+        assert_eq!(updated.actor_name.as_deref(), Some("ci"));
+        assert_eq!(updated.old_value, Some(serde_json::json!({ "title": "Draft" })));
+        assert_eq!(updated.new_value, Some(serde_json::json!({ "title": "Final" })));
+
+        let moved = &history[2];
+        assert_eq!(moved.actor_type, "user");
+        assert_eq!(moved.old_value.as_ref().unwrap()["status_id"], card.status_id);
+        assert_eq!(moved.new_value.as_ref().unwrap()["status_id"], started);
+        assert_eq!(history[3].actor_id.as_deref(), Some("w1"));
+
+        // History outlives the card.
+        delete_card_inner(&config, &project_id, &card.id, &Actor::User).unwrap();
+        let history = get_card_history_inner(&config, &project_id, &card.id).unwrap();
+        assert_eq!(history.last().unwrap().event_type, "deleted");
+    }
+
+    #[test]
+    fn test_events_are_append_only() {
+        let (config, _dir) = config_state();
+        let project_id = create_project_inner(&config, "Test").unwrap().id;
+        let card = create_card_inner(&config, &project_id, card_input("Card"), &Actor::User).unwrap();
+
+        let base_path = config.with_config(|c| Ok(c.resolve_base_path())).unwrap();
+        open_project_db(&base_path, &project_id)
+            .unwrap()
+            .with_conn(|conn| {
+                assert!(conn.execute("UPDATE card_events SET actor_type = 'agent'", []).is_err());
+                assert!(conn.execute("DELETE FROM card_events", []).is_err());
+                assert_eq!(
+                    agent_actor(conn, &card.id),
+                    Actor::Agent { workspace_id: None }
+                );
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(get_card_history_inner(&config, &project_id, &card.id).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_paused_agent_is_still_the_actor() {
+        let (config, _dir) = config_state();
+        let project_id = create_project_inner(&config, "Test").unwrap().id;
+        let card = create_card_inner(&config, &project_id, card_input("Card"), &Actor::User).unwrap();
+
+        let base_path = config.with_config(|c| Ok(c.resolve_base_path())).unwrap();
+        open_project_db(&base_path, &project_id)
+            .unwrap()
+            .with_conn(|conn| {
+                conn.execute(
+                    "INSERT INTO agent_workspaces (id, card_id, agent_type, status, attached_at) \
+                     VALUES ('w1', ?1, 'claude-code', 'paused', ?2)",
+                    rusqlite::params![card.id, chrono::Utc::now().to_rfc3339()],
+                )
+                .map_err(|e| format!("{e}"))?;
+                assert_eq!(
+                    agent_actor(conn, &card.id),
+                    Actor::Agent {
+                        workspace_id: Some("w1".to_string())
+                    }
+                );
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_mutation_rolls_back_when_event_fails() {
+        let (config, _dir) = config_state();
+        let project_id = create_project_inner(&config, "Test").unwrap().id;
+        let card = create_card_inner(&config, &project_id, card_input("Draft"), &Actor::User).unwrap();
+
+        let base_path = config.with_config(|c| Ok(c.resolve_base_path())).unwrap();
+        open_project_db(&base_path, &project_id)
+            .unwrap()
+            .with_conn(|conn| {
+                conn.execute_batch(
+                    "CREATE TRIGGER reject_events BEFORE INSERT ON card_events BEGIN \
+                       SELECT RAISE(ABORT, 'rejected'); \
+                     END;",
+                )
+                .map_err(|e| format!("{e}"))
+            })
+            .unwrap();
+
+        let result = update_card_inner(&config, &project_id, &card.id, Some("Final".to_string()), None, None, &Actor::User);
+        assert!(result.is_err());
+        assert!(delete_card_inner(&config, &project_id, &card.id, &Actor::User).is_err());
+
+        let card = crate::commands::cards::get_card_inner(&config, &project_id, &card.id).unwrap();
+        assert_eq!(card.title, "Draft");
+    }
+}
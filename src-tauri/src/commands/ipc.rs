@@ -3,21 +3,26 @@ use std::sync::Arc;
 use tauri::{AppHandle, State};
 
 use crate::commands::config::ConfigState;
-use crate::executor::EventBus;
+use crate::executor::{AgentRegistry, EventBus};
 use crate::ipc::server::IpcServer;
 
 #[tauri::command]
 pub async fn start_ipc_server(
     app: AppHandle,
-    config: State<'_, ConfigState>,
+    config: State<'_, Arc<ConfigState>>,
     event_bus: State<'_, Arc<EventBus>>,
+    registry: State<'_, Arc<AgentRegistry>>,
     ipc_server: State<'_, Arc<IpcServer>>,
     project_id: String,
 ) -> Result<String, String> {
-    let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
-
     let socket_path = ipc_server
-        .start(app, Some(Arc::clone(&event_bus)), base_path, project_id)
+        .start(
+            Some(app),
+            Some(Arc::clone(&event_bus)),
+            Arc::clone(&config),
+            Arc::clone(&registry),
+            project_id,
+        )
         .await?;
 
     Ok(socket_path.to_string_lossy().to_string())
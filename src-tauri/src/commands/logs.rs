@@ -0,0 +1,212 @@
+use std::sync::Arc;
+
+use rusqlite::Connection;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::State;
+
+use crate::commands::config::ConfigState;
+use crate::commands::projects::open_project_db;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        }
+    }
+
+    pub fn parse(level: &str) -> Result<Self, String> {
+        match level {
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "warn" => Ok(LogLevel::Warn),
+            "error" => Ok(LogLevel::Error),
+            other => Err(format!(
+                "Invalid log level: {other}. Must be 'debug', 'info', 'warn' or 'error'"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct CardLog {
+    pub id: i64,
+    pub card_id: String,
+    /// The agent workspace that logged it, if one was running.
+    pub workspace_id: Option<String>,
+    pub level: LogLevel,
+    pub message: String,
+    /// Structured fields passed with `maestro log --data`.
+    pub data: Option<Value>,
+    pub created_at: String,
+}
+
+fn row_to_log(row: &rusqlite::Row) -> Result<CardLog, rusqlite::Error> {
+    let level: String = row.get(3)?;
+    let data: Option<String> = row.get(5)?;
+    Ok(CardLog {
+        id: row.get(0)?,
+        card_id: row.get(1)?,
+        workspace_id: row.get(2)?,
+        // The column's CHECK constraint only allows known levels.
+        level: LogLevel::parse(&level).unwrap_or(LogLevel::Info),
+        message: row.get(4)?,
+        data: data.and_then(|d| serde_json::from_str(&d).ok()),
+        created_at: row.get(6)?,
+    })
+}
+
+const LOG_SELECT: &str = "\
+    SELECT id, card_id, workspace_id, level, message, data, created_at \
+    FROM card_logs";
+
+pub(crate) fn insert_card_log(
+    conn: &Connection,
+    card_id: &str,
+    workspace_id: Option<&str>,
+    level: LogLevel,
+    message: &str,
+    data: Option<&Value>,
+) -> Result<CardLog, String> {
+    let now = chrono::Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO card_logs (card_id, workspace_id, level, message, data, created_at) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+            card_id,
+            workspace_id,
+            level.as_str(),
+            message,
+            data.map(|d| d.to_string()),
+            now,
+        ],
+    )
+    .map_err(|e| format!("Failed to save log entry: {e}"))?;
+
+    conn.query_row(
+        &format!("{LOG_SELECT} WHERE id = ?1"),
+        rusqlite::params![conn.last_insert_rowid()],
+        row_to_log,
+    )
+    .map_err(|e| format!("Failed to read log entry: {e}"))
+}
+
+pub(crate) fn query_card_logs(
+    conn: &Connection,
+    card_id: &str,
+    min_level: Option<LogLevel>,
+    workspace_id: Option<&str>,
+) -> Result<Vec<CardLog>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "{LOG_SELECT} WHERE card_id = ?1 AND (?2 IS NULL OR workspace_id = ?2) ORDER BY id"
+        ))
+        .map_err(|e| format!("Failed to prepare query: {e}"))?;
+
+    let rows = stmt
+        .query_map(rusqlite::params![card_id, workspace_id], row_to_log)
+        .map_err(|e| format!("Failed to query logs: {e}"))?;
+
+    let logs = rows
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read log row: {e}"))?;
+    Ok(match min_level {
+        Some(min) => logs.into_iter().filter(|l| l.level >= min).collect(),
+        None => logs,
+    })
+}
+
+/// A card's log entries, oldest first. `min_level` drops anything less
+/// severe; `workspace_id` keeps only one agent run's entries.
+pub fn list_card_logs_inner(
+    config: &ConfigState,
+    project_id: &str,
+    card_id: &str,
+    min_level: Option<LogLevel>,
+    workspace_id: Option<&str>,
+) -> Result<Vec<CardLog>, String> {
+    let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
+    let db = open_project_db(&base_path, project_id)?;
+
+    db.with_conn(|conn| {
+        let card_exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM cards WHERE id = ?1 AND project_id = ?2",
+                rusqlite::params![card_id, project_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to verify card: {e}"))?;
+        if !card_exists {
+            return Err("Card not found".to_string());
+        }
+        query_card_logs(conn, card_id, min_level, workspace_id)
+    })
+}
+
+#[tauri::command]
+pub fn list_card_logs(
+    config: State<Arc<ConfigState>>,
+    project_id: String,
+    card_id: String,
+    min_level: Option<LogLevel>,
+    workspace_id: Option<String>,
+) -> Result<Vec<CardLog>, String> {
+    list_card_logs_inner(&config, &project_id, &card_id, min_level, workspace_id.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(LogLevel::parse("warn").unwrap(), LogLevel::Warn);
+        assert!(LogLevel::parse("fatal").is_err());
+        assert!(LogLevel::Debug < LogLevel::Info && LogLevel::Warn < LogLevel::Error);
+    }
+
+    #[test]
+    fn test_logs_are_filtered_by_level_and_workspace() {
+        let (_dir, db, project_id) = fixtures::setup_test_db();
+        db.with_conn(|conn| {
+            let card_id = fixtures::insert_card(conn, &project_id, "Card", "");
+            let data = serde_json::json!({ "tests": 42, "failed": 0 });
+            let saved = insert_card_log(conn, &card_id, Some("w1"), LogLevel::Info, "Tests pass", Some(&data))?;
+            assert_eq!(saved.data, Some(data));
+            insert_card_log(conn, &card_id, Some("w1"), LogLevel::Debug, "Reading config", None)?;
+            insert_card_log(conn, &card_id, Some("w2"), LogLevel::Error, "Build broke", None)?;
+            insert_card_log(conn, &card_id, None, LogLevel::Warn, "Skipped lint", None)?;
+
+            let messages = |logs: Vec<CardLog>| logs.into_iter().map(|l| l.message).collect::<Vec<_>>();
+            assert_eq!(query_card_logs(conn, &card_id, None, None)?.len(), 4);
+            assert_eq!(
+                messages(query_card_logs(conn, &card_id, Some(LogLevel::Warn), None)?),
+                vec!["Build broke", "Skipped lint"]
+            );
+            assert_eq!(
+                messages(query_card_logs(conn, &card_id, None, Some("w1"))?),
+                vec!["Tests pass", "Reading config"]
+            );
+
+            conn.execute("DELETE FROM cards WHERE id = ?1", rusqlite::params![card_id])
+                .map_err(|e| format!("{e}"))?;
+            assert!(query_card_logs(conn, &card_id, None, None)?.is_empty());
+            Ok(())
+        })
+        .unwrap();
+    }
+}
@@ -3,10 +3,120 @@ pub mod artifacts;
 pub mod cards;
 pub mod config;
 pub mod conversations;
+pub mod dependencies;
 pub mod directories;
+pub mod history;
 pub mod ipc;
+pub mod logs;
 pub mod projects;
 pub mod questions;
 pub mod review;
+pub mod search;
 pub mod statuses;
+pub mod tokens;
 pub mod worktrees;
+
+/// Every command the frontend can call, as `module::function`. Expands to
+/// `$callback! { config::get_global_config, ... }`, so the Tauri handler and
+/// the HTTP API are built from the same list.
+macro_rules! for_each_command {
+    ($callback:ident) => {
+        $callback! {
+            config::get_global_config,
+            config::update_global_config,
+            config::set_last_project,
+            config::resolve_config,
+            config::create_agent_profile,
+            config::update_agent_profile,
+            config::delete_agent_profile,
+            config::get_status_group_defaults,
+            config::update_status_group_defaults,
+            config::get_http_server_config,
+            config::update_http_server_config,
+            config::regenerate_auth_token,
+            config::get_local_ip,
+            tokens::create_api_token,
+            tokens::list_api_tokens,
+            tokens::revoke_api_token,
+            projects::create_project,
+            projects::get_project,
+            projects::list_projects,
+            projects::update_project,
+            projects::delete_project,
+            statuses::list_statuses,
+            statuses::create_status,
+            statuses::update_status,
+            statuses::set_status_auto_run,
+            statuses::delete_status,
+            statuses::reorder_statuses,
+            cards::create_card,
+            cards::get_card,
+            cards::update_card,
+            cards::delete_card,
+            cards::list_cards,
+            cards::list_sub_cards,
+            cards::move_card,
+            cards::reorder_cards,
+            dependencies::add_card_dependency,
+            dependencies::remove_card_dependency,
+            dependencies::list_card_dependencies,
+            history::get_card_history,
+            logs::list_card_logs,
+            questions::create_question,
+            questions::list_questions,
+            questions::resolve_question,
+            questions::unresolve_question,
+            questions::delete_question,
+            questions::count_unresolved_questions,
+            artifacts::create_artifact,
+            artifacts::read_artifact,
+            artifacts::update_artifact,
+            artifacts::delete_artifact,
+            artifacts::list_artifacts,
+            directories::add_linked_directory,
+            directories::remove_linked_directory,
+            directories::list_linked_directories,
+            conversations::create_conversation,
+            conversations::list_conversations,
+            conversations::create_message,
+            conversations::list_messages,
+            conversations::count_conversation_messages,
+            agent::launch_agent,
+            agent::send_agent_input,
+            agent::write_agent_terminal,
+            agent::resize_agent_terminal,
+            agent::stop_agent,
+            agent::resume_agent,
+            agent::list_workspaces,
+            agent::get_workspace,
+            agent::list_running_workspaces,
+            agent::stop_all_agents,
+            agent::archive_card_workspaces,
+            agent::set_launch_priority,
+            worktrees::generate_branch_name,
+            worktrees::create_worktree,
+            worktrees::check_worktree_exists,
+            worktrees::get_card_worktree,
+            worktrees::get_claude_worktree_path,
+            ipc::start_ipc_server,
+            ipc::stop_ipc_server,
+            review::get_changed_files,
+            review::get_file_diff,
+            review::send_back_card,
+            review::approve_card,
+            review::create_pr,
+            review::get_review_count,
+            search::search,
+        }
+    };
+}
+pub(crate) use for_each_command;
+
+macro_rules! command_names {
+    ($($module:ident::$command:ident),* $(,)?) => {
+        &[$(stringify!($command)),*]
+    };
+}
+
+/// Names of all commands in [`for_each_command`].
+pub const COMMAND_NAMES: &[&str] = for_each_command!(command_names);
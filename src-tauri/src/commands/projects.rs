@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
@@ -7,7 +8,7 @@ use crate::commands::config::ConfigState;
 use crate::db::DbConnection;
 use crate::executor::{EventBus, MaestroEvent};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct Project {
     pub id: String,
     pub name: String,
@@ -17,7 +18,7 @@ pub struct Project {
     pub updated_at: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct ProjectSummary {
     pub id: String,
     pub name: String,
@@ -161,7 +162,7 @@ pub fn create_project_inner(
 
 #[tauri::command]
 pub fn create_project(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     name: String,
 ) -> Result<Project, String> {
@@ -199,7 +200,7 @@ pub fn get_project_inner(
 
 #[tauri::command]
 pub fn get_project(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     id: String,
 ) -> Result<Project, String> {
     get_project_inner(&config, &id)
@@ -266,7 +267,7 @@ pub fn list_projects_inner(
 
 #[tauri::command]
 pub fn list_projects(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
 ) -> Result<Vec<ProjectSummary>, String> {
     list_projects_inner(&config)
 }
@@ -323,7 +324,7 @@ pub fn update_project_inner(
 
 #[tauri::command]
 pub fn update_project(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     id: String,
     name: Option<String>,
@@ -361,7 +362,7 @@ pub fn delete_project_inner(
 
 #[tauri::command]
 pub fn delete_project(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     id: String,
 ) -> Result<(), String> {
@@ -1,5 +1,7 @@
 use std::sync::Arc;
 
+use rusqlite::{Connection, OptionalExtension};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
@@ -7,7 +9,7 @@ use crate::commands::config::ConfigState;
 use crate::commands::projects::open_project_db;
 use crate::executor::{EventBus, MaestroEvent};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct OpenQuestion {
     pub id: String,
     pub card_id: String,
@@ -36,6 +38,16 @@ const QUESTION_SELECT: &str = "\
     SELECT id, card_id, question, resolution, source, resolved_by, created_at, resolved_at \
     FROM open_questions";
 
+pub(crate) fn get_question_conn(conn: &Connection, id: &str) -> Result<Option<OpenQuestion>, String> {
+    conn.query_row(
+        &format!("{QUESTION_SELECT} WHERE id = ?1"),
+        rusqlite::params![id],
+        row_to_question,
+    )
+    .optional()
+    .map_err(|e| format!("Failed to read question: {e}"))
+}
+
 pub fn create_question_inner(
     config: &ConfigState,
     project_id: &str,
@@ -84,7 +96,7 @@ pub fn create_question_inner(
 
 #[tauri::command]
 pub fn create_question(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     card_id: String,
@@ -124,7 +136,7 @@ pub fn list_questions_inner(
 
 #[tauri::command]
 pub fn list_questions(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_id: String,
     card_id: String,
 ) -> Result<Vec<OpenQuestion>, String> {
@@ -170,7 +182,7 @@ pub fn resolve_question_inner(
 
 #[tauri::command]
 pub fn resolve_question(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     id: String,
@@ -215,7 +227,7 @@ pub fn unresolve_question_inner(
 
 #[tauri::command]
 pub fn unresolve_question(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     id: String,
@@ -253,7 +265,7 @@ pub fn delete_question_inner(
 
 #[tauri::command]
 pub fn delete_question(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     id: String,
@@ -291,7 +303,7 @@ pub fn count_unresolved_questions_inner(
 
 #[tauri::command]
 pub fn count_unresolved_questions(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_id: String,
     card_ids: Vec<String>,
 ) -> Result<Vec<(String, i32)>, String> {
@@ -1,8 +1,13 @@
+use std::sync::Arc;
+
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 
+use crate::commands::agent::spawn_auto_run;
 use crate::commands::config::ConfigState;
+use crate::commands::history::{record_card_event, status_value, Actor};
 use crate::commands::projects::open_project_db;
+use crate::executor::{AgentRegistry, EventBus, MaestroEvent};
 use crate::fs::diff;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -84,14 +89,13 @@ fn convert_file_diff(fd: diff::FileDiff) -> FileDiff {
     }
 }
 
-#[tauri::command]
-pub fn get_changed_files(
-    config: State<ConfigState>,
-    project_id: String,
-    card_id: String,
+pub fn get_changed_files_inner(
+    config: &ConfigState,
+    project_id: &str,
+    card_id: &str,
 ) -> Result<Vec<ChangedFile>, String> {
     let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
-    let db = open_project_db(&base_path, &project_id)?;
+    let db = open_project_db(&base_path, project_id)?;
 
     let (worktree_path, branch_name) = db.with_conn(|conn| {
         conn.query_row(
@@ -118,14 +122,22 @@ pub fn get_changed_files(
 }
 
 #[tauri::command]
-pub fn get_file_diff(
-    config: State<ConfigState>,
+pub fn get_changed_files(
+    config: State<Arc<ConfigState>>,
     project_id: String,
     card_id: String,
-    file_path: String,
+) -> Result<Vec<ChangedFile>, String> {
+    get_changed_files_inner(&config, &project_id, &card_id)
+}
+
+pub fn get_file_diff_inner(
+    config: &ConfigState,
+    project_id: &str,
+    card_id: &str,
+    file_path: &str,
 ) -> Result<FileDiff, String> {
     let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
-    let db = open_project_db(&base_path, &project_id)?;
+    let db = open_project_db(&base_path, project_id)?;
 
     let worktree_path = db.with_conn(|conn| {
         conn.query_row(
@@ -140,23 +152,46 @@ pub fn get_file_diff(
 
     let wt_path = worktree_path.ok_or_else(|| "No worktree path found".to_string())?;
     let base_branch = resolve_base_branch(&wt_path)?;
-    let fd = diff::get_file_diff(&wt_path, &base_branch, &file_path)?;
+    let fd = diff::get_file_diff(&wt_path, &base_branch, file_path)?;
     Ok(convert_file_diff(fd))
 }
 
 #[tauri::command]
-pub fn send_back_card(
-    config: State<ConfigState>,
+pub fn get_file_diff(
+    config: State<Arc<ConfigState>>,
     project_id: String,
     card_id: String,
-    feedback: String,
-    in_progress_status_id: String,
+    file_path: String,
+) -> Result<FileDiff, String> {
+    get_file_diff_inner(&config, &project_id, &card_id, &file_path)
+}
+
+fn card_status_id(conn: &rusqlite::Connection, project_id: &str, card_id: &str) -> Result<String, String> {
+    conn.query_row(
+        "SELECT status_id FROM cards WHERE id = ?1 AND project_id = ?2",
+        rusqlite::params![card_id, project_id],
+        |row| row.get(0),
+    )
+    .map_err(|e| format!("Card not found: {e}"))
+}
+
+pub fn send_back_card_inner(
+    config: &ConfigState,
+    project_id: &str,
+    card_id: &str,
+    feedback: &str,
+    in_progress_status_id: &str,
+    actor: &Actor,
 ) -> Result<(), String> {
     let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
-    let db = open_project_db(&base_path, &project_id)?;
+    let db = open_project_db(&base_path, project_id)?;
     let now = chrono::Utc::now().to_rfc3339();
 
     db.with_conn(|conn| {
+        let old_status_id = card_status_id(conn, project_id, card_id)?;
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to begin transaction: {e}"))?;
         conn.execute(
             "UPDATE cards SET status_id = ?1, updated_at = ?2 WHERE id = ?3 AND project_id = ?4",
             rusqlite::params![in_progress_status_id, now, card_id, project_id],
@@ -170,7 +205,7 @@ pub fn send_back_card(
         )
         .map_err(|e| format!("Failed to increment review count: {e}"))?;
 
-        let conv_id = get_or_create_review_conversation(conn, &card_id, &now)?;
+        let conv_id = get_or_create_review_conversation(conn, card_id, &now)?;
 
         let msg_id = uuid::Uuid::new_v4().to_string();
         conn.execute(
@@ -180,22 +215,63 @@ pub fn send_back_card(
         )
         .map_err(|e| format!("Failed to create review message: {e}"))?;
 
-        Ok(())
+        let mut new_value = status_value(conn, in_progress_status_id);
+        new_value["feedback"] = feedback.into();
+        record_card_event(
+            conn,
+            project_id,
+            card_id,
+            actor,
+            "sent_back",
+            Some(status_value(conn, &old_status_id)),
+            Some(new_value),
+        )?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit transaction: {e}"))
     })
 }
 
 #[tauri::command]
-pub fn approve_card(
-    config: State<ConfigState>,
+pub fn send_back_card(
+    app: AppHandle,
+    config: State<Arc<ConfigState>>,
+    event_bus: State<Arc<EventBus>>,
     project_id: String,
     card_id: String,
-    completed_status_id: String,
+    feedback: String,
+    in_progress_status_id: String,
+) -> Result<(), String> {
+    send_back_card_inner(&config, &project_id, &card_id, &feedback, &in_progress_status_id, &Actor::User)?;
+    event_bus.emit_maestro(MaestroEvent::CardsChanged {
+        project_id: project_id.clone(),
+    });
+    spawn_auto_run(
+        Some(app.clone()),
+        Some(Arc::clone(&event_bus)),
+        Arc::clone(&config),
+        Arc::clone(&app.state::<Arc<AgentRegistry>>()),
+        project_id,
+        card_id,
+    );
+    Ok(())
+}
+
+pub fn approve_card_inner(
+    config: &ConfigState,
+    project_id: &str,
+    card_id: &str,
+    completed_status_id: &str,
+    actor: &Actor,
 ) -> Result<(), String> {
     let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
-    let db = open_project_db(&base_path, &project_id)?;
+    let db = open_project_db(&base_path, project_id)?;
     let now = chrono::Utc::now().to_rfc3339();
 
     db.with_conn(|conn| {
+        let old_status_id = card_status_id(conn, project_id, card_id)?;
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to begin transaction: {e}"))?;
         conn.execute(
             "UPDATE cards SET status_id = ?1, updated_at = ?2 WHERE id = ?3 AND project_id = ?4",
             rusqlite::params![completed_status_id, now, card_id, project_id],
@@ -209,20 +285,53 @@ pub fn approve_card(
         )
         .map_err(|e| format!("Failed to complete workspaces: {e}"))?;
 
-        Ok(())
+        record_card_event(
+            conn,
+            project_id,
+            card_id,
+            actor,
+            "approved",
+            Some(status_value(conn, &old_status_id)),
+            Some(status_value(conn, completed_status_id)),
+        )?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit transaction: {e}"))
     })
 }
 
 #[tauri::command]
-pub fn create_pr(
-    config: State<ConfigState>,
+pub fn approve_card(
+    app: AppHandle,
+    config: State<Arc<ConfigState>>,
+    event_bus: State<Arc<EventBus>>,
     project_id: String,
     card_id: String,
-    title: String,
-    body: String,
+    completed_status_id: String,
+) -> Result<(), String> {
+    approve_card_inner(&config, &project_id, &card_id, &completed_status_id, &Actor::User)?;
+    event_bus.emit_maestro(MaestroEvent::CardsChanged {
+        project_id: project_id.clone(),
+    });
+    spawn_auto_run(
+        Some(app.clone()),
+        Some(Arc::clone(&event_bus)),
+        Arc::clone(&config),
+        Arc::clone(&app.state::<Arc<AgentRegistry>>()),
+        project_id,
+        card_id,
+    );
+    Ok(())
+}
+
+pub fn create_pr_inner(
+    config: &ConfigState,
+    project_id: &str,
+    card_id: &str,
+    title: &str,
+    body: &str,
 ) -> Result<CreatePrResult, String> {
     let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
-    let db = open_project_db(&base_path, &project_id)?;
+    let db = open_project_db(&base_path, project_id)?;
 
     let (worktree_path, branch_name) = db.with_conn(|conn| {
         conn.query_row(
@@ -244,19 +353,29 @@ pub fn create_pr(
     let branch = branch_name.ok_or_else(|| "No branch name found".to_string())?;
 
     diff::push_branch(&wt_path, &branch)?;
-    let url = diff::create_pull_request(&wt_path, &title, &body)?;
+    let url = diff::create_pull_request(&wt_path, title, body)?;
 
     Ok(CreatePrResult { url })
 }
 
 #[tauri::command]
-pub fn get_review_count(
-    config: State<ConfigState>,
+pub fn create_pr(
+    config: State<Arc<ConfigState>>,
     project_id: String,
     card_id: String,
+    title: String,
+    body: String,
+) -> Result<CreatePrResult, String> {
+    create_pr_inner(&config, &project_id, &card_id, &title, &body)
+}
+
+pub fn get_review_count_inner(
+    config: &ConfigState,
+    project_id: &str,
+    card_id: &str,
 ) -> Result<i32, String> {
     let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
-    let db = open_project_db(&base_path, &project_id)?;
+    let db = open_project_db(&base_path, project_id)?;
 
     db.with_conn(|conn| {
         let count: i32 = conn
@@ -270,6 +389,15 @@ pub fn get_review_count(
     })
 }
 
+#[tauri::command]
+pub fn get_review_count(
+    config: State<Arc<ConfigState>>,
+    project_id: String,
+    card_id: String,
+) -> Result<i32, String> {
+    get_review_count_inner(&config, &project_id, &card_id)
+}
+
 fn get_or_create_review_conversation(
     conn: &rusqlite::Connection,
     card_id: &str,
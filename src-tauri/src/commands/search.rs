@@ -0,0 +1,190 @@
+use std::sync::Arc;
+
+use rusqlite::Connection;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::commands::config::ConfigState;
+use crate::commands::projects::open_project_db;
+
+const DEFAULT_LIMIT: u32 = 20;
+const MAX_LIMIT: u32 = 100;
+
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct SearchResult {
+    /// `card`, `artifact`, `question` or `message`.
+    pub kind: String,
+    pub id: String,
+    pub card_id: String,
+    pub card_title: String,
+    pub title: String,
+    /// Matching text with the hits wrapped in `**`.
+    pub snippet: String,
+    /// Lower is a better match.
+    pub rank: f64,
+}
+
+/// Turns free text into an FTS5 query that matches every word as a prefix,
+/// so punctuation in user input can't be misread as query syntax.
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+pub(crate) fn search_conn(
+    conn: &Connection,
+    project_id: &str,
+    query: &str,
+    limit: Option<u32>,
+) -> Result<Vec<SearchResult>, String> {
+    let Some(fts_query) = fts_query(query) else {
+        return Ok(Vec::new());
+    };
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    // Titles count for more than bodies; the first three columns aren't
+    // indexed.
+    let mut stmt = conn
+        .prepare(
+            "SELECT i.kind, i.source_id, i.card_id, c.title, i.title, \
+                    snippet(search_index, -1, '**', '**', '…', 12), \
+                    bm25(search_index, 0.0, 0.0, 0.0, 5.0, 1.0) AS rank \
+             FROM search_index i JOIN cards c ON c.id = i.card_id \
+             WHERE search_index MATCH ?1 AND c.project_id = ?2 \
+             ORDER BY rank LIMIT ?3",
+        )
+        .map_err(|e| format!("Failed to prepare search: {e}"))?;
+
+    let rows = stmt
+        .query_map(rusqlite::params![fts_query, project_id, limit], |row| {
+            Ok(SearchResult {
+                kind: row.get(0)?,
+                id: row.get(1)?,
+                card_id: row.get(2)?,
+                card_title: row.get(3)?,
+                title: row.get(4)?,
+                snippet: row.get(5)?,
+                rank: row.get(6)?,
+            })
+        })
+        .map_err(|e| format!("Failed to search: {e}"))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read search result: {e}"))
+}
+
+/// Stores an artifact's content in the index; the database only knows its
+/// name.
+pub(crate) fn index_artifact_content(conn: &Connection, artifact_id: &str, content: &str) -> Result<(), String> {
+    conn.execute(
+        "UPDATE search_index SET body = ?1 WHERE kind = 'artifact' AND source_id = ?2",
+        rusqlite::params![content, artifact_id],
+    )
+    .map_err(|e| format!("Failed to index artifact: {e}"))?;
+    Ok(())
+}
+
+pub fn search_inner(
+    config: &ConfigState,
+    project_id: &str,
+    query: &str,
+    limit: Option<u32>,
+) -> Result<Vec<SearchResult>, String> {
+    let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
+    let db = open_project_db(&base_path, project_id)?;
+    db.with_conn(|conn| search_conn(conn, project_id, query, limit))
+}
+
+#[tauri::command]
+pub fn search(
+    config: State<Arc<ConfigState>>,
+    project_id: String,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<SearchResult>, String> {
+    search_inner(&config, &project_id, &query, limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{insert_card, setup_test_db};
+
+    fn kinds(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|r| r.kind.as_str()).collect()
+    }
+
+    #[test]
+    fn test_fts_query_quotes_terms() {
+        assert_eq!(fts_query("  "), None);
+        assert_eq!(fts_query("oauth login").unwrap(), "\"oauth\"* \"login\"*");
+        assert_eq!(fts_query("say \"hi\" AND").unwrap(), "\"say\"* \"\"\"hi\"\"\"* \"AND\"*");
+    }
+
+    #[test]
+    fn test_search_covers_every_kind() {
+        let (_dir, db, project_id) = setup_test_db();
+        db.with_conn(|conn| {
+            let card = insert_card(conn, &project_id, "Refresh tokens", "Rotate the OAuth refresh token");
+            let other = insert_card(conn, &project_id, "Dark mode", "Theme toggle");
+            let now = chrono::Utc::now().to_rfc3339();
+            conn.execute_batch(&format!(
+                "INSERT INTO open_questions (id, card_id, question, source, created_at) \
+                   VALUES ('q1', '{other}', 'Should dark mode follow the OS?', 'user', '{now}');
+                 INSERT INTO conversations (id, card_id, agent_type, started_at) VALUES ('conv', '{other}', 'claude', '{now}');
+                 INSERT INTO conversation_messages (id, conversation_id, role, content, timestamp) \
+                   VALUES ('m1', 'conv', 'agent', 'The OAuth provider rejects reused tokens', '{now}');
+                 INSERT INTO artifacts (id, card_id, name, path, created_by, created_at, updated_at) \
+                   VALUES ('a1', '{card}', 'Plan', 'artifacts/plan.md', 'agent', '{now}', '{now}');"
+            ))
+            .unwrap();
+            index_artifact_content(conn, "a1", "Step one: add an oauth scope")?;
+
+            let results = search_conn(conn, &project_id, "oauth", None)?;
+            assert_eq!(results.len(), 3);
+            assert!(kinds(&results).contains(&"card"));
+            assert!(kinds(&results).contains(&"artifact"));
+            assert!(kinds(&results).contains(&"message"));
+            let message = results.iter().find(|r| r.kind == "message").unwrap();
+            assert_eq!(message.card_id, other);
+            assert_eq!(message.card_title, "Dark mode");
+            assert!(message.snippet.contains("**OAuth**"));
+
+            // Prefix and stemmed matches.
+            assert_eq!(kinds(&search_conn(conn, &project_id, "follows", None)?), vec!["question"]);
+            assert_eq!(kinds(&search_conn(conn, &project_id, "rot", None)?), vec!["card"]);
+            assert_eq!(search_conn(conn, &project_id, "oauth", Some(1))?.len(), 1);
+            Ok(())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn test_index_follows_edits_and_deletes() {
+        let (_dir, db, project_id) = setup_test_db();
+        db.with_conn(|conn| {
+            let card = insert_card(conn, &project_id, "Old title", "");
+            conn.execute(
+                "UPDATE cards SET title = 'Billing export' WHERE id = ?1",
+                rusqlite::params![card],
+            )
+            .map_err(|e| format!("{e}"))?;
+            assert!(search_conn(conn, &project_id, "old", None)?.is_empty());
+            assert_eq!(search_conn(conn, &project_id, "billing", None)?.len(), 1);
+
+            conn.execute("DELETE FROM cards WHERE id = ?1", rusqlite::params![card])
+                .map_err(|e| format!("{e}"))?;
+            assert!(search_conn(conn, &project_id, "billing", None)?.is_empty());
+            let left: i64 = conn
+                .query_row("SELECT COUNT(*) FROM search_index", [], |row| row.get(0))
+                .map_err(|e| format!("{e}"))?;
+            assert_eq!(left, 0);
+            Ok(())
+        })
+        .unwrap();
+    }
+}
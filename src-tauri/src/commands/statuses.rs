@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
@@ -7,7 +8,7 @@ use crate::commands::config::ConfigState;
 use crate::commands::projects::open_project_db;
 use crate::executor::{EventBus, MaestroEvent};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
 pub struct Status {
     pub id: String,
     pub project_id: String,
@@ -16,9 +17,25 @@ pub struct Status {
     pub sort_order: i32,
     pub is_default: bool,
     pub status_prompts: Vec<String>,
+    pub auto_run: Option<AutoRunPolicy>,
     pub created_at: String,
 }
 
+/// Launches an agent whenever a card moves into the status.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct AutoRunPolicy {
+    /// Agent profile to run instead of the one resolved from config.
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Run the agent in a fresh worktree of the linked repository.
+    #[serde(default)]
+    pub create_worktree: bool,
+    /// Linked directory to run in. Worktrees default to the project's first
+    /// linked repository.
+    #[serde(default)]
+    pub directory_id: Option<String>,
+}
+
 const VALID_GROUPS: &[&str] = &["Backlog", "Unstarted", "Started", "Completed", "Cancelled"];
 
 fn validate_group(group: &str) -> Result<(), String> {
@@ -49,10 +66,14 @@ fn serialize_status_prompts(prompts: &[String]) -> String {
     serde_json::to_string(prompts).unwrap_or_else(|_| "[]".to_string())
 }
 
+pub fn parse_auto_run(json: Option<String>) -> Option<AutoRunPolicy> {
+    json.and_then(|j| serde_json::from_str(&j).ok())
+}
+
 fn query_statuses(conn: &rusqlite::Connection, project_id: &str) -> Result<Vec<Status>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, project_id, \"group\", name, sort_order, is_default, status_prompts, created_at \
+            "SELECT id, project_id, \"group\", name, sort_order, is_default, status_prompts, created_at, auto_run \
              FROM statuses WHERE project_id = ?1 \
              ORDER BY CASE \"group\" \
                 WHEN 'Backlog' THEN 0 \
@@ -75,6 +96,7 @@ fn query_statuses(conn: &rusqlite::Connection, project_id: &str) -> Result<Vec<S
                 sort_order: row.get(4)?,
                 is_default: row.get(5)?,
                 status_prompts: parse_status_prompts(&prompts_json),
+                auto_run: parse_auto_run(row.get(8)?),
                 created_at: row.get(7)?,
             })
         })
@@ -96,7 +118,7 @@ pub fn list_statuses_inner(
 
 #[tauri::command]
 pub fn list_statuses(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_id: String,
 ) -> Result<Vec<Status>, String> {
     list_statuses_inner(&config, &project_id)
@@ -154,6 +176,7 @@ pub fn create_status_inner(
             sort_order,
             is_default: set_default,
             status_prompts: prompts_val,
+            auto_run: None,
             created_at: now,
         })
     })
@@ -161,7 +184,7 @@ pub fn create_status_inner(
 
 #[tauri::command]
 pub fn create_status(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     group: String,
@@ -190,7 +213,7 @@ pub fn update_status_inner(
     db.with_conn(|conn| {
         let existing = conn
             .query_row(
-                "SELECT id, project_id, \"group\", name, sort_order, is_default, status_prompts, created_at \
+                "SELECT id, project_id, \"group\", name, sort_order, is_default, status_prompts, created_at, auto_run \
                  FROM statuses WHERE id = ?1 AND project_id = ?2",
                 rusqlite::params![id, project_id],
                 |row| {
@@ -203,6 +226,7 @@ pub fn update_status_inner(
                         sort_order: row.get(4)?,
                         is_default: row.get(5)?,
                         status_prompts: parse_status_prompts(&prompts_json),
+                        auto_run: parse_auto_run(row.get(8)?),
                         created_at: row.get(7)?,
                     })
                 },
@@ -236,6 +260,7 @@ pub fn update_status_inner(
             sort_order: existing.sort_order,
             is_default: new_default,
             status_prompts: new_prompts,
+            auto_run: existing.auto_run,
             created_at: existing.created_at,
         })
     })
@@ -243,7 +268,7 @@ pub fn update_status_inner(
 
 #[tauri::command]
 pub fn update_status(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     id: String,
@@ -258,6 +283,73 @@ pub fn update_status(
     Ok(result)
 }
 
+pub fn set_status_auto_run_inner(
+    config: &ConfigState,
+    project_id: &str,
+    id: &str,
+    auto_run: Option<AutoRunPolicy>,
+) -> Result<Status, String> {
+    let base_path = config.with_config(|c| {
+        if let Some(agent) = auto_run.as_ref().and_then(|p| p.agent.as_ref()) {
+            if !c.agents.contains_key(agent) {
+                return Err(format!("Unknown agent profile: {agent}"));
+            }
+        }
+        Ok(c.resolve_base_path())
+    })?;
+    let db = open_project_db(&base_path, project_id)?;
+
+    db.with_conn(|conn| {
+        if let Some(directory_id) = auto_run.as_ref().and_then(|p| p.directory_id.as_ref()) {
+            let is_repo: bool = conn
+                .query_row(
+                    "SELECT is_repo FROM linked_directories WHERE id = ?1 AND project_id = ?2",
+                    rusqlite::params![directory_id, project_id],
+                    |row| row.get(0),
+                )
+                .map_err(|e| format!("Linked directory not found: {e}"))?;
+            if auto_run.as_ref().is_some_and(|p| p.create_worktree) && !is_repo {
+                return Err("Worktrees can only be created from a linked repository".to_string());
+            }
+        }
+
+        let auto_run_json = auto_run
+            .as_ref()
+            .map(|p| serde_json::to_string(p).map_err(|e| format!("Failed to serialize auto-run policy: {e}")))
+            .transpose()?;
+
+        let updated = conn
+            .execute(
+                "UPDATE statuses SET auto_run = ?1 WHERE id = ?2 AND project_id = ?3",
+                rusqlite::params![auto_run_json, id, project_id],
+            )
+            .map_err(|e| format!("Failed to update auto-run policy: {e}"))?;
+        if updated == 0 {
+            return Err(format!("Status {id} not found"));
+        }
+
+        query_statuses(conn, project_id)?
+            .into_iter()
+            .find(|s| s.id == id)
+            .ok_or_else(|| format!("Status {id} not found"))
+    })
+}
+
+#[tauri::command]
+pub fn set_status_auto_run(
+    config: State<Arc<ConfigState>>,
+    event_bus: State<Arc<EventBus>>,
+    project_id: String,
+    id: String,
+    auto_run: Option<AutoRunPolicy>,
+) -> Result<Status, String> {
+    let result = set_status_auto_run_inner(&config, &project_id, &id, auto_run)?;
+    event_bus.emit_maestro(MaestroEvent::StatusesChanged {
+        project_id: project_id.clone(),
+    });
+    Ok(result)
+}
+
 pub fn delete_status_inner(
     config: &ConfigState,
     project_id: &str,
@@ -353,7 +445,7 @@ pub fn delete_status_inner(
 
 #[tauri::command]
 pub fn delete_status(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     id: String,
@@ -404,7 +496,7 @@ pub fn reorder_statuses_inner(
 
 #[tauri::command]
 pub fn reorder_statuses(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     event_bus: State<Arc<EventBus>>,
     project_id: String,
     group: String,
@@ -633,4 +725,28 @@ mod tests {
             Ok(())
         }).unwrap();
     }
+
+    #[test]
+    fn test_auto_run_policy_roundtrip() {
+        let (db, project_id) = setup_test_db();
+        db.with_conn(|conn: &rusqlite::Connection| {
+            conn.execute(
+                "UPDATE statuses SET auto_run = ?1 WHERE project_id = ?2 AND name = 'In Progress'",
+                rusqlite::params![r#"{"agent":"codex","create_worktree":true}"#, project_id],
+            ).unwrap();
+
+            let statuses = query_statuses(conn, &project_id)?;
+            let in_progress = statuses.iter().find(|s| s.name == "In Progress").unwrap();
+            assert_eq!(
+                in_progress.auto_run,
+                Some(AutoRunPolicy {
+                    agent: Some("codex".to_string()),
+                    create_worktree: true,
+                    directory_id: None,
+                })
+            );
+            assert!(statuses.iter().filter(|s| s.name != "In Progress").all(|s| s.auto_run.is_none()));
+            Ok(())
+        }).unwrap();
+    }
 }
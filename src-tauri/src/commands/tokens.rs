@@ -0,0 +1,245 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::State;
+
+use crate::commands::config::ConfigState;
+use crate::config::global::{ApiToken, TokenScope};
+use crate::executor::{EventBus, MaestroEvent};
+
+/// How often a token's `last_used_at` is written back to the config file.
+const LAST_USED_RESOLUTION_SECS: i64 = 60;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiTokenInfo {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<TokenScope>,
+    pub projects: Vec<String>,
+    pub created_at: String,
+    pub expires_at: Option<String>,
+    pub last_used_at: Option<String>,
+}
+
+impl From<&ApiToken> for ApiTokenInfo {
+    fn from(token: &ApiToken) -> Self {
+        Self {
+            id: token.id.clone(),
+            name: token.name.clone(),
+            scopes: token.scopes.clone(),
+            projects: token.projects.clone(),
+            created_at: token.created_at.clone(),
+            expires_at: token.expires_at.clone(),
+            last_used_at: token.last_used_at.clone(),
+        }
+    }
+}
+
+/// Returned once on creation; only the hash is kept afterwards.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatedApiToken {
+    pub token: String,
+    #[serde(flatten)]
+    pub info: ApiTokenInfo,
+}
+
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+pub fn create_api_token_inner(
+    config: &ConfigState,
+    name: &str,
+    scopes: Vec<TokenScope>,
+    projects: Vec<String>,
+    expires_in_days: Option<u32>,
+) -> Result<CreatedApiToken, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Token name cannot be empty".to_string());
+    }
+    if scopes.is_empty() {
+        return Err("Token needs at least one scope".to_string());
+    }
+
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+    let token = hex::encode(bytes);
+    let now = Utc::now();
+    let api_token = ApiToken {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        token_hash: hash_token(&token),
+        scopes,
+        projects,
+        created_at: now.to_rfc3339(),
+        expires_at: expires_in_days.map(|days| (now + Duration::days(days.into())).to_rfc3339()),
+        last_used_at: None,
+    };
+    let info = ApiTokenInfo::from(&api_token);
+
+    config.update(|c| {
+        c.http_server.tokens.push(api_token);
+        c.http_server.ensure_auth_token();
+    })?;
+    Ok(CreatedApiToken { token, info })
+}
+
+#[tauri::command]
+pub fn create_api_token(
+    config: State<Arc<ConfigState>>,
+    event_bus: State<Arc<EventBus>>,
+    name: String,
+    scopes: Vec<TokenScope>,
+    projects: Option<Vec<String>>,
+    expires_in_days: Option<u32>,
+) -> Result<CreatedApiToken, String> {
+    let created = create_api_token_inner(
+        &config,
+        &name,
+        scopes,
+        projects.unwrap_or_default(),
+        expires_in_days,
+    )?;
+    event_bus.emit_maestro(MaestroEvent::ConfigChanged);
+    Ok(created)
+}
+
+pub fn list_api_tokens_inner(config: &ConfigState) -> Result<Vec<ApiTokenInfo>, String> {
+    config.with_config(|c| Ok(c.http_server.tokens.iter().map(ApiTokenInfo::from).collect()))
+}
+
+#[tauri::command]
+pub fn list_api_tokens(config: State<Arc<ConfigState>>) -> Result<Vec<ApiTokenInfo>, String> {
+    list_api_tokens_inner(&config)
+}
+
+pub fn revoke_api_token_inner(config: &ConfigState, id: &str) -> Result<(), String> {
+    let mut found = false;
+    config.update(|c| {
+        let before = c.http_server.tokens.len();
+        c.http_server.tokens.retain(|t| t.id != id);
+        found = c.http_server.tokens.len() != before;
+    })?;
+    if !found {
+        return Err(format!("API token not found: {id}"));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn revoke_api_token(
+    config: State<Arc<ConfigState>>,
+    event_bus: State<Arc<EventBus>>,
+    id: String,
+) -> Result<(), String> {
+    revoke_api_token_inner(&config, &id)?;
+    event_bus.emit_maestro(MaestroEvent::ConfigChanged);
+    Ok(())
+}
+
+/// Records that a token was just used. Writes are coalesced so a busy client
+/// doesn't rewrite the config file on every request.
+pub fn touch_api_token(config: &ConfigState, id: &str, now: DateTime<Utc>) -> Result<(), String> {
+    let stale = |last_used: Option<&str>| {
+        last_used
+            .and_then(|at| DateTime::parse_from_rfc3339(at).ok())
+            .is_none_or(|at| now.signed_duration_since(at).num_seconds() >= LAST_USED_RESOLUTION_SECS)
+    };
+    let needs_write = config.with_config(|c| {
+        Ok(c.http_server
+            .tokens
+            .iter()
+            .any(|t| t.id == id && stale(t.last_used_at.as_deref())))
+    })?;
+    if needs_write {
+        config.update(|c| {
+            if let Some(token) = c.http_server.tokens.iter_mut().find(|t| t.id == id) {
+                token.last_used_at = Some(now.to_rfc3339());
+            }
+        })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config(dir: &tempfile::TempDir) -> ConfigState {
+        ConfigState::load_from(dir.path().join("config.toml")).unwrap()
+    }
+
+    #[test]
+    fn test_create_stores_only_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(&dir);
+
+        let created = create_api_token_inner(
+            &config,
+            "teammate",
+            vec![TokenScope::Read],
+            vec!["p1".to_string()],
+            Some(7),
+        )
+        .unwrap();
+
+        let stored = config.with_config(|c| Ok(c.http_server.clone())).unwrap();
+        assert_eq!(stored.tokens.len(), 1);
+        assert_eq!(stored.tokens[0].token_hash, hash_token(&created.token));
+        assert!(stored.tokens[0].expires_at.is_some());
+        assert!(stored.requires_auth());
+        assert!(!stored.auth_token.is_empty());
+
+        let on_disk = std::fs::read_to_string(dir.path().join("config.toml")).unwrap();
+        assert!(!on_disk.contains(&created.token));
+        assert!(on_disk.contains(&hash_token(&created.token)));
+    }
+
+    #[test]
+    fn test_create_validates_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(&dir);
+        assert!(create_api_token_inner(&config, "  ", vec![TokenScope::Read], vec![], None).is_err());
+        assert!(create_api_token_inner(&config, "ci", vec![], vec![], None).is_err());
+    }
+
+    #[test]
+    fn test_list_and_revoke() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(&dir);
+        let a = create_api_token_inner(&config, "a", vec![TokenScope::Read], vec![], None).unwrap();
+        create_api_token_inner(&config, "b", vec![TokenScope::Agent], vec![], None).unwrap();
+
+        revoke_api_token_inner(&config, &a.info.id).unwrap();
+        let names: Vec<_> = list_api_tokens_inner(&config)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, vec!["b"]);
+        assert!(revoke_api_token_inner(&config, &a.info.id).is_err());
+    }
+
+    #[test]
+    fn test_touch_is_throttled() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = test_config(&dir);
+        let created = create_api_token_inner(&config, "a", vec![TokenScope::Read], vec![], None).unwrap();
+        let id = created.info.id;
+        let last_used = || list_api_tokens_inner(&config).unwrap()[0].last_used_at.clone();
+
+        let t0 = Utc::now();
+        touch_api_token(&config, &id, t0).unwrap();
+        assert_eq!(last_used(), Some(t0.to_rfc3339()));
+
+        touch_api_token(&config, &id, t0 + Duration::seconds(10)).unwrap();
+        assert_eq!(last_used(), Some(t0.to_rfc3339()));
+
+        let t1 = t0 + Duration::seconds(LAST_USED_RESOLUTION_SECS);
+        touch_api_token(&config, &id, t1).unwrap();
+        assert_eq!(last_used(), Some(t1.to_rfc3339()));
+    }
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use tauri::State;
 
 use crate::commands::config::ConfigState;
@@ -35,7 +37,7 @@ pub fn create_worktree_inner(
 
 #[tauri::command]
 pub async fn create_worktree(
-    config: State<'_, ConfigState>,
+    config: State<'_, Arc<ConfigState>>,
     project_id: String,
     card_id: String,
     repo_path: String,
@@ -62,7 +64,7 @@ pub fn check_worktree_exists_inner(
 
 #[tauri::command]
 pub fn check_worktree_exists(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_id: String,
     card_id: String,
     branch_slug: String,
@@ -97,7 +99,7 @@ pub fn get_card_worktree_inner(
 
 #[tauri::command]
 pub fn get_card_worktree(
-    config: State<ConfigState>,
+    config: State<Arc<ConfigState>>,
     project_id: String,
     card_id: String,
 ) -> Result<Option<WorktreeInfo>, String> {
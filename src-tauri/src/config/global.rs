@@ -13,6 +13,39 @@ pub struct GlobalConfig {
     pub defaults: DefaultsConfig,
     #[serde(default)]
     pub http_server: HttpServerConfig,
+    #[serde(default)]
+    pub concurrency: ConcurrencyConfig,
+}
+
+/// Limits on how many agents may run at once. Launches beyond a limit are
+/// queued and started as running agents exit.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ConcurrencyConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_agents: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_agents_per_project: Option<usize>,
+    /// Per-project overrides of `max_agents_per_project`, keyed by project ID.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub projects: HashMap<String, usize>,
+}
+
+impl ConcurrencyConfig {
+    pub fn project_limit(&self, project_id: &str) -> Option<usize> {
+        self.projects
+            .get(project_id)
+            .copied()
+            .or(self.max_agents_per_project)
+    }
+
+    pub fn allows_global(&self, running_total: usize) -> bool {
+        !matches!(self.max_agents, Some(max) if running_total >= max)
+    }
+
+    pub fn allows(&self, project_id: &str, running_total: usize, running_in_project: usize) -> bool {
+        self.allows_global(running_total)
+            && !matches!(self.project_limit(project_id), Some(max) if running_in_project >= max)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -23,6 +56,78 @@ pub struct HttpServerConfig {
     pub auth_token: String,
     #[serde(default = "default_http_port")]
     pub port: u16,
+    /// Additional named tokens with limited access, managed with the
+    /// `*_api_token` commands. `auth_token` keeps full access.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tokens: Vec<ApiToken>,
+    #[serde(default, skip_serializing_if = "TlsConfig::is_unset")]
+    pub tls: TlsConfig,
+}
+
+/// Serve HTTPS instead of HTTP. Without `cert_path`/`key_path`, a
+/// self-signed certificate is generated under the base path.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct TlsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cert_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_path: Option<String>,
+}
+
+impl TlsConfig {
+    fn is_unset(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenScope {
+    /// Read boards, cards, questions, artifacts and agent output.
+    Read,
+    /// Create and edit projects, cards, questions and artifacts.
+    Write,
+    /// Launch, stop and talk to agents.
+    Agent,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    /// Hex SHA-256 of the token; the token itself is only shown once.
+    pub token_hash: String,
+    pub scopes: Vec<TokenScope>,
+    /// Projects the token may access. Empty means all projects.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<String>,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used_at: Option<String>,
+}
+
+impl ApiToken {
+    /// `Write` and `Agent` both imply `Read`.
+    pub fn has_scope(&self, scope: TokenScope) -> bool {
+        self.scopes
+            .iter()
+            .any(|&s| s == scope || scope == TokenScope::Read)
+    }
+
+    pub fn allows_project(&self, project_id: &str) -> bool {
+        self.projects.is_empty() || self.projects.iter().any(|p| p == project_id)
+    }
+
+    pub fn is_expired(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.expires_at
+            .as_deref()
+            .and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+            .is_some_and(|at| at <= now)
+    }
 }
 
 fn default_bind_address() -> String {
@@ -39,13 +144,24 @@ impl Default for HttpServerConfig {
             bind_address: default_bind_address(),
             auth_token: String::new(),
             port: default_http_port(),
+            tokens: Vec::new(),
+            tls: TlsConfig::default(),
         }
     }
 }
 
 impl HttpServerConfig {
     pub fn requires_auth(&self) -> bool {
-        self.bind_address != "127.0.0.1" && self.bind_address != "localhost"
+        let localhost = self.bind_address == "127.0.0.1" || self.bind_address == "localhost";
+        !localhost || !self.tokens.is_empty()
+    }
+
+    pub fn scheme(&self) -> &'static str {
+        if self.tls.enabled {
+            "https"
+        } else {
+            "http"
+        }
     }
 
     pub fn ensure_auth_token(&mut self) {
@@ -72,6 +188,127 @@ pub struct AgentProfile {
     pub custom_command: Option<String>,
     #[serde(default)]
     pub env_vars: Option<HashMap<String, String>>,
+    /// Regex used to pick the agent's session ID out of its output. The first
+    /// capture group is taken as the ID. JSON lines carrying a top-level
+    /// `session_id` field are always recognized, pattern or not.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id_pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "OutputFormat::is_text")]
+    pub output_format: OutputFormat,
+    #[serde(default, skip_serializing_if = "AgentLimits::is_unlimited")]
+    pub limits: AgentLimits,
+    /// Run the agent on a pseudo-terminal instead of plain pipes, for agents
+    /// that only behave interactively when attached to a TTY.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pty: bool,
+    /// How launches are turned into a command line. Inferred from `binary`
+    /// when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adapter: Option<AdapterKind>,
+    /// Command-line templates for the `generic` adapter.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<AdapterTemplate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum AdapterKind {
+    ClaudeCode,
+    Codex,
+    Aider,
+    Generic,
+}
+
+/// How the assembled prompt reaches the agent.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PromptDelivery {
+    /// Substituted into the prompt arguments as `{prompt}`.
+    #[default]
+    Arg,
+    /// Written to the agent's stdin once it starts.
+    Stdin,
+    /// Written to a file whose path is substituted as `{prompt}`.
+    File,
+}
+
+/// Argument templates for an agent without a built-in adapter. Each list is
+/// appended when it applies, with `{prompt}`, `{model}`, `{session_id}`,
+/// `{worktree}` and `{repo}` replaced.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AdapterTemplate {
+    #[serde(default)]
+    pub prompt: PromptDelivery,
+    #[serde(default = "default_prompt_args")]
+    pub prompt_args: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub model_args: Vec<String>,
+    /// Replaces the prompt arguments when resuming. Resume is unsupported
+    /// when empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub resume_args: Vec<String>,
+    /// Set for agents that create their own git worktree; otherwise Maestro
+    /// creates one and starts the agent inside it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub worktree_args: Vec<String>,
+    /// Where the agent puts the worktree it creates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree_path: Option<String>,
+}
+
+fn default_prompt_args() -> Vec<String> {
+    vec!["{prompt}".to_string()]
+}
+
+impl Default for AdapterTemplate {
+    fn default() -> Self {
+        Self {
+            prompt: PromptDelivery::default(),
+            prompt_args: default_prompt_args(),
+            model_args: Vec::new(),
+            resume_args: Vec::new(),
+            worktree_args: Vec::new(),
+            worktree_path: None,
+        }
+    }
+}
+
+/// Resource limits applied to every process launched from a profile.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AgentLimits {
+    /// Wall-clock time after which the agent is stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_runtime_secs: Option<u64>,
+    /// Time without any stdout/stderr output after which the agent is stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_timeout_secs: Option<u64>,
+    /// Address space limit (`RLIMIT_AS`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_memory_mb: Option<u64>,
+    /// CPU time limit (`RLIMIT_CPU`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cpu_secs: Option<u64>,
+}
+
+impl AgentLimits {
+    pub fn is_unlimited(&self) -> bool {
+        *self == AgentLimits::default()
+    }
+}
+
+/// How an agent's stdout should be interpreted beyond raw lines.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    ClaudeStreamJson,
+}
+
+impl OutputFormat {
+    pub fn is_text(&self) -> bool {
+        *self == OutputFormat::Text
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -123,6 +360,12 @@ impl Default for GlobalConfig {
                 flags: vec!["--dangerously-skip-permissions".to_string()],
                 custom_command: None,
                 env_vars: None,
+                session_id_pattern: None,
+                output_format: OutputFormat::Text,
+                limits: AgentLimits::default(),
+                pty: false,
+                adapter: Some(AdapterKind::ClaudeCode),
+                template: None,
             },
         );
         agents.insert(
@@ -132,6 +375,12 @@ impl Default for GlobalConfig {
                 flags: vec!["--full-auto".to_string()],
                 custom_command: None,
                 env_vars: None,
+                session_id_pattern: None,
+                output_format: OutputFormat::Text,
+                limits: AgentLimits::default(),
+                pty: false,
+                adapter: Some(AdapterKind::Codex),
+                template: None,
             },
         );
 
@@ -140,6 +389,7 @@ impl Default for GlobalConfig {
             agents,
             defaults: default_defaults(),
             http_server: HttpServerConfig::default(),
+            concurrency: ConcurrencyConfig::default(),
         }
     }
 }
@@ -268,4 +518,70 @@ last_project_id = "abc-123"
         let loaded = GlobalConfig::load(&path).unwrap();
         assert_eq!(loaded.defaults.last_project_id, "test-id");
     }
+
+    #[test]
+    fn test_concurrency_limits() {
+        let content = r#"
+[concurrency]
+max_agents = 4
+max_agents_per_project = 2
+
+[concurrency.projects]
+busy = 3
+"#;
+        let config: GlobalConfig = toml::from_str(content).unwrap();
+        let limits = &config.concurrency;
+
+        assert_eq!(limits.project_limit("other"), Some(2));
+        assert_eq!(limits.project_limit("busy"), Some(3));
+        assert!(limits.allows("other", 1, 1));
+        assert!(!limits.allows("other", 2, 2));
+        assert!(limits.allows("busy", 2, 2));
+        assert!(!limits.allows("busy", 4, 0));
+        assert!(GlobalConfig::default().concurrency.allows("any", 100, 100));
+    }
+
+    #[test]
+    fn test_agent_limits_parse_and_skip_when_unset() {
+        let content = r#"
+[agents.capped]
+binary = "claude"
+
+[agents.capped.limits]
+max_runtime_secs = 3600
+idle_timeout_secs = 300
+max_memory_mb = 4096
+"#;
+        let config: GlobalConfig = toml::from_str(content).unwrap();
+        let limits = config.agents["capped"].limits;
+        assert_eq!(limits.max_runtime_secs, Some(3600));
+        assert_eq!(limits.idle_timeout_secs, Some(300));
+        assert_eq!(limits.max_memory_mb, Some(4096));
+        assert_eq!(limits.max_cpu_secs, None);
+
+        let toml_str = toml::to_string_pretty(&GlobalConfig::default()).unwrap();
+        assert!(!toml_str.contains("limits"));
+    }
+
+    #[test]
+    fn test_generic_adapter_template_parses() {
+        let content = r#"
+[agents.custom]
+binary = "my-agent"
+adapter = "generic"
+
+[agents.custom.template]
+prompt = "file"
+prompt_args = ["--instructions", "{prompt}"]
+model_args = ["-m", "{model}"]
+"#;
+        let config: GlobalConfig = toml::from_str(content).unwrap();
+        let profile = &config.agents["custom"];
+        assert_eq!(profile.adapter, Some(AdapterKind::Generic));
+        let template = profile.template.as_ref().unwrap();
+        assert_eq!(template.prompt, PromptDelivery::File);
+        assert_eq!(template.prompt_args, vec!["--instructions", "{prompt}"]);
+        assert!(template.resume_args.is_empty());
+        assert_eq!(AdapterTemplate::default().prompt_args, vec!["{prompt}"]);
+    }
 }
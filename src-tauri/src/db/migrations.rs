@@ -39,6 +39,249 @@ fn migrate_add_status_prompts_to_statuses(conn: &Connection) -> Result<(), Strin
     Ok(())
 }
 
+/// Adds the `queued` workspace status and the table that holds pending
+/// launches. SQLite can't alter a CHECK constraint, so `agent_workspaces` is
+/// rebuilt.
+fn migrate_add_launch_queue(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "PRAGMA foreign_keys = OFF;
+         CREATE TABLE agent_workspaces_new (
+           id TEXT PRIMARY KEY,
+           card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+           agent_type TEXT NOT NULL,
+           status TEXT NOT NULL DEFAULT 'running' CHECK(status IN ('queued', 'running', 'paused', 'reviewing', 'completed', 'failed')),
+           session_id TEXT,
+           pid INTEGER,
+           worktree_path TEXT,
+           branch_name TEXT,
+           review_count INTEGER NOT NULL DEFAULT 0,
+           attached_at TEXT NOT NULL,
+           completed_at TEXT
+         );
+         INSERT INTO agent_workspaces_new
+           SELECT id, card_id, agent_type, status, session_id, pid, worktree_path, branch_name,
+                  review_count, attached_at, completed_at
+           FROM agent_workspaces;
+         DROP TABLE agent_workspaces;
+         ALTER TABLE agent_workspaces_new RENAME TO agent_workspaces;
+         CREATE INDEX idx_agent_workspaces_card ON agent_workspaces(card_id);
+         PRAGMA foreign_keys = ON;",
+    )
+    .map_err(|e| format!("Failed to rebuild agent_workspaces: {e}"))?;
+
+    conn.execute_batch(
+        "CREATE TABLE launch_queue (
+           workspace_id TEXT PRIMARY KEY REFERENCES agent_workspaces(id) ON DELETE CASCADE,
+           card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+           status_id TEXT NOT NULL,
+           worktree_path TEXT,
+           branch_name TEXT,
+           repo_path TEXT,
+           priority INTEGER NOT NULL DEFAULT 0,
+           enqueued_at TEXT NOT NULL
+         );
+         CREATE INDEX idx_launch_queue_order ON launch_queue(priority DESC, enqueued_at);",
+    )
+    .map_err(|e| format!("Failed to create launch_queue: {e}"))?;
+
+    Ok(())
+}
+
+fn migrate_add_status_auto_run(conn: &Connection) -> Result<(), String> {
+    if !has_column(conn, "statuses", "auto_run") {
+        conn.execute_batch("ALTER TABLE statuses ADD COLUMN auto_run TEXT")
+            .map_err(|e| format!("Failed to add auto_run column: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Adds the `timed_out` and `killed` terminal statuses plus the reason a run
+/// ended. Like `add_launch_queue`, this has to rebuild `agent_workspaces`.
+fn migrate_add_workspace_exit_reason(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "PRAGMA foreign_keys = OFF;
+         CREATE TABLE agent_workspaces_new (
+           id TEXT PRIMARY KEY,
+           card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+           agent_type TEXT NOT NULL,
+           status TEXT NOT NULL DEFAULT 'running' CHECK(status IN ('queued', 'running', 'paused', 'reviewing', 'completed', 'failed', 'timed_out', 'killed')),
+           session_id TEXT,
+           pid INTEGER,
+           worktree_path TEXT,
+           branch_name TEXT,
+           review_count INTEGER NOT NULL DEFAULT 0,
+           attached_at TEXT NOT NULL,
+           completed_at TEXT,
+           exit_reason TEXT
+         );
+         INSERT INTO agent_workspaces_new
+           SELECT id, card_id, agent_type, status, session_id, pid, worktree_path, branch_name,
+                  review_count, attached_at, completed_at, NULL
+           FROM agent_workspaces;
+         DROP TABLE agent_workspaces;
+         ALTER TABLE agent_workspaces_new RENAME TO agent_workspaces;
+         CREATE INDEX idx_agent_workspaces_card ON agent_workspaces(card_id);
+         PRAGMA foreign_keys = ON;",
+    )
+    .map_err(|e| format!("Failed to rebuild agent_workspaces: {e}"))
+}
+
+fn migrate_add_workspace_pid_start_time(conn: &Connection) -> Result<(), String> {
+    if !has_column(conn, "agent_workspaces", "pid_start_time") {
+        conn.execute_batch("ALTER TABLE agent_workspaces ADD COLUMN pid_start_time INTEGER")
+            .map_err(|e| format!("Failed to add pid_start_time column: {e}"))?;
+    }
+    Ok(())
+}
+
+const ADD_CARD_DEPENDENCIES: &str = "
+CREATE TABLE card_dependencies (
+  card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+  depends_on_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+  created_at TEXT NOT NULL,
+  PRIMARY KEY (card_id, depends_on_id),
+  CHECK (card_id != depends_on_id)
+);
+
+CREATE INDEX idx_card_dependencies_depends_on ON card_dependencies(depends_on_id);
+";
+
+const ADD_SEARCH_INDEX: &str = "
+CREATE VIRTUAL TABLE search_index USING fts5(
+  kind UNINDEXED,
+  source_id UNINDEXED,
+  card_id UNINDEXED,
+  title,
+  body,
+  tokenize = 'porter unicode61'
+);
+
+CREATE TRIGGER search_cards_ai AFTER INSERT ON cards BEGIN
+  INSERT INTO search_index (kind, source_id, card_id, title, body)
+    VALUES ('card', new.id, new.id, new.title, new.description);
+END;
+CREATE TRIGGER search_cards_au AFTER UPDATE OF title, description ON cards BEGIN
+  UPDATE search_index SET title = new.title, body = new.description
+    WHERE kind = 'card' AND source_id = new.id;
+END;
+CREATE TRIGGER search_cards_ad AFTER DELETE ON cards BEGIN
+  DELETE FROM search_index WHERE card_id = old.id;
+END;
+
+CREATE TRIGGER search_questions_ai AFTER INSERT ON open_questions BEGIN
+  INSERT INTO search_index (kind, source_id, card_id, title, body)
+    VALUES ('question', new.id, new.card_id, new.question, COALESCE(new.resolution, ''));
+END;
+CREATE TRIGGER search_questions_au AFTER UPDATE OF question, resolution ON open_questions BEGIN
+  UPDATE search_index SET title = new.question, body = COALESCE(new.resolution, '')
+    WHERE kind = 'question' AND source_id = new.id;
+END;
+CREATE TRIGGER search_questions_ad AFTER DELETE ON open_questions BEGIN
+  DELETE FROM search_index WHERE kind = 'question' AND source_id = old.id;
+END;
+
+CREATE TRIGGER search_messages_ai AFTER INSERT ON conversation_messages BEGIN
+  INSERT INTO search_index (kind, source_id, card_id, title, body)
+    SELECT 'message', new.id, card_id, '', new.content FROM conversations WHERE id = new.conversation_id;
+END;
+CREATE TRIGGER search_messages_ad AFTER DELETE ON conversation_messages BEGIN
+  DELETE FROM search_index WHERE kind = 'message' AND source_id = old.id;
+END;
+
+-- Artifact contents live on disk; the app fills in `body` when it writes them.
+CREATE TRIGGER search_artifacts_ai AFTER INSERT ON artifacts BEGIN
+  INSERT INTO search_index (kind, source_id, card_id, title, body)
+    VALUES ('artifact', new.id, new.card_id, new.name, '');
+END;
+CREATE TRIGGER search_artifacts_au AFTER UPDATE OF name ON artifacts BEGIN
+  UPDATE search_index SET title = new.name WHERE kind = 'artifact' AND source_id = new.id;
+END;
+CREATE TRIGGER search_artifacts_ad AFTER DELETE ON artifacts BEGIN
+  DELETE FROM search_index WHERE kind = 'artifact' AND source_id = old.id;
+END;
+
+INSERT INTO search_index (kind, source_id, card_id, title, body)
+  SELECT 'card', id, id, title, description FROM cards;
+INSERT INTO search_index (kind, source_id, card_id, title, body)
+  SELECT 'question', id, card_id, question, COALESCE(resolution, '') FROM open_questions;
+INSERT INTO search_index (kind, source_id, card_id, title, body)
+  SELECT 'message', m.id, c.card_id, '', m.content
+  FROM conversation_messages m JOIN conversations c ON c.id = m.conversation_id;
+INSERT INTO search_index (kind, source_id, card_id, title, body)
+  SELECT 'artifact', id, card_id, name, '' FROM artifacts;
+";
+
+const ADD_CARD_EVENTS: &str = "
+CREATE TABLE card_events (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  project_id TEXT NOT NULL,
+  card_id TEXT NOT NULL,
+  event_type TEXT NOT NULL,
+  actor_type TEXT NOT NULL CHECK(actor_type IN ('user', 'agent', 'token')),
+  actor_id TEXT,
+  actor_name TEXT,
+  old_value TEXT,
+  new_value TEXT,
+  created_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_card_events_card ON card_events(card_id, id);
+
+CREATE TRIGGER card_events_no_update BEFORE UPDATE ON card_events BEGIN
+  SELECT RAISE(ABORT, 'card_events is append-only');
+END;
+CREATE TRIGGER card_events_no_delete BEFORE DELETE ON card_events BEGIN
+  SELECT RAISE(ABORT, 'card_events is append-only');
+END;
+";
+
+const ADD_CARD_LOGS: &str = "
+CREATE TABLE card_logs (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  card_id TEXT NOT NULL REFERENCES cards(id) ON DELETE CASCADE,
+  workspace_id TEXT,
+  level TEXT NOT NULL CHECK(level IN ('debug', 'info', 'warn', 'error')),
+  message TEXT NOT NULL,
+  data TEXT,
+  created_at TEXT NOT NULL
+);
+
+CREATE INDEX idx_card_logs_card ON card_logs(card_id, id);
+";
+
+/// Creates the full-text index and fills it from existing rows. Artifact
+/// paths are relative to the project directory the database lives in, so
+/// their contents are read from next to the database file.
+fn migrate_add_search_index(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(ADD_SEARCH_INDEX)
+        .map_err(|e| format!("Failed to create search index: {e}"))?;
+
+    let Some(project_dir) = conn
+        .path()
+        .filter(|p| !p.is_empty())
+        .and_then(|p| std::path::Path::new(p).parent().map(|d| d.to_path_buf()))
+    else {
+        return Ok(());
+    };
+    let artifacts: Vec<(String, String)> = conn
+        .prepare("SELECT id, path FROM artifacts")
+        .map_err(|e| format!("Failed to query artifacts: {e}"))?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| format!("Failed to read artifacts: {e}"))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to collect artifacts: {e}"))?;
+    for (id, path) in artifacts {
+        if let Ok(content) = std::fs::read_to_string(project_dir.join(path)) {
+            conn.execute(
+                "UPDATE search_index SET body = ?1 WHERE kind = 'artifact' AND source_id = ?2",
+                rusqlite::params![content, id],
+            )
+            .map_err(|e| format!("Failed to index artifact {id}: {e}"))?;
+        }
+    }
+    Ok(())
+}
+
 const MIGRATIONS: &[Migration] = &[
     Migration {
         version: 1,
@@ -50,6 +293,46 @@ const MIGRATIONS: &[Migration] = &[
         name: "add_status_prompts_to_statuses",
         up: MigrationFn::Func(migrate_add_status_prompts_to_statuses),
     },
+    Migration {
+        version: 3,
+        name: "add_launch_queue",
+        up: MigrationFn::Func(migrate_add_launch_queue),
+    },
+    Migration {
+        version: 4,
+        name: "add_status_auto_run",
+        up: MigrationFn::Func(migrate_add_status_auto_run),
+    },
+    Migration {
+        version: 5,
+        name: "add_workspace_exit_reason",
+        up: MigrationFn::Func(migrate_add_workspace_exit_reason),
+    },
+    Migration {
+        version: 6,
+        name: "add_workspace_pid_start_time",
+        up: MigrationFn::Func(migrate_add_workspace_pid_start_time),
+    },
+    Migration {
+        version: 7,
+        name: "add_card_dependencies",
+        up: MigrationFn::Sql(ADD_CARD_DEPENDENCIES),
+    },
+    Migration {
+        version: 8,
+        name: "add_search_index",
+        up: MigrationFn::Func(migrate_add_search_index),
+    },
+    Migration {
+        version: 9,
+        name: "add_card_events",
+        up: MigrationFn::Sql(ADD_CARD_EVENTS),
+    },
+    Migration {
+        version: 10,
+        name: "add_card_logs",
+        up: MigrationFn::Sql(ADD_CARD_LOGS),
+    },
 ];
 
 pub fn run_migrations(conn: &Connection) -> Result<(), String> {
@@ -110,7 +393,7 @@ mod tests {
         let count: i64 = conn
             .query_row("SELECT COUNT(*) FROM _migrations", [], |row| row.get(0))
             .unwrap();
-        assert_eq!(count, 2);
+        assert_eq!(count, 10);
     }
 
     #[test]
@@ -123,7 +406,7 @@ mod tests {
         let count: i64 = conn
             .query_row("SELECT COUNT(*) FROM _migrations", [], |row| row.get(0))
             .unwrap();
-        assert_eq!(count, 2);
+        assert_eq!(count, 10);
     }
 
     #[test]
@@ -149,6 +432,11 @@ mod tests {
         assert!(tables.contains(&"conversation_messages".to_string()));
         assert!(tables.contains(&"agent_workspaces".to_string()));
         assert!(tables.contains(&"artifacts".to_string()));
+        assert!(tables.contains(&"launch_queue".to_string()));
+        assert!(tables.contains(&"card_dependencies".to_string()));
+        assert!(tables.contains(&"search_index".to_string()));
+        assert!(tables.contains(&"card_events".to_string()));
+        assert!(tables.contains(&"card_logs".to_string()));
     }
 
     #[test]
@@ -158,5 +446,78 @@ mod tests {
         run_migrations(&conn).unwrap();
 
         assert!(has_column(&conn, "statuses", "status_prompts"));
+        assert!(has_column(&conn, "statuses", "auto_run"));
+    }
+
+    #[test]
+    fn test_launch_queue_migration_keeps_workspaces() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON").unwrap();
+        conn.execute_batch(
+            "CREATE TABLE _migrations (version INTEGER PRIMARY KEY, name TEXT NOT NULL, applied_at TEXT NOT NULL)",
+        )
+        .unwrap();
+        conn.execute_batch(include_str!("schema.sql")).unwrap();
+        conn.execute_batch(
+            "INSERT INTO _migrations VALUES (1, 'initial_schema', '2024-01-01'), (2, 'add_status_prompts_to_statuses', '2024-01-01');
+             INSERT INTO projects (id, name, created_at, updated_at) VALUES ('p', 'P', '2024-01-01', '2024-01-01');
+             INSERT INTO statuses (id, project_id, \"group\", name, sort_order, created_at) VALUES ('s', 'p', 'Backlog', 'Backlog', 0, '2024-01-01');
+             INSERT INTO cards (id, project_id, status_id, title, sort_order, created_at, updated_at) VALUES ('c', 'p', 's', 'C', 0, '2024-01-01', '2024-01-01');
+             INSERT INTO agent_workspaces (id, card_id, agent_type, status, attached_at) VALUES ('w', 'c', 'claude', 'completed', '2024-01-01');",
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let status: String = conn
+            .query_row("SELECT status FROM agent_workspaces WHERE id = 'w'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(status, "completed");
+        conn.execute(
+            "INSERT INTO agent_workspaces (id, card_id, agent_type, status, attached_at) VALUES ('q', 'c', 'claude', 'queued', '2024-01-01')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO agent_workspaces (id, card_id, agent_type, status, exit_reason, attached_at) \
+             VALUES ('t', 'c', 'claude', 'timed_out', 'Exceeded max runtime of 60s', '2024-01-01')",
+            [],
+        )
+        .unwrap();
+        assert!(has_column(&conn, "agent_workspaces", "pid_start_time"));
+    }
+
+    #[test]
+    fn test_search_index_backfills_existing_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("artifacts/c")).unwrap();
+        std::fs::write(dir.path().join("artifacts/c/plan.md"), "Migrate the billing tables").unwrap();
+
+        let conn = Connection::open(dir.path().join("db.sqlite")).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE _migrations (version INTEGER PRIMARY KEY, name TEXT NOT NULL, applied_at TEXT NOT NULL)",
+        )
+        .unwrap();
+        conn.execute_batch(include_str!("schema.sql")).unwrap();
+        conn.execute_batch(
+            "INSERT INTO _migrations VALUES (1, 'initial_schema', '2024-01-01');
+             INSERT INTO projects (id, name, created_at, updated_at) VALUES ('p', 'P', '2024-01-01', '2024-01-01');
+             INSERT INTO statuses (id, project_id, \"group\", name, sort_order, created_at) VALUES ('s', 'p', 'Backlog', 'Backlog', 0, '2024-01-01');
+             INSERT INTO cards (id, project_id, status_id, title, sort_order, created_at, updated_at) VALUES ('c', 'p', 's', 'Invoices', 0, '2024-01-01', '2024-01-01');
+             INSERT INTO artifacts (id, card_id, name, path, created_by, created_at, updated_at) \
+               VALUES ('a', 'c', 'Plan', 'artifacts/c/plan.md', 'agent', '2024-01-01', '2024-01-01');",
+        )
+        .unwrap();
+
+        run_migrations(&conn).unwrap();
+
+        let hits: Vec<String> = conn
+            .prepare("SELECT kind FROM search_index WHERE search_index MATCH 'billing OR invoices' ORDER BY kind")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(hits, vec!["artifact", "card"]);
     }
 }
@@ -0,0 +1,261 @@
+use std::path::{Path, PathBuf};
+
+use crate::config::global::{AdapterKind, AdapterTemplate, AgentProfile, OutputFormat, PromptDelivery};
+use crate::fs::worktrees::claude_worktree_path;
+
+/// Knows how to turn a launch into a particular agent CLI's command line.
+/// The profile's own flags always come right after `command_prefix`.
+pub trait AgentAdapter: Send + Sync {
+    fn prompt_delivery(&self) -> PromptDelivery {
+        PromptDelivery::Arg
+    }
+
+    /// Arguments placed before the profile's flags, such as a subcommand.
+    fn command_prefix(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Arguments carrying the prompt, or the prompt file's path when the
+    /// prompt is delivered by file. Empty for stdin delivery.
+    fn prompt_args(&self, prompt: &str) -> Vec<String>;
+
+    fn model_args(&self, model: &str) -> Vec<String>;
+
+    /// Arguments that continue `session_id` in place of a new prompt, or
+    /// `None` if the agent can't resume sessions.
+    fn resume_args(&self, _session_id: &str) -> Option<Vec<String>> {
+        None
+    }
+
+    /// Arguments asking the agent to create a worktree named `name`, for
+    /// agents that manage worktrees themselves.
+    fn worktree_args(&self, _name: &str) -> Option<Vec<String>> {
+        None
+    }
+
+    /// Where the agent puts the worktree it creates. `None` means Maestro
+    /// has to create the worktree and start the agent inside it.
+    fn worktree_path(&self, _repo_path: &str, _name: &str) -> Option<PathBuf> {
+        None
+    }
+
+    /// Output format used when the profile doesn't set one.
+    fn output_format(&self, _flags: &[String]) -> OutputFormat {
+        OutputFormat::Text
+    }
+}
+
+pub struct ClaudeCodeAdapter;
+
+impl AgentAdapter for ClaudeCodeAdapter {
+    fn prompt_args(&self, prompt: &str) -> Vec<String> {
+        vec!["--print".to_string(), prompt.to_string()]
+    }
+
+    fn model_args(&self, model: &str) -> Vec<String> {
+        vec!["--model".to_string(), model.to_string()]
+    }
+
+    fn resume_args(&self, session_id: &str) -> Option<Vec<String>> {
+        Some(vec!["--resume".to_string(), session_id.to_string()])
+    }
+
+    fn worktree_args(&self, name: &str) -> Option<Vec<String>> {
+        Some(vec!["--worktree".to_string(), name.to_string()])
+    }
+
+    fn worktree_path(&self, repo_path: &str, name: &str) -> Option<PathBuf> {
+        Some(claude_worktree_path(repo_path, name))
+    }
+
+    fn output_format(&self, flags: &[String]) -> OutputFormat {
+        let stream_json = flags.iter().any(|f| f == "--output-format=stream-json")
+            || flags
+                .windows(2)
+                .any(|pair| pair[0] == "--output-format" && pair[1] == "stream-json");
+        if stream_json {
+            OutputFormat::ClaudeStreamJson
+        } else {
+            OutputFormat::Text
+        }
+    }
+}
+
+/// OpenAI Codex CLI, run non-interactively through `codex exec`.
+pub struct CodexAdapter;
+
+impl AgentAdapter for CodexAdapter {
+    fn command_prefix(&self) -> Vec<String> {
+        vec!["exec".to_string()]
+    }
+
+    fn prompt_args(&self, prompt: &str) -> Vec<String> {
+        vec![prompt.to_string()]
+    }
+
+    fn model_args(&self, model: &str) -> Vec<String> {
+        vec!["--model".to_string(), model.to_string()]
+    }
+
+    fn resume_args(&self, session_id: &str) -> Option<Vec<String>> {
+        Some(vec!["resume".to_string(), session_id.to_string()])
+    }
+}
+
+pub struct AiderAdapter;
+
+impl AgentAdapter for AiderAdapter {
+    fn prompt_args(&self, prompt: &str) -> Vec<String> {
+        vec!["--message".to_string(), prompt.to_string()]
+    }
+
+    fn model_args(&self, model: &str) -> Vec<String> {
+        vec!["--model".to_string(), model.to_string()]
+    }
+}
+
+/// Adapter driven entirely by a profile's `[template]` table.
+pub struct GenericAdapter {
+    template: AdapterTemplate,
+}
+
+impl GenericAdapter {
+    pub fn new(template: AdapterTemplate) -> Self {
+        Self { template }
+    }
+}
+
+impl AgentAdapter for GenericAdapter {
+    fn prompt_delivery(&self) -> PromptDelivery {
+        self.template.prompt
+    }
+
+    fn prompt_args(&self, prompt: &str) -> Vec<String> {
+        if self.template.prompt == PromptDelivery::Stdin {
+            return Vec::new();
+        }
+        fill(&self.template.prompt_args, &[("prompt", prompt)])
+    }
+
+    fn model_args(&self, model: &str) -> Vec<String> {
+        fill(&self.template.model_args, &[("model", model)])
+    }
+
+    fn resume_args(&self, session_id: &str) -> Option<Vec<String>> {
+        if self.template.resume_args.is_empty() {
+            return None;
+        }
+        Some(fill(&self.template.resume_args, &[("session_id", session_id)]))
+    }
+
+    fn worktree_args(&self, name: &str) -> Option<Vec<String>> {
+        if self.template.worktree_args.is_empty() || self.template.worktree_path.is_none() {
+            return None;
+        }
+        Some(fill(&self.template.worktree_args, &[("worktree", name)]))
+    }
+
+    fn worktree_path(&self, repo_path: &str, name: &str) -> Option<PathBuf> {
+        self.worktree_args(name)?;
+        let path = self.template.worktree_path.as_deref()?;
+        Some(PathBuf::from(fill_one(path, &[("repo", repo_path), ("worktree", name)])))
+    }
+}
+
+fn fill(templates: &[String], vars: &[(&str, &str)]) -> Vec<String> {
+    templates.iter().map(|t| fill_one(t, vars)).collect()
+}
+
+fn fill_one(template: &str, vars: &[(&str, &str)]) -> String {
+    vars.iter().fold(template.to_string(), |acc, (name, value)| {
+        acc.replace(&format!("{{{name}}}"), value)
+    })
+}
+
+/// Picks the adapter for a profile, inferring it from the binary name when
+/// the profile doesn't say. Unknown binaries get Claude Code's conventions,
+/// which is what every profile used before adapters existed.
+pub fn adapter_for(profile: &AgentProfile) -> Box<dyn AgentAdapter> {
+    let kind = profile.adapter.unwrap_or_else(|| infer_adapter(&profile.binary));
+    match kind {
+        AdapterKind::ClaudeCode => Box::new(ClaudeCodeAdapter),
+        AdapterKind::Codex => Box::new(CodexAdapter),
+        AdapterKind::Aider => Box::new(AiderAdapter),
+        AdapterKind::Generic => Box::new(GenericAdapter::new(profile.template.clone().unwrap_or_default())),
+    }
+}
+
+fn infer_adapter(binary: &str) -> AdapterKind {
+    match Path::new(binary).file_name().and_then(|n| n.to_str()) {
+        Some("codex") => AdapterKind::Codex,
+        Some("aider") => AdapterKind::Aider,
+        _ => AdapterKind::ClaudeCode,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(binary: &str, adapter: Option<AdapterKind>) -> AgentProfile {
+        AgentProfile {
+            binary: binary.to_string(),
+            flags: Vec::new(),
+            custom_command: None,
+            env_vars: None,
+            session_id_pattern: None,
+            output_format: OutputFormat::Text,
+            limits: Default::default(),
+            pty: false,
+            adapter,
+            template: None,
+        }
+    }
+
+    #[test]
+    fn test_adapter_inferred_from_binary() {
+        let codex = adapter_for(&profile("/usr/local/bin/codex", None));
+        assert_eq!(codex.command_prefix(), vec!["exec"]);
+
+        let aider = adapter_for(&profile("aider", None));
+        assert_eq!(aider.prompt_args("hi"), vec!["--message", "hi"]);
+        assert!(aider.resume_args("s").is_none());
+
+        let claude = adapter_for(&profile("my-wrapper", None));
+        assert_eq!(claude.prompt_args("hi"), vec!["--print", "hi"]);
+
+        let explicit = adapter_for(&profile("codex", Some(AdapterKind::Aider)));
+        assert!(explicit.command_prefix().is_empty());
+    }
+
+    #[test]
+    fn test_claude_output_format_follows_flags() {
+        let flags = vec!["--output-format".to_string(), "stream-json".to_string()];
+        assert_eq!(ClaudeCodeAdapter.output_format(&flags), OutputFormat::ClaudeStreamJson);
+        assert_eq!(ClaudeCodeAdapter.output_format(&[]), OutputFormat::Text);
+    }
+
+    #[test]
+    fn test_generic_adapter_fills_templates() {
+        let adapter = GenericAdapter::new(AdapterTemplate {
+            prompt: PromptDelivery::File,
+            prompt_args: vec!["--instructions={prompt}".to_string()],
+            model_args: vec!["-m".to_string(), "{model}".to_string()],
+            resume_args: vec!["--continue".to_string(), "{session_id}".to_string()],
+            worktree_args: vec!["--tree".to_string(), "{worktree}".to_string()],
+            worktree_path: Some("{repo}/.trees/{worktree}".to_string()),
+        });
+
+        assert_eq!(adapter.prompt_delivery(), PromptDelivery::File);
+        assert_eq!(adapter.prompt_args("/tmp/p.md"), vec!["--instructions=/tmp/p.md"]);
+        assert_eq!(adapter.model_args("fast"), vec!["-m", "fast"]);
+        assert_eq!(adapter.resume_args("abc"), Some(vec!["--continue".to_string(), "abc".to_string()]));
+        assert_eq!(adapter.worktree_args("wt"), Some(vec!["--tree".to_string(), "wt".to_string()]));
+        assert_eq!(adapter.worktree_path("/repo", "wt"), Some(PathBuf::from("/repo/.trees/wt")));
+
+        let bare = GenericAdapter::new(AdapterTemplate::default());
+        assert_eq!(bare.prompt_args("do it"), vec!["do it"]);
+        assert!(bare.resume_args("abc").is_none());
+        assert!(bare.worktree_path("/repo", "wt").is_none());
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::MaestroEvent;
+
+const DEFAULT_CAPACITY: usize = 2000;
+pub(crate) const DEFAULT_MAX_WORKSPACES: usize = 64;
+const DEFAULT_RECENT_CAPACITY: usize = 1000;
+const DEFAULT_RECENT_AGENT_CAPACITY: usize = 2000;
+
+#[derive(Debug, Clone)]
+pub struct SequencedEvent {
+    pub seq: u64,
+    pub event: MaestroEvent,
+}
+
+#[derive(Debug, Default)]
+pub struct Replay {
+    pub events: Vec<SequencedEvent>,
+    /// Events newer than the requested cursor were already evicted, so the
+    /// replay has a gap at the front.
+    pub truncated: bool,
+}
+
+struct WorkspaceBuffer {
+    events: VecDeque<SequencedEvent>,
+    dropped_through: u64,
+}
+
+/// Bounded per-workspace history of agent events, kept so clients that
+/// connect mid-run can catch up.
+pub struct OutputBuffer {
+    workspaces: HashMap<String, WorkspaceBuffer>,
+    capacity: usize,
+    max_workspaces: usize,
+}
+
+impl OutputBuffer {
+    pub fn new() -> Self {
+        Self::with_limits(DEFAULT_CAPACITY, DEFAULT_MAX_WORKSPACES)
+    }
+
+    pub fn with_limits(capacity: usize, max_workspaces: usize) -> Self {
+        Self {
+            workspaces: HashMap::new(),
+            capacity,
+            max_workspaces,
+        }
+    }
+
+    pub fn push(&mut self, event: &SequencedEvent) {
+        if !event.event.event_type().starts_with("agent-") {
+            return;
+        }
+        let Some(workspace_id) = event.event.scope() else {
+            return;
+        };
+
+        if !self.workspaces.contains_key(workspace_id) {
+            self.evict_stale_workspace();
+        }
+        let capacity = self.capacity;
+        let buffer = self
+            .workspaces
+            .entry(workspace_id.to_string())
+            .or_insert_with(|| WorkspaceBuffer {
+                events: VecDeque::with_capacity(capacity.min(256)),
+                dropped_through: 0,
+            });
+
+        if buffer.events.len() >= capacity {
+            if let Some(dropped) = buffer.events.pop_front() {
+                buffer.dropped_through = dropped.seq;
+            }
+        }
+        buffer.events.push_back(event.clone());
+    }
+
+    pub fn replay(&self, workspace_id: &str, since: Option<u64>) -> Replay {
+        let Some(buffer) = self.workspaces.get(workspace_id) else {
+            // A client with a cursor saw events here before, which have
+            // since been evicted along with the workspace.
+            return Replay {
+                events: Vec::new(),
+                truncated: since.is_some(),
+            };
+        };
+        let since = since.unwrap_or(0);
+        Replay {
+            events: buffer
+                .events
+                .iter()
+                .filter(|e| e.seq > since)
+                .cloned()
+                .collect(),
+            truncated: since < buffer.dropped_through,
+        }
+    }
+
+    /// Drops the workspace whose most recent event is oldest once the
+    /// workspace limit is reached.
+    fn evict_stale_workspace(&mut self) {
+        if self.workspaces.len() < self.max_workspaces {
+            return;
+        }
+        let stale = self
+            .workspaces
+            .iter()
+            .min_by_key(|(_, b)| b.events.back().map(|e| e.seq).unwrap_or(0))
+            .map(|(id, _)| id.clone());
+        if let Some(id) = stale {
+            self.workspaces.remove(&id);
+        }
+    }
+}
+
+impl Default for OutputBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The most recent events of every type, so an event stream can resume
+/// from the last sequence number a client saw. Agent events are kept in a
+/// ring of their own so a chatty agent can't push every state change out.
+pub struct RecentEvents {
+    state: Ring,
+    agent: Ring,
+}
+
+struct Ring {
+    events: VecDeque<SequencedEvent>,
+    capacity: usize,
+    dropped_through: u64,
+}
+
+impl Ring {
+    fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::new(),
+            capacity,
+            dropped_through: 0,
+        }
+    }
+
+    fn push(&mut self, event: &SequencedEvent) {
+        if self.events.len() >= self.capacity {
+            if let Some(dropped) = self.events.pop_front() {
+                self.dropped_through = dropped.seq;
+            }
+        }
+        self.events.push_back(event.clone());
+    }
+}
+
+impl RecentEvents {
+    pub fn new() -> Self {
+        Self::with_capacities(DEFAULT_RECENT_CAPACITY, DEFAULT_RECENT_AGENT_CAPACITY)
+    }
+
+    pub fn with_capacities(state_capacity: usize, agent_capacity: usize) -> Self {
+        Self {
+            state: Ring::new(state_capacity),
+            agent: Ring::new(agent_capacity),
+        }
+    }
+
+    pub fn push(&mut self, event: &SequencedEvent) {
+        if event.event.event_type().starts_with("agent-") {
+            self.agent.push(event);
+        } else {
+            self.state.push(event);
+        }
+    }
+
+    /// Events newer than `since`, in sequence order. A cursor ahead of the
+    /// newest event comes from before a restart, when numbering began
+    /// again, so it can't be trusted.
+    pub fn replay(&self, since: u64, next_seq: u64) -> Replay {
+        let stale = since >= next_seq;
+        let since = if stale { 0 } else { since };
+        let mut events: Vec<SequencedEvent> = self
+            .state
+            .events
+            .iter()
+            .chain(&self.agent.events)
+            .filter(|e| e.seq > since)
+            .cloned()
+            .collect();
+        events.sort_by_key(|e| e.seq);
+        Replay {
+            events,
+            truncated: stale || since < self.state.dropped_through || since < self.agent.dropped_through,
+        }
+    }
+}
+
+impl Default for RecentEvents {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::stream::AgentOutputEvent;
+
+    fn output(seq: u64, workspace_id: &str) -> SequencedEvent {
+        SequencedEvent {
+            seq,
+            event: MaestroEvent::AgentOutput(AgentOutputEvent {
+                workspace_id: workspace_id.to_string(),
+                stream: "stdout".to_string(),
+                line: format!("line {seq}"),
+            }),
+        }
+    }
+
+    fn seqs(replay: &Replay) -> Vec<u64> {
+        replay.events.iter().map(|e| e.seq).collect()
+    }
+
+    #[test]
+    fn test_replay_returns_events_after_cursor() {
+        let mut buffer = OutputBuffer::new();
+        for seq in 1..=5 {
+            buffer.push(&output(seq, "ws-1"));
+        }
+        buffer.push(&output(6, "ws-2"));
+
+        assert_eq!(seqs(&buffer.replay("ws-1", None)), vec![1, 2, 3, 4, 5]);
+        assert_eq!(seqs(&buffer.replay("ws-1", Some(3))), vec![4, 5]);
+        assert_eq!(seqs(&buffer.replay("ws-2", None)), vec![6]);
+        assert!(buffer.replay("ws-3", None).events.is_empty());
+    }
+
+    #[test]
+    fn test_buffer_is_bounded_and_reports_truncation() {
+        let mut buffer = OutputBuffer::with_limits(3, 8);
+        for seq in 1..=5 {
+            buffer.push(&output(seq, "ws-1"));
+        }
+
+        let replay = buffer.replay("ws-1", None);
+        assert_eq!(seqs(&replay), vec![3, 4, 5]);
+        assert!(replay.truncated);
+
+        let replay = buffer.replay("ws-1", Some(2));
+        assert_eq!(seqs(&replay), vec![3, 4, 5]);
+        assert!(!replay.truncated);
+    }
+
+    #[test]
+    fn test_ignores_non_agent_events() {
+        let mut buffer = OutputBuffer::new();
+        buffer.push(&SequencedEvent {
+            seq: 1,
+            event: MaestroEvent::WorkspacesChanged {
+                project_id: "ws-1".to_string(),
+            },
+        });
+        assert!(buffer.replay("ws-1", None).events.is_empty());
+    }
+
+    #[test]
+    fn test_recent_events_resume_from_cursor() {
+        let mut recent = RecentEvents::with_capacities(1, 2);
+        recent.push(&SequencedEvent {
+            seq: 1,
+            event: MaestroEvent::ProjectsChanged,
+        });
+        for seq in 2..=4 {
+            recent.push(&output(seq, "ws-1"));
+        }
+
+        let replay = recent.replay(2, 5);
+        assert_eq!(seqs(&replay), vec![3, 4]);
+        assert!(!replay.truncated);
+        // Agent output only evicts older agent output.
+        let replay = recent.replay(0, 5);
+        assert_eq!(seqs(&replay), vec![1, 3, 4]);
+        assert!(replay.truncated);
+        // A cursor from before a restart gets everything still held.
+        let replay = recent.replay(40, 5);
+        assert_eq!(seqs(&replay), vec![1, 3, 4]);
+        assert!(replay.truncated);
+    }
+
+    #[test]
+    fn test_evicts_least_recent_workspace() {
+        let mut buffer = OutputBuffer::with_limits(10, 2);
+        buffer.push(&output(1, "ws-1"));
+        buffer.push(&output(2, "ws-2"));
+        buffer.push(&output(3, "ws-1"));
+        buffer.push(&output(4, "ws-3"));
+
+        assert!(buffer.replay("ws-2", None).events.is_empty());
+        assert!(!buffer.replay("ws-2", None).truncated);
+        assert!(buffer.replay("ws-2", Some(2)).truncated);
+        assert_eq!(seqs(&buffer.replay("ws-1", None)), vec![1, 3]);
+        assert_eq!(seqs(&buffer.replay("ws-3", None)), vec![4]);
+    }
+}
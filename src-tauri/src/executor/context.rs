@@ -1,6 +1,10 @@
-use crate::config::global::GlobalConfig;
+use std::path::{Path, PathBuf};
+
+use crate::config::global::{AgentLimits, GlobalConfig, OutputFormat, PromptDelivery};
 use crate::config::resolution::{resolve_agent_config, ResolvedAgentConfig};
 
+use super::adapter::{adapter_for, AgentAdapter};
+
 #[derive(Debug)]
 pub struct AgentContext {
     pub binary: String,
@@ -8,6 +12,16 @@ pub struct AgentContext {
     pub working_dir: String,
     pub env: Vec<(String, String)>,
     pub system_prompt: String,
+    pub session_id_pattern: Option<String>,
+    pub output_format: OutputFormat,
+    pub limits: AgentLimits,
+    pub pty: bool,
+    /// File the system prompt is written to before spawning, for agents that
+    /// read their prompt from a file. It lives in the workspace's log
+    /// directory, so it's private to the user and goes when the agent does.
+    pub prompt_file: Option<PathBuf>,
+    /// Whether the system prompt is sent as the agent's first line of input.
+    pub stdin_prompt: bool,
 }
 
 pub struct CardInfo {
@@ -28,25 +42,52 @@ pub fn assemble_context(
     socket_path: Option<&str>,
     worktree_name: Option<&str>,
     status_prompts: &[String],
+    resume_session: Option<&str>,
+    prompt_dir: &Path,
 ) -> Result<AgentContext, String> {
     let resolved = resolve_agent_config(global_config, project_agent_config, status_group);
 
     let (binary, base_flags) = resolve_binary_and_flags(global_config, &resolved)?;
+    let profile = global_config.agents.get(&resolved.agent);
+    let adapter = resolve_adapter(global_config, project_agent_config, status_group)?;
+    let session_id_pattern = profile.and_then(|p| p.session_id_pattern.clone());
+    let output_format = match profile.map(|p| p.output_format).unwrap_or_default() {
+        OutputFormat::Text => adapter.output_format(&base_flags),
+        format => format,
+    };
+    let limits = profile.map(|p| p.limits).unwrap_or_default();
+    let pty = profile.is_some_and(|p| p.pty);
 
     let system_prompt = build_system_prompt(&resolved, card, artifact_contents, socket_path.is_some(), status_prompts);
 
-    let mut args = base_flags;
-    args.push("--print".to_string());
-    args.push(system_prompt.clone());
+    let mut args = adapter.command_prefix();
+    args.extend(base_flags);
+
+    let mut prompt_file = None;
+    let mut stdin_prompt = false;
+    if let Some(session_id) = resume_session {
+        let resume_args = adapter
+            .resume_args(session_id)
+            .ok_or_else(|| format!("Agent '{}' does not support resuming sessions", resolved.agent))?;
+        args.extend(resume_args);
+    } else {
+        match adapter.prompt_delivery() {
+            PromptDelivery::Arg => args.extend(adapter.prompt_args(&system_prompt)),
+            PromptDelivery::File => {
+                let path = prompt_dir.join("prompt.md");
+                args.extend(adapter.prompt_args(&path.to_string_lossy()));
+                prompt_file = Some(path);
+            }
+            PromptDelivery::Stdin => stdin_prompt = true,
+        }
+    }
 
     if let Some(ref model) = resolved.model {
-        args.push("--model".to_string());
-        args.push(model.clone());
+        args.extend(adapter.model_args(model));
     }
 
     if let Some(name) = worktree_name {
-        args.push("--worktree".to_string());
-        args.push(name.to_string());
+        args.extend(adapter.worktree_args(name).unwrap_or_default());
     }
 
     let mut env = vec![("MAESTRO_CARD_ID".to_string(), card.id.clone())];
@@ -62,9 +103,29 @@ pub fn assemble_context(
         working_dir: working_dir.to_string(),
         env,
         system_prompt,
+        session_id_pattern,
+        output_format,
+        limits,
+        pty,
+        prompt_file,
+        stdin_prompt,
     })
 }
 
+/// The adapter for whichever agent profile handles `status_group`.
+pub fn resolve_adapter(
+    global_config: &GlobalConfig,
+    project_agent_config: &serde_json::Value,
+    status_group: &str,
+) -> Result<Box<dyn AgentAdapter>, String> {
+    let resolved = resolve_agent_config(global_config, project_agent_config, status_group);
+    global_config
+        .agents
+        .get(&resolved.agent)
+        .map(adapter_for)
+        .ok_or_else(|| format!("Agent profile '{}' not found in config", resolved.agent))
+}
+
 fn resolve_binary_and_flags(
     global_config: &GlobalConfig,
     resolved: &ResolvedAgentConfig,
@@ -153,7 +214,7 @@ fn build_system_prompt(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::global::{AgentProfile, DefaultsConfig, StatusGroupConfig};
+    use crate::config::global::{AdapterKind, AdapterTemplate, AgentProfile, DefaultsConfig, StatusGroupConfig};
     use std::collections::HashMap;
 
     fn test_config() -> GlobalConfig {
@@ -165,6 +226,12 @@ mod tests {
                 flags: vec!["--dangerously-skip-permissions".to_string()],
                 custom_command: None,
                 env_vars: None,
+                session_id_pattern: None,
+                output_format: OutputFormat::Text,
+                limits: AgentLimits::default(),
+                pty: false,
+                adapter: None,
+                template: None,
             },
         );
 
@@ -201,7 +268,7 @@ mod tests {
         };
 
         let ctx =
-            assemble_context(&config, &serde_json::json!({}), "Backlog", &card, "/tmp/work", &[], None, None, &[])
+            assemble_context(&config, &serde_json::json!({}), "Backlog", &card, "/tmp/work", &[], None, None, &[], None, Path::new("/tmp/prompt"))
                 .unwrap();
 
         assert_eq!(ctx.binary, "claude");
@@ -229,7 +296,7 @@ mod tests {
         };
 
         let ctx =
-            assemble_context(&config, &serde_json::json!({}), "Backlog", &card, "/tmp/work", &[], None, None, &[])
+            assemble_context(&config, &serde_json::json!({}), "Backlog", &card, "/tmp/work", &[], None, None, &[], None, Path::new("/tmp/prompt"))
                 .unwrap();
 
         assert!(ctx.system_prompt.contains("Parent Card: Parent Feature"));
@@ -248,7 +315,7 @@ mod tests {
         };
 
         let project_config = serde_json::json!({ "agent": "nonexistent" });
-        let result = assemble_context(&config, &project_config, "Backlog", &card, "/tmp/work", &[], None, None, &[]);
+        let result = assemble_context(&config, &project_config, "Backlog", &card, "/tmp/work", &[], None, None, &[], None, Path::new("/tmp/prompt"));
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("not found"));
     }
@@ -279,6 +346,8 @@ mod tests {
             None,
             None,
             &[],
+            None,
+            Path::new("/tmp/prompt"),
         )
         .unwrap();
 
@@ -310,6 +379,8 @@ mod tests {
             None,
             Some("a1b2c3d4-build-feature-x"),
             &[],
+            None,
+            Path::new("/tmp/prompt"),
         )
         .unwrap();
 
@@ -340,6 +411,8 @@ mod tests {
             None,
             None,
             &prompts,
+            None,
+            Path::new("/tmp/prompt"),
         )
         .unwrap();
 
@@ -368,6 +441,8 @@ mod tests {
             None,
             None,
             &[],
+            None,
+            Path::new("/tmp/prompt"),
         )
         .unwrap();
 
@@ -397,6 +472,8 @@ mod tests {
             None,
             None,
             &prompts,
+            None,
+            Path::new("/tmp/prompt"),
         )
         .unwrap();
 
@@ -426,6 +503,8 @@ mod tests {
             None,
             None,
             &prompts,
+            None,
+            Path::new("/tmp/prompt"),
         )
         .unwrap();
 
@@ -433,4 +512,132 @@ mod tests {
         let task_pos = ctx.system_prompt.find("# Task: Build feature X").unwrap();
         assert!(brainstorming_pos < task_pos, "Status prompts should appear before card info");
     }
+
+    fn card() -> CardInfo {
+        CardInfo {
+            id: "card-123".to_string(),
+            title: "Build feature X".to_string(),
+            description: String::new(),
+            parent_title: None,
+            parent_description: None,
+        }
+    }
+
+    #[test]
+    fn test_assemble_context_resume_replaces_prompt() {
+        let config = test_config();
+        let ctx = assemble_context(
+            &config,
+            &serde_json::json!({}),
+            "Backlog",
+            &card(),
+            "/tmp/work",
+            &[],
+            None,
+            None,
+            &[],
+            Some("session-1"),
+            Path::new("/tmp/prompt"),
+        )
+        .unwrap();
+
+        assert!(!ctx.args.contains(&"--print".to_string()));
+        assert!(ctx.args.windows(2).any(|w| w == ["--resume", "session-1"]));
+        assert!(ctx.args.contains(&"sonnet".to_string()));
+    }
+
+    #[test]
+    fn test_assemble_context_uses_profile_adapter() {
+        let mut config = test_config();
+        let profile = config.agents.get_mut("claude-code").unwrap();
+        profile.binary = "aider".to_string();
+        profile.flags = vec!["--yes-always".to_string()];
+
+        let ctx = assemble_context(
+            &config,
+            &serde_json::json!({}),
+            "Backlog",
+            &card(),
+            "/home/user/repo",
+            &[],
+            None,
+            Some("a1b2c3d4-build-feature-x"),
+            &[],
+            None,
+            Path::new("/tmp/prompt"),
+        )
+        .unwrap();
+
+        assert_eq!(ctx.args[0], "--yes-always");
+        assert_eq!(ctx.args[1], "--message");
+        assert!(ctx.args.windows(2).any(|w| w == ["--model", "sonnet"]));
+        assert!(!ctx.args.contains(&"--worktree".to_string()));
+
+        let result = assemble_context(
+            &config,
+            &serde_json::json!({}),
+            "Backlog",
+            &card(),
+            "/tmp/work",
+            &[],
+            None,
+            None,
+            &[],
+            Some("session-1"),
+            Path::new("/tmp/prompt"),
+        );
+        assert!(result.unwrap_err().contains("does not support resuming"));
+    }
+
+    #[test]
+    fn test_assemble_context_prompt_delivery() {
+        let mut config = test_config();
+        let profile = config.agents.get_mut("claude-code").unwrap();
+        profile.adapter = Some(AdapterKind::Generic);
+        profile.template = Some(AdapterTemplate {
+            prompt: PromptDelivery::File,
+            prompt_args: vec!["--prompt-file".to_string(), "{prompt}".to_string()],
+            ..AdapterTemplate::default()
+        });
+
+        let ctx = assemble_context(
+            &config,
+            &serde_json::json!({}),
+            "Backlog",
+            &card(),
+            "/tmp/work",
+            &[],
+            None,
+            None,
+            &[],
+            None,
+            Path::new("/tmp/prompt"),
+        )
+        .unwrap();
+        let prompt_file = ctx.prompt_file.as_ref().unwrap();
+        assert_eq!(prompt_file, Path::new("/tmp/prompt/prompt.md"));
+        assert!(ctx.args.windows(2).any(|w| w[0] == "--prompt-file" && w[1] == prompt_file.to_string_lossy()));
+        assert!(!ctx.args.iter().any(|a| a.contains("Build feature X")));
+        assert!(!ctx.stdin_prompt);
+
+        let profile = config.agents.get_mut("claude-code").unwrap();
+        profile.template.as_mut().unwrap().prompt = PromptDelivery::Stdin;
+        let ctx = assemble_context(
+            &config,
+            &serde_json::json!({}),
+            "Backlog",
+            &card(),
+            "/tmp/work",
+            &[],
+            None,
+            None,
+            &[],
+            None,
+            Path::new("/tmp/prompt"),
+        )
+        .unwrap();
+        assert!(ctx.stdin_prompt);
+        assert!(ctx.prompt_file.is_none());
+        assert_eq!(ctx.args, vec!["--dangerously-skip-permissions"]);
+    }
 }
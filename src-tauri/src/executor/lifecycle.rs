@@ -2,10 +2,19 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
 use tokio::process::Child;
-use tokio::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::{Duration, Instant};
 
 use crate::commands::projects::open_project_db;
+use crate::config::global::AgentLimits;
 
+#[cfg(unix)]
+use super::process_group::stop_process_group;
+pub use super::process_group::StopReport;
+
+use super::logs::WorkspaceLogs;
+use super::pty::AgentTerminalEvent;
+use super::stream::AgentOutputEvent;
 use super::{AgentEvent, AgentRegistry, EventBus, MaestroEvent};
 
 use serde::{Deserialize, Serialize};
@@ -15,6 +24,32 @@ pub struct AgentExitEvent {
     pub workspace_id: String,
     pub exit_code: Option<i32>,
     pub status: String,
+    pub reason: Option<String>,
+}
+
+const STOP_GRACE: Duration = Duration::from_secs(5);
+
+/// The agent a lifecycle monitor watches, and the limits it runs under.
+pub struct MonitoredAgent {
+    pub workspace_id: String,
+    pub project_id: String,
+    pub base_path: PathBuf,
+    pub limits: AgentLimits,
+}
+
+/// Why the monitor ended a run itself rather than waiting for it to exit.
+enum LimitExceeded {
+    Runtime(u64),
+    Idle(u64),
+}
+
+impl LimitExceeded {
+    fn reason(&self) -> String {
+        match self {
+            LimitExceeded::Runtime(secs) => format!("Exceeded max runtime of {secs}s"),
+            LimitExceeded::Idle(secs) => format!("No output for {secs}s"),
+        }
+    }
 }
 
 pub fn start_lifecycle_monitor(
@@ -25,51 +60,77 @@ pub fn start_lifecycle_monitor(
     project_id: String,
     base_path: PathBuf,
 ) {
-    start_lifecycle_monitor_inner(Some(app), None, registry, child, workspace_id, project_id, base_path);
+    start_lifecycle_monitor_inner(
+        Some(app),
+        None,
+        registry,
+        child,
+        MonitoredAgent {
+            workspace_id,
+            project_id,
+            base_path,
+            limits: AgentLimits::default(),
+        },
+    );
 }
 
+/// Waits for an agent to exit and records its terminal status. Runtime and
+/// idle limits are enforced here; the idle timer is fed by output events on
+/// the bus, so it only applies when one is given.
 pub fn start_lifecycle_monitor_inner(
     app: Option<AppHandle>,
     event_bus: Option<Arc<EventBus>>,
     registry: Arc<AgentRegistry>,
     mut child: Child,
-    workspace_id: String,
-    project_id: String,
-    base_path: PathBuf,
+    agent: MonitoredAgent,
 ) {
+    let MonitoredAgent {
+        workspace_id,
+        project_id,
+        base_path,
+        limits,
+    } = agent;
     tokio::spawn(async move {
+        let exceeded = watch_limits(&mut child, event_bus.as_ref(), &workspace_id, &limits).await;
+        if exceeded.is_some() {
+            terminate(&mut child).await;
+        }
         let exit_status = child.wait().await;
-        let (exit_code, status) = match exit_status {
-            Ok(status) => {
-                let code = status.code();
-                let s = if code == Some(0) {
-                    "completed"
-                } else {
-                    "failed"
-                };
-                (code, s.to_string())
-            }
-            Err(_) => (None, "failed".to_string()),
-        };
+        WorkspaceLogs::new(&base_path, &project_id, &workspace_id).remove();
 
-        registry.remove(&workspace_id);
+        let stopped = registry.remove(&workspace_id).is_none();
+        let (exit_code, status, reason) = match (&exceeded, exit_status) {
+            (Some(limit), Ok(status)) => (status.code(), "timed_out", Some(limit.reason())),
+            (Some(limit), Err(_)) => (None, "timed_out", Some(limit.reason())),
+            (None, Ok(status)) => match status.code() {
+                Some(0) => (Some(0), "completed", None),
+                Some(code) => (Some(code), "failed", None),
+                None if stopped => (None, "failed", None),
+                None => (None, "killed", signal_reason(&status)),
+            },
+            (None, Err(_)) => (None, "failed", None),
+        };
 
-        if let Ok(db) = open_project_db(&base_path, &project_id) {
-            let completed_at = chrono::Utc::now().to_rfc3339();
-            let _ = db.with_conn(|conn| {
-                conn.execute(
-                    "UPDATE agent_workspaces SET status = ?1, completed_at = ?2 WHERE id = ?3",
-                    rusqlite::params![status, completed_at, workspace_id],
-                )
-                .map_err(|e| format!("Failed to update workspace status: {e}"))?;
-                Ok(())
-            });
+        // An explicit stop records the workspace's final state itself.
+        if !stopped {
+            if let Ok(db) = open_project_db(&base_path, &project_id) {
+                let completed_at = chrono::Utc::now().to_rfc3339();
+                let _ = db.with_conn(|conn| {
+                    conn.execute(
+                        "UPDATE agent_workspaces SET status = ?1, exit_reason = ?2, completed_at = ?3 WHERE id = ?4",
+                        rusqlite::params![status, reason, completed_at, workspace_id],
+                    )
+                    .map_err(|e| format!("Failed to update workspace status: {e}"))?;
+                    Ok(())
+                });
+            }
         }
 
         let event = AgentExitEvent {
             workspace_id: workspace_id.clone(),
             exit_code,
-            status: status.clone(),
+            status: status.to_string(),
+            reason,
         };
         if let Some(ref bus) = event_bus {
             bus.emit(AgentEvent::Exit(event.clone()));
@@ -84,31 +145,221 @@ pub fn start_lifecycle_monitor_inner(
     });
 }
 
-pub async fn stop_agent_process(pid: u32) -> Result<(), String> {
-    #[cfg(unix)]
-    {
-        unsafe {
-            libc::kill(pid as i32, libc::SIGTERM);
-        }
+/// Resolves once the child exits (`None`) or a limit is exceeded.
+async fn watch_limits(
+    child: &mut Child,
+    event_bus: Option<&Arc<EventBus>>,
+    workspace_id: &str,
+    limits: &AgentLimits,
+) -> Option<LimitExceeded> {
+    let started = Instant::now();
+    let runtime_deadline = limits.max_runtime_secs.map(|secs| started + Duration::from_secs(secs));
+    let idle_timeout = limits
+        .idle_timeout_secs
+        .filter(|_| event_bus.is_some())
+        .map(Duration::from_secs);
+    let mut last_output = started;
+    let mut rx = event_bus.map(|bus| bus.subscribe());
 
-        let start = std::time::Instant::now();
-        loop {
-            if start.elapsed() > Duration::from_secs(5) {
-                unsafe {
-                    libc::kill(pid as i32, libc::SIGKILL);
+    loop {
+        let idle_deadline = idle_timeout.map(|timeout| last_output + timeout);
+        let next_deadline = match (runtime_deadline, idle_deadline) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        tokio::select! {
+            _ = child.wait() => return None,
+            event = recv_event(rx.as_mut()) => match event {
+                Some(AgentEvent::Output(AgentOutputEvent { workspace_id: id, .. }))
+                | Some(AgentEvent::Terminal(AgentTerminalEvent { workspace_id: id, .. }))
+                    if id == workspace_id =>
+                {
+                    last_output = Instant::now();
+                }
+                Some(_) => {}
+                None => rx = None,
+            },
+            _ = sleep_until(next_deadline) => {
+                let now = Instant::now();
+                if runtime_deadline.is_some_and(|d| now >= d) {
+                    return limits.max_runtime_secs.map(LimitExceeded::Runtime);
+                }
+                if idle_deadline.is_some_and(|d| now >= d) {
+                    return limits.idle_timeout_secs.map(LimitExceeded::Idle);
                 }
-                return Ok(());
-            }
-            let result = unsafe { libc::kill(pid as i32, 0) };
-            if result != 0 {
-                return Ok(());
             }
-            tokio::time::sleep(Duration::from_millis(100)).await;
         }
     }
+}
+
+async fn recv_event(rx: Option<&mut broadcast::Receiver<AgentEvent>>) -> Option<AgentEvent> {
+    let Some(rx) = rx else {
+        return std::future::pending().await;
+    };
+    loop {
+        match rx.recv().await {
+            Ok(event) => return Some(event),
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Stops the child's process group. The child itself is killed directly as
+/// well in case it was not started as a group leader.
+async fn terminate(child: &mut Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        let report = stop_process_group(pid, STOP_GRACE).await;
+        if !report.survivors.is_empty() {
+            eprintln!("[agent] Killed processes left behind by {pid}: {:?}", report.survivors);
+        }
+    }
+    let _ = child.start_kill();
+}
+
+#[cfg(unix)]
+fn signal_reason(status: &std::process::ExitStatus) -> Option<String> {
+    use std::os::unix::process::ExitStatusExt;
+
+    status.signal().map(|signal| match signal {
+        libc::SIGXCPU => "CPU time limit exceeded".to_string(),
+        libc::SIGKILL => "Killed (SIGKILL)".to_string(),
+        libc::SIGSEGV => "Segmentation fault".to_string(),
+        libc::SIGABRT => "Aborted".to_string(),
+        other => format!("Terminated by signal {other}"),
+    })
+}
+
+#[cfg(not(unix))]
+fn signal_reason(_status: &std::process::ExitStatus) -> Option<String> {
+    None
+}
+
+/// Stops an agent and every process in its group, reporting descendants
+/// that ignored SIGTERM.
+pub async fn stop_agent_process(pid: u32) -> Result<StopReport, String> {
+    #[cfg(unix)]
+    {
+        let report = stop_process_group(pid, STOP_GRACE).await;
+        if !report.survivors.is_empty() {
+            eprintln!(
+                "[agent] Killed {} process(es) left behind by {pid}: {:?}",
+                report.survivors.len(),
+                report.survivors
+            );
+        }
+        Ok(report)
+    }
 
     #[cfg(not(unix))]
     {
+        let _ = pid;
         Err("Stop not supported on this platform".to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::AgentHandle;
+    use crate::fixtures;
+
+    fn seed_workspace(base_path: &std::path::Path) -> String {
+        let project_id = uuid::Uuid::new_v4().to_string();
+        let db = fixtures::create_project_db(base_path, &project_id);
+        db.with_conn(|conn| {
+            let card_id = fixtures::insert_card(conn, &project_id, "Card", "");
+            fixtures::insert_running_workspace(conn, "ws-1", &card_id, "sleep");
+            Ok(())
+        })
+        .unwrap();
+        project_id
+    }
+
+    fn run_until_exit(limits: AgentLimits) -> (AgentExitEvent, String, Option<String>) {
+        let dir = tempfile::tempdir().unwrap();
+        let project_id = seed_workspace(dir.path());
+        let bus = Arc::new(EventBus::new());
+
+        let exit = fixtures::block_on(async {
+            let mut rx = bus.subscribe();
+            let child = tokio::process::Command::new("sleep").arg("30").spawn().unwrap();
+            let registry = Arc::new(AgentRegistry::new());
+            registry.insert(AgentHandle {
+                workspace_id: "ws-1".to_string(),
+                project_id: project_id.clone(),
+                stdin_tx: tokio::sync::mpsc::channel(1).0,
+                pid: child.id().unwrap(),
+                pty: None,
+            });
+            start_lifecycle_monitor_inner(
+                None,
+                Some(Arc::clone(&bus)),
+                registry,
+                child,
+                MonitoredAgent {
+                    workspace_id: "ws-1".to_string(),
+                    project_id: project_id.clone(),
+                    base_path: dir.path().to_path_buf(),
+                    limits,
+                },
+            );
+            loop {
+                match tokio::time::timeout(Duration::from_secs(10), rx.recv()).await {
+                    Ok(Ok(AgentEvent::Exit(e))) => break e,
+                    Ok(Ok(_)) => continue,
+                    _ => panic!("agent did not exit"),
+                }
+            }
+        });
+
+        let db = open_project_db(dir.path(), &project_id).unwrap();
+        let (status, reason): (String, Option<String>) = db
+            .with_conn(|conn| {
+                conn.query_row(
+                    "SELECT status, exit_reason FROM agent_workspaces WHERE id = 'ws-1'",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .map_err(|e| format!("{e}"))
+            })
+            .unwrap();
+        (exit, status, reason)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_max_runtime_marks_workspace_timed_out() {
+        let limits = AgentLimits {
+            max_runtime_secs: Some(1),
+            ..AgentLimits::default()
+        };
+        let (exit, status, reason) = run_until_exit(limits);
+        assert_eq!(exit.status, "timed_out");
+        assert_eq!(status, "timed_out");
+        assert_eq!(reason.as_deref(), Some("Exceeded max runtime of 1s"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_idle_timeout_emits_timed_out_exit() {
+        let limits = AgentLimits {
+            idle_timeout_secs: Some(1),
+            ..AgentLimits::default()
+        };
+        let (exit, status, reason) = run_until_exit(limits);
+        assert_eq!(exit.status, "timed_out");
+        assert_eq!(exit.reason.as_deref(), Some("No output for 1s"));
+        assert_eq!(status, "timed_out");
+        assert_eq!(reason.as_deref(), Some("No output for 1s"));
+    }
+}
@@ -0,0 +1,248 @@
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use std::process::Stdio;
+
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, DuplexStream};
+#[cfg(unix)]
+use tokio::net::unix::pipe;
+use tokio::time::Duration;
+
+use super::monitor::is_same_process;
+
+const TAIL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const TAIL_BUFFER: usize = 64 * 1024;
+
+/// Files an agent's stdio is routed through. The agent writes its output to
+/// plain log files and reads input from a FIFO, none of which depend on the
+/// app staying alive, so a restarted app can pick the run back up. FIFOs are
+/// Unix-only; elsewhere agents run on plain pipes and nothing is created.
+#[derive(Debug, Clone)]
+pub struct WorkspaceLogs {
+    pub dir: PathBuf,
+    pub stdout: PathBuf,
+    pub stderr: PathBuf,
+    pub stdin: PathBuf,
+}
+
+impl WorkspaceLogs {
+    pub fn new(base_path: &Path, project_id: &str, workspace_id: &str) -> Self {
+        let dir = base_path
+            .join("projects")
+            .join(project_id)
+            .join("agents")
+            .join(workspace_id);
+        Self {
+            stdout: dir.join("stdout.log"),
+            stderr: dir.join("stderr.log"),
+            stdin: dir.join("stdin"),
+            dir,
+        }
+    }
+
+    pub fn exists(&self) -> bool {
+        self.stdout.exists() && self.stderr.exists() && self.stdin.exists()
+    }
+
+    /// Creates the log files and input FIFO and returns the child's stdio.
+    #[cfg(unix)]
+    pub fn create(&self) -> Result<(Stdio, Stdio, Stdio), String> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create agent log directory: {e}"))?;
+
+        if !self.stdin.exists() {
+            let path = std::ffi::CString::new(self.stdin.to_string_lossy().as_bytes())
+                .map_err(|e| format!("Invalid stdin path: {e}"))?;
+            if unsafe { libc::mkfifo(path.as_ptr(), 0o600) } != 0 {
+                return Err(format!(
+                    "Failed to create stdin pipe: {}",
+                    std::io::Error::last_os_error()
+                ));
+            }
+        }
+
+        let append = |path: &Path| {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| format!("Failed to open {}: {e}", path.display()))
+        };
+        let stdout = append(&self.stdout)?;
+        let stderr = append(&self.stderr)?;
+        // Opened read-write so the open doesn't block waiting for a writer and
+        // the agent never sees EOF while the app is away.
+        let stdin = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&self.stdin)
+            .map_err(|e| format!("Failed to open stdin pipe: {e}"))?;
+
+        Ok((Stdio::from(stdin), Stdio::from(stdout), Stdio::from(stderr)))
+    }
+
+    /// Opens the input FIFO for writing before the agent is spawned. Held
+    /// read-write, the open can't fail for want of a reader, as it would if
+    /// the agent hadn't started or had already exited.
+    #[cfg(unix)]
+    pub fn open_sender(&self) -> Result<pipe::Sender, String> {
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&self.stdin)
+            .map_err(|e| format!("Failed to open agent input: {e}"))?;
+        pipe::Sender::from_file(file).map_err(|e| format!("Failed to open agent input: {e}"))
+    }
+
+    /// Deletes the workspace's log files once it's finished with. Tails that
+    /// already have them open keep reading what's left.
+    pub fn remove(&self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+
+    /// Opens a running agent's input FIFO for writing. Fails if nothing holds
+    /// the read end, i.e. the agent is gone.
+    #[cfg(unix)]
+    pub fn open_input(&self) -> Result<pipe::Sender, String> {
+        pipe::OpenOptions::new()
+            .open_sender(&self.stdin)
+            .map_err(|e| format!("Failed to open agent input: {e}"))
+    }
+
+    #[cfg(not(unix))]
+    pub fn open_input(&self) -> Result<tokio::io::Sink, String> {
+        Err("Agent input FIFOs are only available on Unix".to_string())
+    }
+}
+
+/// Sidecar file recording how far into a log has been streamed.
+fn offset_path(log: &Path) -> PathBuf {
+    log.with_extension("offset")
+}
+
+/// Where the last session stopped streaming a log, so whatever the agent
+/// wrote while the app was away is picked up on reattach.
+pub fn resume_position(log: &Path) -> SeekFrom {
+    let offset = std::fs::read_to_string(offset_path(log))
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0);
+    SeekFrom::Start(offset)
+}
+
+/// Follows a log file from `start`, feeding it into the returned reader until
+/// `pid` has exited and everything it wrote has been read. `start_time` tells
+/// the agent apart from a later process reusing its pid. Progress is saved
+/// next to the log for [`resume_position`]. The file is opened before this
+/// returns, so the log can be deleted as soon as the agent exits.
+pub fn tail_log(path: PathBuf, pid: u32, start_time: Option<u64>, start: SeekFrom) -> DuplexStream {
+    let (reader, mut writer) = tokio::io::duplex(TAIL_BUFFER);
+    let file = std::fs::File::open(&path);
+    tokio::spawn(async move {
+        let Ok(file) = file else {
+            return;
+        };
+        let mut file = tokio::fs::File::from_std(file);
+        let Ok(mut offset) = file.seek(start).await else {
+            return;
+        };
+        let offset_path = offset_path(&path);
+
+        let mut buf = vec![0u8; 8192];
+        let mut exited = false;
+        loop {
+            match file.read(&mut buf).await {
+                Ok(0) if exited => break,
+                Ok(0) => {
+                    if is_same_process(pid, start_time) {
+                        tokio::time::sleep(TAIL_POLL_INTERVAL).await;
+                    } else {
+                        // One more pass picks up anything written just before exit.
+                        exited = true;
+                    }
+                }
+                Ok(n) => {
+                    if writer.write_all(&buf[..n]).await.is_err() {
+                        break;
+                    }
+                    offset += n as u64;
+                    let _ = tokio::fs::write(&offset_path, offset.to_string()).await;
+                }
+                Err(_) => break,
+            }
+        }
+    });
+    reader
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::fixtures::block_on;
+    use tokio::io::AsyncBufReadExt;
+
+    #[test]
+    fn test_agent_io_goes_through_log_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let logs = WorkspaceLogs::new(dir.path(), "p", "ws-1");
+
+        block_on(async {
+            let (stdin, stdout, stderr) = logs.create().unwrap();
+            let mut input = logs.open_sender().unwrap();
+            let mut child = tokio::process::Command::new("sh")
+                .arg("-c")
+                .arg("echo started; read line; echo \"got $line\"; echo oops >&2")
+                .stdin(stdin)
+                .stdout(stdout)
+                .stderr(stderr)
+                .spawn()
+                .unwrap();
+            let pid = child.id().unwrap();
+
+            input.write_all(b"hello\n").await.unwrap();
+
+            let reaper = tokio::spawn(async move { child.wait().await });
+            let mut lines = tokio::io::BufReader::new(tail_log(logs.stdout.clone(), pid, None, SeekFrom::Start(0))).lines();
+            let mut stdout = Vec::new();
+            while let Some(line) = lines.next_line().await.unwrap() {
+                stdout.push(line);
+            }
+            reaper.await.unwrap().unwrap();
+
+            assert_eq!(stdout, vec!["started", "got hello"]);
+            let written = std::fs::metadata(&logs.stdout).unwrap().len();
+            assert_eq!(resume_position(&logs.stdout), SeekFrom::Start(written));
+            assert_eq!(std::fs::read_to_string(&logs.stderr).unwrap(), "oops\n");
+            // Needs no reader, unlike open_input below.
+            assert!(logs.open_sender().is_ok());
+        });
+
+        assert!(logs.exists());
+        assert!(logs.open_input().is_err());
+        logs.remove();
+        assert!(!logs.dir.exists());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_tail_ends_when_pid_is_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("stdout.log");
+        std::fs::write(&path, "done\n").unwrap();
+
+        // Our own pid is alive, but not with this start time.
+        let pid = std::process::id();
+        let start_time = crate::executor::monitor::process_start_time(pid).map(|t| t + 1);
+        let lines = block_on(async {
+            let mut lines = tokio::io::BufReader::new(tail_log(path, pid, start_time, SeekFrom::Start(0))).lines();
+            let mut read = Vec::new();
+            while let Ok(Ok(Some(line))) =
+                tokio::time::timeout(Duration::from_secs(5), lines.next_line()).await
+            {
+                read.push(line);
+            }
+            read
+        });
+        assert_eq!(lines, vec!["done"]);
+    }
+}
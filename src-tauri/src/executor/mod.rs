@@ -1,29 +1,48 @@
+pub mod adapter;
+pub mod buffer;
 pub mod context;
 pub mod lifecycle;
+pub mod logs;
 pub mod monitor;
+pub mod parser;
+pub mod process_group;
+pub mod pty;
 pub mod reattach;
 pub mod spawn;
 pub mod stream;
+pub mod transcript;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Mutex;
 use tokio::sync::{broadcast, mpsc};
 
+use crate::config::global::ConcurrencyConfig;
+use buffer::{OutputBuffer, RecentEvents, Replay, SequencedEvent, DEFAULT_MAX_WORKSPACES};
 use lifecycle::AgentExitEvent;
 use monitor::AgentCrashedEvent;
+use parser::{
+    AgentResultEvent, AgentSessionInitEvent, AgentTextEvent, AgentToolCallEvent, AgentToolResultEvent,
+    AgentUsageEvent,
+};
+use pty::{AgentTerminalEvent, PtyControl};
 use serde::{Deserialize, Serialize};
 use stream::AgentOutputEvent;
 
 #[derive(Clone)]
 pub enum AgentEvent {
     Output(AgentOutputEvent),
+    Terminal(AgentTerminalEvent),
     Exit(AgentExitEvent),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentLogEvent {
+    pub project_id: String,
     pub card_id: String,
+    pub workspace_id: Option<String>,
+    pub level: String,
     pub message: String,
+    pub data: Option<serde_json::Value>,
     pub timestamp: String,
 }
 
@@ -32,12 +51,26 @@ pub struct AgentLogEvent {
 pub enum MaestroEvent {
     #[serde(rename = "agent-output")]
     AgentOutput(AgentOutputEvent),
+    #[serde(rename = "agent-terminal-output")]
+    AgentTerminalOutput(AgentTerminalEvent),
     #[serde(rename = "agent-exit")]
     AgentExit(AgentExitEvent),
     #[serde(rename = "agent-crashed")]
     AgentCrashed(AgentCrashedEvent),
     #[serde(rename = "agent-log")]
     AgentLog(AgentLogEvent),
+    #[serde(rename = "agent-session-init")]
+    AgentSessionInit(AgentSessionInitEvent),
+    #[serde(rename = "agent-text")]
+    AgentText(AgentTextEvent),
+    #[serde(rename = "agent-tool-call")]
+    AgentToolCall(AgentToolCallEvent),
+    #[serde(rename = "agent-tool-result")]
+    AgentToolResult(AgentToolResultEvent),
+    #[serde(rename = "agent-usage")]
+    AgentUsage(AgentUsageEvent),
+    #[serde(rename = "agent-result")]
+    AgentResult(AgentResultEvent),
 
     #[serde(rename = "cards-changed")]
     CardsChanged { project_id: String },
@@ -64,9 +97,16 @@ impl MaestroEvent {
     pub fn scope(&self) -> Option<&str> {
         match self {
             MaestroEvent::AgentOutput(e) => Some(&e.workspace_id),
+            MaestroEvent::AgentTerminalOutput(e) => Some(&e.workspace_id),
             MaestroEvent::AgentExit(e) => Some(&e.workspace_id),
             MaestroEvent::AgentCrashed(e) => Some(&e.workspace_id),
-            MaestroEvent::AgentLog(_) => None,
+            MaestroEvent::AgentLog(e) => e.workspace_id.as_deref(),
+            MaestroEvent::AgentSessionInit(e) => Some(&e.workspace_id),
+            MaestroEvent::AgentText(e) => Some(&e.workspace_id),
+            MaestroEvent::AgentToolCall(e) => Some(&e.workspace_id),
+            MaestroEvent::AgentToolResult(e) => Some(&e.workspace_id),
+            MaestroEvent::AgentUsage(e) => Some(&e.workspace_id),
+            MaestroEvent::AgentResult(e) => Some(&e.workspace_id),
             MaestroEvent::CardsChanged { project_id } => Some(project_id),
             MaestroEvent::StatusesChanged { project_id } => Some(project_id),
             MaestroEvent::QuestionsChanged { project_id } => Some(project_id),
@@ -82,9 +122,16 @@ impl MaestroEvent {
     pub fn event_type(&self) -> &'static str {
         match self {
             MaestroEvent::AgentOutput(_) => "agent-output",
+            MaestroEvent::AgentTerminalOutput(_) => "agent-terminal-output",
             MaestroEvent::AgentExit(_) => "agent-exit",
             MaestroEvent::AgentCrashed(_) => "agent-crashed",
             MaestroEvent::AgentLog(_) => "agent-log",
+            MaestroEvent::AgentSessionInit(_) => "agent-session-init",
+            MaestroEvent::AgentText(_) => "agent-text",
+            MaestroEvent::AgentToolCall(_) => "agent-tool-call",
+            MaestroEvent::AgentToolResult(_) => "agent-tool-result",
+            MaestroEvent::AgentUsage(_) => "agent-usage",
+            MaestroEvent::AgentResult(_) => "agent-result",
             MaestroEvent::CardsChanged { .. } => "cards-changed",
             MaestroEvent::StatusesChanged { .. } => "statuses-changed",
             MaestroEvent::QuestionsChanged { .. } => "questions-changed",
@@ -96,18 +143,45 @@ impl MaestroEvent {
             MaestroEvent::ConfigChanged => "config-changed",
         }
     }
+
+    /// Project the event concerns. Agent events are scoped to a workspace,
+    /// so theirs is looked up in the registry; `None` for global events.
+    pub fn project_id(&self, registry: &AgentRegistry) -> Option<String> {
+        match self {
+            MaestroEvent::AgentLog(e) => Some(e.project_id.clone()),
+            _ if self.event_type().starts_with("agent-") => {
+                self.scope().and_then(|workspace_id| registry.workspace_project(workspace_id))
+            }
+            _ => self.scope().map(str::to_string),
+        }
+    }
 }
 
 pub struct EventBus {
     agent_tx: broadcast::Sender<AgentEvent>,
-    maestro_tx: broadcast::Sender<MaestroEvent>,
+    maestro_tx: broadcast::Sender<SequencedEvent>,
+    history: Mutex<EventHistory>,
+}
+
+struct EventHistory {
+    next_seq: u64,
+    buffer: OutputBuffer,
+    recent: RecentEvents,
 }
 
 impl EventBus {
     pub fn new() -> Self {
         let (agent_tx, _) = broadcast::channel(1024);
         let (maestro_tx, _) = broadcast::channel(1024);
-        Self { agent_tx, maestro_tx }
+        Self {
+            agent_tx,
+            maestro_tx,
+            history: Mutex::new(EventHistory {
+                next_seq: 1,
+                buffer: OutputBuffer::new(),
+                recent: RecentEvents::new(),
+            }),
+        }
     }
 
     pub fn emit(&self, event: AgentEvent) {
@@ -115,62 +189,282 @@ impl EventBus {
     }
 
     pub fn emit_maestro(&self, event: MaestroEvent) {
-        let _ = self.maestro_tx.send(event);
+        // Sequence assignment, buffering and sending happen under one lock so
+        // that subscribe_workspace never sees an event both replayed and live.
+        let mut history = self.history.lock().unwrap();
+        let sequenced = SequencedEvent {
+            seq: history.next_seq,
+            event,
+        };
+        history.next_seq += 1;
+        history.buffer.push(&sequenced);
+        history.recent.push(&sequenced);
+        let _ = self.maestro_tx.send(sequenced);
     }
 
     pub fn subscribe(&self) -> broadcast::Receiver<AgentEvent> {
         self.agent_tx.subscribe()
     }
 
-    pub fn subscribe_maestro(&self) -> broadcast::Receiver<MaestroEvent> {
+    pub fn subscribe_maestro(&self) -> broadcast::Receiver<SequencedEvent> {
         self.maestro_tx.subscribe()
     }
+
+    /// Subscribes to live events and snapshots a workspace's buffered agent
+    /// events newer than `since`, with no gap or overlap between the two.
+    pub fn subscribe_workspace(
+        &self,
+        workspace_id: &str,
+        since: Option<u64>,
+    ) -> (Replay, broadcast::Receiver<SequencedEvent>) {
+        let history = self.history.lock().unwrap();
+        let rx = self.maestro_tx.subscribe();
+        (history.buffer.replay(workspace_id, since), rx)
+    }
+
+    /// Subscribes to live events and snapshots the recent events of every
+    /// type newer than `since`.
+    pub fn subscribe_since(&self, since: u64) -> (Replay, broadcast::Receiver<SequencedEvent>) {
+        let history = self.history.lock().unwrap();
+        let rx = self.maestro_tx.subscribe();
+        (history.recent.replay(since, history.next_seq), rx)
+    }
 }
 
 pub struct AgentHandle {
     pub workspace_id: String,
+    pub project_id: String,
     pub stdin_tx: mpsc::Sender<String>,
     pub pid: u32,
+    /// Set when the agent runs on a pseudo-terminal.
+    pub pty: Option<PtyControl>,
 }
 
 pub struct AgentRegistry {
-    handles: Mutex<HashMap<String, AgentHandle>>,
+    slots: Mutex<Slots>,
+    projects: Mutex<WorkspaceProjects>,
+}
+
+/// Owning project of every running workspace. Kept for a while after the
+/// handle is removed, since the agent's exit and last output are emitted
+/// after that and can be replayed as long as the output buffer holds them.
+#[derive(Default)]
+struct WorkspaceProjects {
+    projects: HashMap<String, String>,
+    /// Exited workspaces, oldest first.
+    exited: VecDeque<String>,
+}
+
+/// Running agents plus the launches that have claimed a slot but not yet
+/// spawned, kept under one lock so concurrent launches can't both take the
+/// last slot.
+#[derive(Default)]
+struct Slots {
+    handles: HashMap<String, AgentHandle>,
+    /// Reserved workspace IDs, mapped to their project.
+    reserved: HashMap<String, String>,
+}
+
+impl Slots {
+    fn count(&self) -> usize {
+        self.handles.len() + self.reserved.len()
+    }
+
+    fn count_for_project(&self, project_id: &str) -> usize {
+        self.handles.values().filter(|h| h.project_id == project_id).count()
+            + self.reserved.values().filter(|p| *p == project_id).count()
+    }
+}
+
+/// A concurrency slot claimed by [`AgentRegistry::reserve`]. Inserting the
+/// agent's handle takes the slot over; dropping the reservation first gives
+/// it back.
+pub struct SlotReservation<'a> {
+    registry: &'a AgentRegistry,
+    workspace_id: String,
+}
+
+impl Drop for SlotReservation<'_> {
+    fn drop(&mut self) {
+        self.registry.slots.lock().unwrap().reserved.remove(&self.workspace_id);
+    }
 }
 
 impl AgentRegistry {
     pub fn new() -> Self {
         Self {
-            handles: Mutex::new(HashMap::new()),
+            slots: Mutex::new(Slots::default()),
+            projects: Mutex::new(WorkspaceProjects::default()),
+        }
+    }
+
+    /// Claims a slot for `workspace_id` if `limits` allow one more agent in
+    /// `project_id`. Reserved slots count as running until released.
+    pub fn reserve(
+        &self,
+        workspace_id: &str,
+        project_id: &str,
+        limits: &ConcurrencyConfig,
+    ) -> Option<SlotReservation<'_>> {
+        let mut slots = self.slots.lock().unwrap();
+        if !limits.allows(project_id, slots.count(), slots.count_for_project(project_id)) {
+            return None;
         }
+        slots.reserved.insert(workspace_id.to_string(), project_id.to_string());
+        Some(SlotReservation {
+            registry: self,
+            workspace_id: workspace_id.to_string(),
+        })
     }
 
     pub fn insert(&self, handle: AgentHandle) {
-        let mut handles = self.handles.lock().unwrap();
-        handles.insert(handle.workspace_id.clone(), handle);
+        self.projects
+            .lock()
+            .unwrap()
+            .projects
+            .insert(handle.workspace_id.clone(), handle.project_id.clone());
+        let mut slots = self.slots.lock().unwrap();
+        slots.reserved.remove(&handle.workspace_id);
+        slots.handles.insert(handle.workspace_id.clone(), handle);
     }
 
     pub fn remove(&self, workspace_id: &str) -> Option<AgentHandle> {
-        let mut handles = self.handles.lock().unwrap();
-        handles.remove(workspace_id)
+        let mut slots = self.slots.lock().unwrap();
+        let handle = slots.handles.remove(workspace_id)?;
+
+        // Forget the oldest exited workspaces once the output buffer can no
+        // longer be holding their events.
+        let mut projects = self.projects.lock().unwrap();
+        projects.exited.push_back(handle.workspace_id.clone());
+        while projects.exited.len() > DEFAULT_MAX_WORKSPACES {
+            if let Some(stale) = projects.exited.pop_front() {
+                if !slots.handles.contains_key(&stale) {
+                    projects.projects.remove(&stale);
+                }
+            }
+        }
+        Some(handle)
     }
 
     pub fn get_stdin_tx(&self, workspace_id: &str) -> Option<mpsc::Sender<String>> {
-        let handles = self.handles.lock().unwrap();
-        handles.get(workspace_id).map(|h| h.stdin_tx.clone())
+        let slots = self.slots.lock().unwrap();
+        slots.handles.get(workspace_id).map(|h| h.stdin_tx.clone())
+    }
+
+    pub fn get_pty(&self, workspace_id: &str) -> Option<PtyControl> {
+        let slots = self.slots.lock().unwrap();
+        slots.handles.get(workspace_id).and_then(|h| h.pty.clone())
     }
 
     pub fn has(&self, workspace_id: &str) -> bool {
-        let handles = self.handles.lock().unwrap();
-        handles.contains_key(workspace_id)
+        let slots = self.slots.lock().unwrap();
+        slots.handles.contains_key(workspace_id)
+    }
+
+    pub fn project_of(&self, workspace_id: &str) -> Option<String> {
+        let slots = self.slots.lock().unwrap();
+        slots.handles.get(workspace_id).map(|h| h.project_id.clone())
+    }
+
+    /// Project an agent event belongs to, including for agents that have
+    /// already exited.
+    pub fn workspace_project(&self, workspace_id: &str) -> Option<String> {
+        self.projects.lock().unwrap().projects.get(workspace_id).cloned()
     }
 
     pub fn running_count(&self) -> usize {
-        let handles = self.handles.lock().unwrap();
-        handles.len()
+        self.slots.lock().unwrap().count()
+    }
+
+    pub fn running_count_for_project(&self, project_id: &str) -> usize {
+        self.slots.lock().unwrap().count_for_project(project_id)
+    }
+
+    pub fn drain(&self) -> Vec<AgentHandle> {
+        let mut slots = self.slots.lock().unwrap();
+        slots.handles.drain().map(|(_, h)| h).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(workspace_id: &str, line: &str) -> MaestroEvent {
+        MaestroEvent::AgentOutput(AgentOutputEvent {
+            workspace_id: workspace_id.to_string(),
+            stream: "stdout".to_string(),
+            line: line.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_subscribe_workspace_replays_then_streams_live() {
+        let bus = EventBus::new();
+        bus.emit_maestro(output("ws-1", "first"));
+        bus.emit_maestro(MaestroEvent::ProjectsChanged);
+        bus.emit_maestro(output("ws-1", "second"));
+
+        let (replay, mut rx) = bus.subscribe_workspace("ws-1", None);
+        let replayed: Vec<u64> = replay.events.iter().map(|e| e.seq).collect();
+        assert_eq!(replayed, vec![1, 3]);
+
+        bus.emit_maestro(output("ws-1", "third"));
+        let live = rx.try_recv().unwrap();
+        assert_eq!(live.seq, 4);
+
+        let (replay, _) = bus.subscribe_workspace("ws-1", Some(3));
+        assert_eq!(replay.events.len(), 1);
+        assert_eq!(replay.events[0].seq, 4);
     }
 
-    pub fn all_pids(&self) -> Vec<u32> {
-        let handles = self.handles.lock().unwrap();
-        handles.values().map(|h| h.pid).collect()
+    #[test]
+    fn test_reserve_claims_the_last_slot_once() {
+        let registry = AgentRegistry::new();
+        let limits = ConcurrencyConfig {
+            max_agents: Some(1),
+            ..Default::default()
+        };
+
+        let slot = registry.reserve("ws-1", "proj-1", &limits).unwrap();
+        assert_eq!(registry.running_count_for_project("proj-1"), 1);
+        assert!(registry.reserve("ws-2", "proj-1", &limits).is_none());
+
+        drop(slot);
+        assert_eq!(registry.running_count(), 0);
+
+        let _slot = registry.reserve("ws-2", "proj-1", &limits).unwrap();
+        registry.insert(AgentHandle {
+            workspace_id: "ws-2".to_string(),
+            project_id: "proj-1".to_string(),
+            stdin_tx: mpsc::channel(1).0,
+            pid: 1,
+            pty: None,
+        });
+        assert_eq!(registry.running_count(), 1);
+        assert!(registry.reserve("ws-3", "proj-2", &limits).is_none());
+    }
+
+    #[test]
+    fn test_exited_workspace_projects_are_pruned() {
+        let registry = AgentRegistry::new();
+        let handle = |workspace_id: &str| AgentHandle {
+            workspace_id: workspace_id.to_string(),
+            project_id: "proj-1".to_string(),
+            stdin_tx: mpsc::channel(1).0,
+            pid: 1,
+            pty: None,
+        };
+        registry.insert(handle("running"));
+        for i in 0..=DEFAULT_MAX_WORKSPACES {
+            let workspace_id = format!("ws-{i}");
+            registry.insert(handle(&workspace_id));
+            registry.remove(&workspace_id);
+        }
+
+        assert_eq!(registry.workspace_project("ws-0"), None);
+        assert_eq!(registry.workspace_project("ws-1").as_deref(), Some("proj-1"));
+        assert_eq!(registry.workspace_project("running").as_deref(), Some("proj-1"));
+        assert_eq!(registry.projects.lock().unwrap().projects.len(), DEFAULT_MAX_WORKSPACES + 1);
     }
 }
@@ -3,129 +3,182 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter};
-use tokio::time::{interval, Duration};
+use tokio::time::Duration;
 
 use crate::commands::projects::open_project_db;
 
+#[cfg(not(target_os = "linux"))]
 use super::reattach::is_process_alive;
+use super::logs::WorkspaceLogs;
+use super::reattach::ReattachedWorkspace;
 use super::{AgentRegistry, EventBus, MaestroEvent};
 
+/// How often a detached process is probed where pidfd isn't available.
+const FALLBACK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentCrashedEvent {
     pub workspace_id: String,
     pub project_id: String,
 }
 
-pub fn start_pid_monitor(
-    app: AppHandle,
-    event_bus: Option<Arc<EventBus>>,
-    registry: Arc<AgentRegistry>,
-    base_path: PathBuf,
-) {
-    tauri::async_runtime::spawn(async move {
-        let mut tick = interval(Duration::from_secs(5));
-        loop {
-            tick.tick().await;
-            check_running_pids(&app, event_bus.as_ref(), &registry, &base_path);
-        }
-    });
+/// Kernel start time of `pid` in clock ticks since boot, which together with
+/// the pid identifies a process even after the pid has been reused.
+#[cfg(target_os = "linux")]
+pub fn process_start_time(pid: u32) -> Option<u64> {
+    proc_stat(pid).map(|(_, start_time)| start_time)
 }
 
-fn check_running_pids(
-    app: &AppHandle,
-    event_bus: Option<&Arc<EventBus>>,
-    registry: &AgentRegistry,
-    base_path: &PathBuf,
-) {
-    let projects_dir = base_path.join("projects");
-    if !projects_dir.exists() {
-        return;
+#[cfg(not(target_os = "linux"))]
+pub fn process_start_time(_pid: u32) -> Option<u64> {
+    None
+}
+
+/// State and start time from `/proc/<pid>/stat`.
+#[cfg(target_os = "linux")]
+fn proc_stat(pid: u32) -> Option<(String, u64)> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // Fields after the parenthesised command name start at field 3 (state);
+    // starttime is field 22.
+    let (_, rest) = stat.rsplit_once(')')?;
+    let mut fields = rest.split_whitespace();
+    let state = fields.next()?.to_string();
+    let start_time = fields.nth(18)?.parse().ok()?;
+    Some((state, start_time))
+}
+
+/// Whether `pid` is still the live process that was recorded with
+/// `start_time`. Without a recorded start time only liveness is checked.
+#[cfg(target_os = "linux")]
+pub fn is_same_process(pid: u32, start_time: Option<u64>) -> bool {
+    match proc_stat(pid) {
+        Some((state, actual)) => state != "Z" && start_time.is_none_or(|expected| expected == actual),
+        None => false,
     }
+}
 
-    let entries = match std::fs::read_dir(&projects_dir) {
-        Ok(entries) => entries,
-        Err(_) => return,
-    };
+#[cfg(not(target_os = "linux"))]
+pub fn is_same_process(pid: u32, _start_time: Option<u64>) -> bool {
+    is_process_alive(pid)
+}
 
-    for entry in entries.flatten() {
-        let path = entry.path();
-        if !path.is_dir() {
-            continue;
+/// Resolves when a process this app didn't spawn exits. Children are awaited
+/// through their `Child` instead; this is for agents picked up after a
+/// restart, which were reparented away from us.
+pub async fn wait_for_exit(pid: u32, start_time: Option<u64>) {
+    #[cfg(target_os = "linux")]
+    if let Some(fd) = pidfd_open(pid) {
+        // Checked after opening so a pid reused in between isn't followed.
+        if !is_same_process(pid, start_time) {
+            return;
         }
-        let db_path = path.join("db.sqlite");
-        if !db_path.exists() {
-            continue;
+        if let Ok(fd) = tokio::io::unix::AsyncFd::new(fd) {
+            // A pidfd becomes readable once the process has terminated.
+            let _ = fd.readable().await;
+            return;
         }
+    }
+
+    while is_same_process(pid, start_time) {
+        tokio::time::sleep(FALLBACK_POLL_INTERVAL).await;
+    }
+}
 
-        let project_id = match path.file_name().and_then(|n| n.to_str()) {
-            Some(id) => id.to_string(),
-            None => continue,
-        };
+#[cfg(target_os = "linux")]
+fn pidfd_open(pid: u32) -> Option<std::os::fd::OwnedFd> {
+    use std::os::fd::FromRawFd;
 
-        check_project_pids(app, event_bus, registry, base_path, &project_id);
+    let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+    if fd < 0 {
+        return None;
     }
+    Some(unsafe { std::os::fd::OwnedFd::from_raw_fd(fd as i32) })
 }
 
-fn check_project_pids(
-    app: &AppHandle,
-    event_bus: Option<&Arc<EventBus>>,
-    registry: &AgentRegistry,
-    base_path: &PathBuf,
-    project_id: &str,
+/// Waits for a detached agent to exit and marks its workspace failed, since
+/// the exit status of a process we don't own can't be collected. A stop that
+/// already claimed the workspace records its state itself.
+pub async fn watch_detached_exit(
+    app: Option<AppHandle>,
+    event_bus: Option<Arc<EventBus>>,
+    registry: Arc<AgentRegistry>,
+    base_path: PathBuf,
+    workspace: ReattachedWorkspace,
 ) {
-    let db = match open_project_db(base_path, project_id) {
-        Ok(db) => db,
-        Err(_) => return,
-    };
+    let ReattachedWorkspace { workspace_id, project_id, pid, pid_start_time, .. } = workspace;
+    let registered = registry.has(&workspace_id);
+    wait_for_exit(pid, pid_start_time).await;
+    WorkspaceLogs::new(&base_path, &project_id, &workspace_id).remove();
 
-    let running = db.with_conn(|conn| {
-        let mut stmt = conn
-            .prepare(
-                "SELECT id, pid FROM agent_workspaces WHERE status = 'running' AND pid IS NOT NULL",
-            )
-            .map_err(|e| format!("{e}"))?;
-
-        let rows = stmt
-            .query_map([], |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
-            })
-            .map_err(|e| format!("{e}"))?;
-
-        rows.collect::<Result<Vec<_>, _>>()
-            .map_err(|e| format!("{e}"))
+    if registered && registry.remove(&workspace_id).is_none() {
+        return;
+    }
+    let Ok(db) = open_project_db(&base_path, &project_id) else {
+        return;
+    };
+    let completed_at = chrono::Utc::now().to_rfc3339();
+    let updated = db.with_conn(|conn| {
+        conn.execute(
+            "UPDATE agent_workspaces SET status = 'failed', exit_reason = ?1, completed_at = ?2 \
+             WHERE id = ?3 AND status IN ('running', 'paused')",
+            rusqlite::params!["Exited while detached; exit status unknown", completed_at, workspace_id],
+        )
+        .map_err(|e| format!("Failed to update workspace status: {e}"))
     });
+    if !matches!(updated, Ok(1)) {
+        return;
+    }
 
-    let running = match running {
-        Ok(r) => r,
-        Err(_) => return,
+    let event = AgentCrashedEvent {
+        workspace_id: workspace_id.clone(),
+        project_id: project_id.clone(),
     };
+    if let Some(bus) = event_bus {
+        bus.emit_maestro(MaestroEvent::AgentCrashed(event.clone()));
+        bus.emit_maestro(MaestroEvent::WorkspacesChanged { project_id });
+    }
+    if let Some(app) = app {
+        let _ = app.emit("agent-crashed", &event);
+    }
+}
 
-    for (workspace_id, pid) in running {
-        let pid = pid as u32;
-        if !is_process_alive(pid) {
-            registry.remove(&workspace_id);
-
-            let completed_at = chrono::Utc::now().to_rfc3339();
-            let _ = db.with_conn(|conn| {
-                conn.execute(
-                    "UPDATE agent_workspaces SET status = 'failed', completed_at = ?1 WHERE id = ?2",
-                    rusqlite::params![completed_at, workspace_id],
-                )
-                .map_err(|e| format!("{e}"))?;
-                Ok(())
-            });
-
-            let event = AgentCrashedEvent {
-                workspace_id: workspace_id.clone(),
-                project_id: project_id.to_string(),
-            };
-            if let Some(bus) = event_bus {
-                bus.emit_maestro(MaestroEvent::AgentCrashed(event.clone()));
-                bus.emit_maestro(MaestroEvent::WorkspacesChanged {
-                    project_id: project_id.to_string(),
-                });
-            }
-            let _ = app.emit("agent-crashed", &event);
-        }
+// Only Linux has pidfds and /proc start times; elsewhere exit is noticed
+// on a slow poll.
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::fixtures::block_on;
+
+    #[test]
+    fn test_start_time_identifies_process() {
+        let pid = std::process::id();
+        let start_time = process_start_time(pid);
+        assert!(start_time.is_some());
+        assert!(is_same_process(pid, start_time));
+        assert!(!is_same_process(pid, start_time.map(|t| t + 1)));
+        assert!(!is_same_process(99999, None));
+    }
+
+    #[test]
+    fn test_wait_for_exit_resolves_when_process_ends() {
+        block_on(async {
+            // A grandchild that outlives its parent, like an agent left behind
+            // by a previous app session.
+            let output = tokio::process::Command::new("sh")
+                .arg("-c")
+                .arg("sleep 0.3 >/dev/null 2>&1 & echo $!")
+                .output()
+                .await
+                .unwrap();
+            let pid: u32 = String::from_utf8(output.stdout).unwrap().trim().parse().unwrap();
+            let start_time = process_start_time(pid);
+
+            let started = std::time::Instant::now();
+            tokio::time::timeout(Duration::from_secs(5), wait_for_exit(pid, start_time))
+                .await
+                .unwrap();
+            assert!(started.elapsed() < Duration::from_secs(1));
+            assert!(!is_same_process(pid, start_time));
+        });
     }
 }
@@ -0,0 +1,353 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::global::OutputFormat;
+
+use super::MaestroEvent;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentSessionInitEvent {
+    pub workspace_id: String,
+    pub session_id: String,
+    pub model: Option<String>,
+    pub tools: Vec<String>,
+    pub raw: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentTextEvent {
+    pub workspace_id: String,
+    pub text: String,
+    pub raw: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentToolCallEvent {
+    pub workspace_id: String,
+    pub tool_use_id: String,
+    pub name: String,
+    pub input: Value,
+    pub raw: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentToolResultEvent {
+    pub workspace_id: String,
+    pub tool_use_id: String,
+    pub content: String,
+    pub is_error: bool,
+    pub raw: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentUsageEvent {
+    pub workspace_id: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+    pub cost_usd: Option<f64>,
+    pub raw: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentResultEvent {
+    pub workspace_id: String,
+    pub subtype: String,
+    pub is_error: bool,
+    pub result: Option<String>,
+    pub duration_ms: Option<u64>,
+    pub num_turns: Option<u64>,
+    pub total_cost_usd: Option<f64>,
+    pub raw: String,
+}
+
+/// Turns lines of agent stdout into typed events. Parsers only add events on
+/// top of the raw `agent-output` stream; they never replace it.
+pub trait OutputParser: Send {
+    fn parse_line(&mut self, workspace_id: &str, line: &str) -> Vec<MaestroEvent>;
+}
+
+pub struct TextParser;
+
+impl OutputParser for TextParser {
+    fn parse_line(&mut self, _workspace_id: &str, _line: &str) -> Vec<MaestroEvent> {
+        Vec::new()
+    }
+}
+
+/// Parser for Claude Code's `--output-format stream-json`.
+pub struct ClaudeStreamJsonParser;
+
+impl OutputParser for ClaudeStreamJsonParser {
+    fn parse_line(&mut self, workspace_id: &str, line: &str) -> Vec<MaestroEvent> {
+        let value: Value = match serde_json::from_str(line.trim()) {
+            Ok(v) => v,
+            Err(_) => return Vec::new(),
+        };
+
+        match value.get("type").and_then(Value::as_str) {
+            Some("system") => parse_system(workspace_id, line, &value),
+            Some("assistant") => parse_assistant(workspace_id, line, &value),
+            Some("user") => parse_user(workspace_id, line, &value),
+            Some("result") => parse_result(workspace_id, line, &value),
+            _ => Vec::new(),
+        }
+    }
+}
+
+pub fn parser_for(format: OutputFormat) -> Box<dyn OutputParser> {
+    match format {
+        OutputFormat::Text => Box::new(TextParser),
+        OutputFormat::ClaudeStreamJson => Box::new(ClaudeStreamJsonParser),
+    }
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn parse_system(workspace_id: &str, line: &str, value: &Value) -> Vec<MaestroEvent> {
+    if value.get("subtype").and_then(Value::as_str) != Some("init") {
+        return Vec::new();
+    }
+    let Some(session_id) = str_field(value, "session_id") else {
+        return Vec::new();
+    };
+    let tools = value
+        .get("tools")
+        .and_then(Value::as_array)
+        .map(|tools| {
+            tools
+                .iter()
+                .filter_map(|t| t.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    vec![MaestroEvent::AgentSessionInit(AgentSessionInitEvent {
+        workspace_id: workspace_id.to_string(),
+        session_id,
+        model: str_field(value, "model"),
+        tools,
+        raw: line.to_string(),
+    })]
+}
+
+fn parse_assistant(workspace_id: &str, line: &str, value: &Value) -> Vec<MaestroEvent> {
+    let Some(message) = value.get("message") else {
+        return Vec::new();
+    };
+    let mut events = Vec::new();
+
+    if let Some(blocks) = message.get("content").and_then(Value::as_array) {
+        for block in blocks {
+            match block.get("type").and_then(Value::as_str) {
+                Some("text") => {
+                    if let Some(text) = str_field(block, "text") {
+                        events.push(MaestroEvent::AgentText(AgentTextEvent {
+                            workspace_id: workspace_id.to_string(),
+                            text,
+                            raw: line.to_string(),
+                        }));
+                    }
+                }
+                Some("tool_use") => {
+                    events.push(MaestroEvent::AgentToolCall(AgentToolCallEvent {
+                        workspace_id: workspace_id.to_string(),
+                        tool_use_id: str_field(block, "id").unwrap_or_default(),
+                        name: str_field(block, "name").unwrap_or_default(),
+                        input: block.get("input").cloned().unwrap_or(Value::Null),
+                        raw: line.to_string(),
+                    }));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if let Some(usage) = message.get("usage") {
+        events.push(MaestroEvent::AgentUsage(usage_event(workspace_id, line, usage, None)));
+    }
+
+    events
+}
+
+fn parse_user(workspace_id: &str, line: &str, value: &Value) -> Vec<MaestroEvent> {
+    let Some(blocks) = value
+        .get("message")
+        .and_then(|m| m.get("content"))
+        .and_then(Value::as_array)
+    else {
+        return Vec::new();
+    };
+
+    blocks
+        .iter()
+        .filter(|b| b.get("type").and_then(Value::as_str) == Some("tool_result"))
+        .map(|block| {
+            MaestroEvent::AgentToolResult(AgentToolResultEvent {
+                workspace_id: workspace_id.to_string(),
+                tool_use_id: str_field(block, "tool_use_id").unwrap_or_default(),
+                content: tool_result_content(block.get("content")),
+                is_error: block.get("is_error").and_then(Value::as_bool).unwrap_or(false),
+                raw: line.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn parse_result(workspace_id: &str, line: &str, value: &Value) -> Vec<MaestroEvent> {
+    let total_cost_usd = value.get("total_cost_usd").and_then(Value::as_f64);
+    let mut events = Vec::new();
+
+    if let Some(usage) = value.get("usage") {
+        events.push(MaestroEvent::AgentUsage(usage_event(
+            workspace_id,
+            line,
+            usage,
+            total_cost_usd,
+        )));
+    }
+
+    events.push(MaestroEvent::AgentResult(AgentResultEvent {
+        workspace_id: workspace_id.to_string(),
+        subtype: str_field(value, "subtype").unwrap_or_default(),
+        is_error: value.get("is_error").and_then(Value::as_bool).unwrap_or(false),
+        result: str_field(value, "result"),
+        duration_ms: value.get("duration_ms").and_then(Value::as_u64),
+        num_turns: value.get("num_turns").and_then(Value::as_u64),
+        total_cost_usd,
+        raw: line.to_string(),
+    }));
+
+    events
+}
+
+fn usage_event(workspace_id: &str, line: &str, usage: &Value, cost_usd: Option<f64>) -> AgentUsageEvent {
+    let count = |key: &str| usage.get(key).and_then(Value::as_u64).unwrap_or(0);
+    AgentUsageEvent {
+        workspace_id: workspace_id.to_string(),
+        input_tokens: count("input_tokens"),
+        output_tokens: count("output_tokens"),
+        cache_creation_input_tokens: count("cache_creation_input_tokens"),
+        cache_read_input_tokens: count("cache_read_input_tokens"),
+        cost_usd,
+        raw: line.to_string(),
+    }
+}
+
+/// Tool results carry either a plain string or a list of content blocks.
+fn tool_result_content(content: Option<&Value>) -> String {
+    match content {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|b| b.get("text").and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Vec<MaestroEvent> {
+        ClaudeStreamJsonParser.parse_line("ws-1", line)
+    }
+
+    #[test]
+    fn test_text_parser_emits_nothing() {
+        assert!(TextParser.parse_line("ws-1", r#"{"type":"result"}"#).is_empty());
+    }
+
+    #[test]
+    fn test_ignores_non_json_lines() {
+        assert!(parse("plain output").is_empty());
+        assert!(parse(r#"{"type":"unknown"}"#).is_empty());
+    }
+
+    #[test]
+    fn test_parses_session_init() {
+        let events = parse(
+            r#"{"type":"system","subtype":"init","session_id":"s-1","model":"claude-sonnet","tools":["Bash","Read"]}"#,
+        );
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            MaestroEvent::AgentSessionInit(e) => {
+                assert_eq!(e.workspace_id, "ws-1");
+                assert_eq!(e.session_id, "s-1");
+                assert_eq!(e.model.as_deref(), Some("claude-sonnet"));
+                assert_eq!(e.tools, vec!["Bash", "Read"]);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parses_assistant_text_tool_use_and_usage() {
+        let events = parse(
+            r#"{"type":"assistant","message":{"content":[{"type":"text","text":"Reading file"},{"type":"tool_use","id":"tu-1","name":"Read","input":{"path":"a.rs"}}],"usage":{"input_tokens":10,"output_tokens":5}}}"#,
+        );
+        assert_eq!(events.len(), 3);
+        match &events[0] {
+            MaestroEvent::AgentText(e) => assert_eq!(e.text, "Reading file"),
+            other => panic!("unexpected event: {other:?}"),
+        }
+        match &events[1] {
+            MaestroEvent::AgentToolCall(e) => {
+                assert_eq!(e.tool_use_id, "tu-1");
+                assert_eq!(e.name, "Read");
+                assert_eq!(e.input["path"], "a.rs");
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+        match &events[2] {
+            MaestroEvent::AgentUsage(e) => {
+                assert_eq!(e.input_tokens, 10);
+                assert_eq!(e.output_tokens, 5);
+                assert_eq!(e.cost_usd, None);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parses_tool_result_blocks() {
+        let events = parse(
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"tu-1","content":[{"type":"text","text":"line 1"},{"type":"text","text":"line 2"}],"is_error":true}]}}"#,
+        );
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            MaestroEvent::AgentToolResult(e) => {
+                assert_eq!(e.tool_use_id, "tu-1");
+                assert_eq!(e.content, "line 1\nline 2");
+                assert!(e.is_error);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parses_final_result() {
+        let line = r#"{"type":"result","subtype":"success","is_error":false,"duration_ms":1200,"num_turns":3,"result":"Done","total_cost_usd":0.05,"usage":{"input_tokens":100,"output_tokens":40}}"#;
+        let events = parse(line);
+        assert_eq!(events.len(), 2);
+        match &events[0] {
+            MaestroEvent::AgentUsage(e) => assert_eq!(e.cost_usd, Some(0.05)),
+            other => panic!("unexpected event: {other:?}"),
+        }
+        match &events[1] {
+            MaestroEvent::AgentResult(e) => {
+                assert_eq!(e.subtype, "success");
+                assert!(!e.is_error);
+                assert_eq!(e.result.as_deref(), Some("Done"));
+                assert_eq!(e.num_turns, Some(3));
+                assert_eq!(e.raw, line);
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+    }
+}
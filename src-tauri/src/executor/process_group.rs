@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use tokio::time::{Duration, Instant};
+
+/// Outcome of stopping an agent's process group.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StopReport {
+    /// Descendants that were still alive after SIGTERM and had to be killed.
+    pub survivors: Vec<u32>,
+}
+
+/// Makes the spawned process the leader of a new session (and process group)
+/// so the whole tree it starts can be signalled at once.
+#[cfg(unix)]
+pub fn become_group_leader() -> std::io::Result<()> {
+    if unsafe { libc::setsid() } == -1 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+pub fn signal_group(pgid: u32, signal: i32) {
+    unsafe {
+        libc::kill(-(pgid as i32), signal);
+    }
+}
+
+/// Live (non-zombie) processes in the process group `pgid`.
+#[cfg(target_os = "linux")]
+pub fn group_members(pgid: u32) -> Vec<u32> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|pid| {
+            let Ok(stat) = std::fs::read_to_string(format!("/proc/{pid}/stat")) else {
+                return false;
+            };
+            // Fields after the parenthesised command name: state ppid pgrp ...
+            let Some((_, rest)) = stat.rsplit_once(')') else {
+                return false;
+            };
+            let mut fields = rest.split_whitespace();
+            let state = fields.next();
+            let pgrp = fields.nth(1).and_then(|f| f.parse::<u32>().ok());
+            state != Some("Z") && pgrp == Some(pgid)
+        })
+        .collect()
+}
+
+/// Without /proc the members can't be listed, only whether any are left.
+#[cfg(all(unix, not(target_os = "linux")))]
+pub fn group_members(pgid: u32) -> Vec<u32> {
+    if unsafe { libc::kill(-(pgid as i32), 0) } == 0 {
+        vec![pgid]
+    } else {
+        Vec::new()
+    }
+}
+
+/// Sends SIGTERM to the group led by `pid`, escalates to SIGKILL once
+/// `grace` has passed, and waits for every member to be gone.
+#[cfg(unix)]
+pub async fn stop_process_group(pid: u32, grace: Duration) -> StopReport {
+    signal_group(pid, libc::SIGTERM);
+
+    let deadline = Instant::now() + grace;
+    let survivors = loop {
+        let alive = group_members(pid);
+        if alive.is_empty() {
+            return StopReport::default();
+        }
+        if Instant::now() >= deadline {
+            break alive;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    };
+
+    signal_group(pid, libc::SIGKILL);
+    let survivors: Vec<u32> = survivors.into_iter().filter(|p| *p != pid).collect();
+    reap(&survivors, pid).await;
+
+    StopReport { survivors }
+}
+
+/// Collects killed descendants. Ones that aren't our children are reaped by
+/// init; the group leader is left to whoever owns its `Child`.
+#[cfg(unix)]
+async fn reap(pids: &[u32], pgid: u32) {
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while Instant::now() < deadline {
+        for pid in pids {
+            let mut status = 0;
+            unsafe {
+                libc::waitpid(*pid as i32, &mut status, libc::WNOHANG);
+            }
+        }
+        if group_members(pgid).is_empty() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use crate::fixtures::block_on;
+    use std::process::Stdio;
+
+    fn spawn_tree(script: &str) -> tokio::process::Child {
+        let mut cmd = tokio::process::Command::new("sh");
+        cmd.arg("-c").arg(script).stdin(Stdio::null());
+        unsafe {
+            cmd.pre_exec(become_group_leader);
+        }
+        cmd.spawn().unwrap()
+    }
+
+    async fn wait_for_members(pgid: u32, count: usize) {
+        for _ in 0..100 {
+            if group_members(pgid).len() >= count {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("process tree did not start");
+    }
+
+    #[test]
+    fn test_stop_kills_whole_tree() {
+        block_on(async {
+            let mut child = spawn_tree("sleep 30 & sleep 30 & wait");
+            let pid = child.id().unwrap();
+            wait_for_members(pid, 3).await;
+
+            let leader = tokio::spawn(async move { child.wait().await });
+            let report = stop_process_group(pid, Duration::from_secs(2)).await;
+
+            assert!(report.survivors.is_empty());
+            assert!(group_members(pid).is_empty());
+            leader.await.unwrap().unwrap();
+        });
+    }
+
+    #[test]
+    fn test_stop_reports_and_kills_survivors() {
+        block_on(async {
+            let mut child = spawn_tree("trap '' TERM; sleep 30 & sleep 30 & wait");
+            let pid = child.id().unwrap();
+            wait_for_members(pid, 3).await;
+
+            let leader = tokio::spawn(async move { child.wait().await });
+            let report = stop_process_group(pid, Duration::from_millis(300)).await;
+
+            assert!(!report.survivors.is_empty());
+            assert!(!report.survivors.contains(&pid));
+            assert!(group_members(pid).is_empty());
+            leader.await.unwrap().unwrap();
+        });
+    }
+}
@@ -0,0 +1,447 @@
+//! Pseudo-terminals for agents that want one. These are Unix-only; on other
+//! targets agents always run on piped stdio and no `PtyControl` is ever made.
+
+#[cfg(unix)]
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+#[cfg(unix)]
+use std::process::Stdio;
+#[cfg(unix)]
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+#[cfg(unix)]
+use tauri::{AppHandle, Emitter};
+#[cfg(unix)]
+use tokio::io::unix::AsyncFd;
+#[cfg(unix)]
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::mpsc;
+
+#[cfg(unix)]
+use super::transcript::TranscriptSink;
+#[cfg(unix)]
+use super::{AgentEvent, EventBus, MaestroEvent};
+
+#[cfg(unix)]
+const DEFAULT_COLS: u16 = 120;
+#[cfg(unix)]
+const DEFAULT_ROWS: u16 = 40;
+#[cfg(unix)]
+const READ_BUFFER: usize = 8192;
+
+/// A chunk of raw terminal output, escape sequences and all. A UTF-8
+/// sequence split across reads is held back until it is complete.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentTerminalEvent {
+    pub workspace_id: String,
+    pub data: String,
+}
+
+#[cfg(unix)]
+/// A freshly opened pseudo-terminal. The slave end becomes the agent's
+/// stdio; the master stays with us.
+pub struct PtyPair {
+    master: OwnedFd,
+    slave: OwnedFd,
+}
+
+#[cfg(unix)]
+impl PtyPair {
+    pub fn open() -> Result<Self, String> {
+        let mut master = -1;
+        let mut slave = -1;
+        let size = winsize(DEFAULT_COLS, DEFAULT_ROWS);
+        let result = unsafe {
+            libc::openpty(
+                &mut master,
+                &mut slave,
+                std::ptr::null_mut(),
+                std::ptr::null(),
+                &size,
+            )
+        };
+        if result != 0 {
+            return Err(format!(
+                "Failed to open pseudo-terminal: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        // SAFETY: openpty succeeded, so both descriptors are open and ours.
+        let pair = unsafe {
+            Self {
+                master: OwnedFd::from_raw_fd(master),
+                slave: OwnedFd::from_raw_fd(slave),
+            }
+        };
+        // openpty doesn't set close-on-exec, and any other process spawned
+        // meanwhile (another agent included) would inherit both ends.
+        set_cloexec(&pair.master)?;
+        set_cloexec(&pair.slave)?;
+        Ok(pair)
+    }
+
+    pub fn slave_stdio(&self) -> Result<(Stdio, Stdio, Stdio), String> {
+        let dup = || {
+            self.slave
+                .try_clone()
+                .map(Stdio::from)
+                .map_err(|e| format!("Failed to duplicate terminal: {e}"))
+        };
+        Ok((dup()?, dup()?, dup()?))
+    }
+
+    /// Drops the slave end once the child holds its own copies, so reads on
+    /// the master end when the agent exits.
+    pub fn into_master(self) -> Result<PtyMaster, String> {
+        let fd = self.master;
+        let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL) };
+        if flags < 0 || unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFL, flags | libc::O_NONBLOCK) } < 0 {
+            return Err(format!(
+                "Failed to configure terminal: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        let fd = AsyncFd::new(fd).map_err(|e| format!("Failed to register terminal: {e}"))?;
+        Ok(PtyMaster { fd: Arc::new(fd) })
+    }
+}
+
+#[cfg(unix)]
+fn set_cloexec(fd: &OwnedFd) -> Result<(), String> {
+    let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFD) };
+    if flags < 0 || unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, flags | libc::FD_CLOEXEC) } < 0 {
+        return Err(format!(
+            "Failed to configure terminal: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn winsize(cols: u16, rows: u16) -> libc::winsize {
+    libc::winsize {
+        ws_row: rows,
+        ws_col: cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    }
+}
+
+#[cfg(unix)]
+/// Master side of an agent's pseudo-terminal, shared by the output and input
+/// tasks and the registry.
+#[derive(Clone)]
+pub struct PtyMaster {
+    fd: Arc<AsyncFd<OwnedFd>>,
+}
+
+#[cfg(unix)]
+impl PtyMaster {
+    pub fn resize(&self, cols: u16, rows: u16) -> Result<(), String> {
+        let size = winsize(cols, rows);
+        if unsafe { libc::ioctl(self.fd.as_raw_fd(), libc::TIOCSWINSZ, &size) } != 0 {
+            return Err(format!(
+                "Failed to resize terminal: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+        Ok(())
+    }
+
+    async fn read(&self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            let mut guard = self.fd.readable().await?;
+            let result = guard.try_io(|fd| {
+                let n = unsafe { libc::read(fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) };
+                if n < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(n as usize)
+            });
+            if let Ok(result) = result {
+                return result;
+            }
+        }
+    }
+
+    async fn write_all(&self, mut data: &[u8]) -> std::io::Result<()> {
+        while !data.is_empty() {
+            let mut guard = self.fd.writable().await?;
+            let result = guard.try_io(|fd| {
+                let n = unsafe { libc::write(fd.as_raw_fd(), data.as_ptr().cast(), data.len()) };
+                if n < 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(n as usize)
+            });
+            if let Ok(result) = result {
+                data = &data[result?..];
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Stands in for the master where there are no pseudo-terminals. It has no
+/// values, so code holding a `PtyControl` is unreachable there.
+#[cfg(not(unix))]
+#[derive(Clone)]
+pub enum PtyMaster {}
+
+#[cfg(not(unix))]
+impl PtyMaster {
+    pub fn resize(&self, _cols: u16, _rows: u16) -> Result<(), String> {
+        match *self {}
+    }
+}
+
+/// What the registry keeps for an agent running on a terminal: the master
+/// for resizing, and a channel for raw keystrokes.
+#[derive(Clone)]
+pub struct PtyControl {
+    pub master: PtyMaster,
+    pub input_tx: mpsc::Sender<Vec<u8>>,
+}
+
+#[cfg(unix)]
+/// Forwards terminal output until the agent closes its end. On Linux the
+/// master reports EIO once no slave is left open, which ends the stream.
+/// The output is also written to `text` with escape sequences and carriage
+/// returns removed, for the line-based processing piped agents get.
+pub fn start_pty_streaming<W: AsyncWrite + Unpin + Send + 'static>(
+    app: Option<AppHandle>,
+    event_bus: Option<Arc<EventBus>>,
+    workspace_id: String,
+    master: PtyMaster,
+    mut text: W,
+) {
+    tokio::spawn(async move {
+        let mut buf = vec![0u8; READ_BUFFER];
+        let mut pending = Vec::new();
+        let mut filter = PlainText::default();
+        let mut text_open = true;
+        loop {
+            let n = match master.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+            if text_open {
+                let plain = filter.feed(&buf[..n]);
+                text_open = text.write_all(&plain).await.is_ok();
+            }
+            let event = AgentTerminalEvent {
+                workspace_id: workspace_id.clone(),
+                data: decode_utf8(&mut pending, &buf[..n]),
+            };
+            if let Some(ref bus) = event_bus {
+                bus.emit(AgentEvent::Terminal(event.clone()));
+                bus.emit_maestro(MaestroEvent::AgentTerminalOutput(event.clone()));
+            }
+            if let Some(ref handle) = app {
+                let _ = handle.emit(&format!("agent-terminal-{}", workspace_id), &event);
+            }
+        }
+        let _ = text.shutdown().await;
+    });
+}
+
+#[cfg(unix)]
+/// Decodes `chunk` after whatever `pending` held back, keeping an incomplete
+/// trailing sequence for the next call.
+fn decode_utf8(pending: &mut Vec<u8>, chunk: &[u8]) -> String {
+    pending.extend_from_slice(chunk);
+    let complete = match std::str::from_utf8(pending) {
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        _ => pending.len(),
+    };
+    let text = String::from_utf8_lossy(&pending[..complete]).into_owned();
+    pending.drain(..complete);
+    text
+}
+
+#[cfg(unix)]
+/// Strips terminal control sequences from output, carrying a sequence split
+/// across reads over to the next one.
+#[derive(Default)]
+struct PlainText {
+    state: EscapeState,
+}
+
+#[cfg(unix)]
+#[derive(Default, Clone, Copy)]
+enum EscapeState {
+    #[default]
+    Ground,
+    Escape,
+    Csi,
+    Osc,
+    OscEscape,
+}
+
+#[cfg(unix)]
+impl PlainText {
+    fn feed(&mut self, chunk: &[u8]) -> Vec<u8> {
+        let mut out = Vec::with_capacity(chunk.len());
+        for &byte in chunk {
+            self.state = match (self.state, byte) {
+                (EscapeState::Ground, 0x1b) => EscapeState::Escape,
+                (EscapeState::Ground, b'\n' | b'\t' | 0x20..=0x7e | 0x80..) => {
+                    out.push(byte);
+                    EscapeState::Ground
+                }
+                (EscapeState::Ground, _) => EscapeState::Ground,
+                (EscapeState::Escape, b'[') => EscapeState::Csi,
+                (EscapeState::Escape, b']') => EscapeState::Osc,
+                (EscapeState::Escape, _) => EscapeState::Ground,
+                (EscapeState::Csi, 0x40..=0x7e) => EscapeState::Ground,
+                (EscapeState::Csi, _) => EscapeState::Csi,
+                (EscapeState::Osc, 0x07) => EscapeState::Ground,
+                (EscapeState::Osc, 0x1b) => EscapeState::OscEscape,
+                (EscapeState::Osc, _) => EscapeState::Osc,
+                (EscapeState::OscEscape, b'\\') => EscapeState::Ground,
+                (EscapeState::OscEscape, _) => EscapeState::Osc,
+            };
+        }
+        out
+    }
+}
+
+#[cfg(unix)]
+/// Writes input to the terminal. Lines from `send_agent_input` are entered
+/// with a carriage return, as if typed; raw input is passed through as is.
+pub fn start_pty_input(
+    master: PtyMaster,
+    mut lines: mpsc::Receiver<String>,
+    mut raw: mpsc::Receiver<Vec<u8>>,
+    transcript: Option<TranscriptSink>,
+) {
+    tokio::spawn(async move {
+        loop {
+            let data = tokio::select! {
+                Some(line) = lines.recv() => {
+                    if let Some(ref sink) = transcript {
+                        sink.user(&line);
+                    }
+                    format!("{line}\r").into_bytes()
+                }
+                Some(bytes) = raw.recv() => bytes,
+                else => break,
+            };
+            if master.write_all(&data).await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::executor::context::AgentContext;
+    use crate::executor::spawn::spawn_agent_pty;
+    use crate::fixtures::block_on;
+    use tokio::io::AsyncBufReadExt;
+
+    #[test]
+    fn test_agent_sees_a_terminal() {
+        block_on(async {
+            let dir = tempfile::tempdir().unwrap();
+            let ctx = AgentContext {
+                binary: "sh".to_string(),
+                // /dev/tty only opens when the pty is the controlling terminal.
+                args: vec![
+                    "-c".to_string(),
+                    "[ -t 1 ] && : </dev/tty && echo tty; read line; echo \"got $line\"".to_string(),
+                ],
+                working_dir: dir.path().to_string_lossy().to_string(),
+                env: Vec::new(),
+                system_prompt: String::new(),
+                session_id_pattern: None,
+                output_format: Default::default(),
+                limits: Default::default(),
+                pty: true,
+                prompt_file: None,
+                stdin_prompt: false,
+            };
+            let pty = PtyPair::open().unwrap();
+            let mut child = spawn_agent_pty(&ctx, &pty).unwrap().child;
+            let master = pty.into_master().unwrap();
+            master.resize(100, 30).unwrap();
+
+            let bus = Arc::new(EventBus::new());
+            let mut rx = bus.subscribe();
+            let (text_tx, text_rx) = tokio::io::duplex(READ_BUFFER);
+            start_pty_streaming(None, Some(Arc::clone(&bus)), "ws-1".to_string(), master.clone(), text_tx);
+            let (line_tx, line_rx) = mpsc::channel(4);
+            let (_raw_tx, raw_rx) = mpsc::channel(4);
+            start_pty_input(master, line_rx, raw_rx, None);
+            line_tx.send("hello".to_string()).await.unwrap();
+
+            let mut output = String::new();
+            while let Ok(Ok(event)) =
+                tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv()).await
+            {
+                if let AgentEvent::Terminal(event) = event {
+                    output.push_str(&event.data);
+                    if output.contains("got hello") {
+                        break;
+                    }
+                }
+            }
+            child.wait().await.unwrap();
+
+            assert!(output.contains("tty"), "{output}");
+            assert!(output.contains("got hello"), "{output}");
+
+            let mut lines = tokio::io::BufReader::new(text_rx).lines();
+            let mut text = Vec::new();
+            while let Ok(Some(line)) = lines.next_line().await {
+                text.push(line);
+            }
+            assert!(text.contains(&"got hello".to_string()), "{text:?}");
+        });
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_other_children_do_not_inherit_the_terminal() {
+        let pty = PtyPair::open().unwrap();
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+
+        let held: Vec<_> = std::fs::read_dir(format!("/proc/{}/fd", child.id()))
+            .unwrap()
+            .filter_map(|entry| std::fs::read_link(entry.unwrap().path()).ok())
+            .collect();
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        let master = std::fs::read_link(format!("/proc/self/fd/{}", pty.master.as_raw_fd())).unwrap();
+        let slave = std::fs::read_link(format!("/proc/self/fd/{}", pty.slave.as_raw_fd())).unwrap();
+        assert!(!held.contains(&master), "{held:?}");
+        assert!(!held.contains(&slave), "{held:?}");
+    }
+
+    #[test]
+    fn test_plain_text_strips_split_escape_sequences() {
+        let mut filter = PlainText::default();
+        let mut out = filter.feed(b"\x1b[1mbold\x1b[");
+        out.extend(filter.feed(b"0m done\r\n\x1b]0;title\x07next\n"));
+        assert_eq!(String::from_utf8(out).unwrap(), "bold done\nnext\n");
+    }
+
+    #[test]
+    fn test_decode_utf8_holds_back_split_characters() {
+        let mut pending = Vec::new();
+        let bytes = "héllo".as_bytes();
+        assert_eq!(decode_utf8(&mut pending, &bytes[..2]), "h");
+        assert_eq!(decode_utf8(&mut pending, &bytes[2..]), "éllo");
+        assert!(pending.is_empty());
+    }
+}
@@ -1,9 +1,19 @@
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter};
 
 use crate::commands::projects::open_project_db;
+use crate::config::global::AgentProfile;
+
+use super::logs::{resume_position, tail_log, WorkspaceLogs};
+use super::monitor::{is_same_process, watch_detached_exit};
+use super::parser::{parser_for, OutputParser};
+use super::stream::{start_stderr_streaming_inner, start_stdin_forwarding, start_stdout_streaming_inner};
+use super::transcript::reopen_transcript;
+use super::{AgentHandle, AgentRegistry, EventBus};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReattachResult {
@@ -16,7 +26,9 @@ pub struct ReattachedWorkspace {
     pub workspace_id: String,
     pub project_id: String,
     pub card_id: String,
+    pub agent_type: Option<String>,
     pub pid: u32,
+    pub pid_start_time: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,7 +52,15 @@ pub fn is_process_alive(pid: u32) -> bool {
     }
 }
 
-pub fn startup_scan(app: &AppHandle, base_path: &Path) -> ReattachResult {
+/// Finds workspaces left running by a previous session. Dead ones are marked
+/// failed; live ones get their output, input and stop handle reconnected.
+pub fn startup_scan(
+    app: Option<&AppHandle>,
+    event_bus: Arc<EventBus>,
+    registry: Arc<AgentRegistry>,
+    base_path: &Path,
+    agents: &HashMap<String, AgentProfile>,
+) -> ReattachResult {
     let mut result = ReattachResult {
         reattached: Vec::new(),
         failed: Vec::new(),
@@ -71,18 +91,52 @@ pub fn startup_scan(app: &AppHandle, base_path: &Path) -> ReattachResult {
             None => continue,
         };
 
-        scan_project(app, base_path, &project_id, &mut result);
+        scan_project(base_path, &project_id, &mut result);
+    }
+
+    for ws in &result.reattached {
+        let output_format = ws
+            .agent_type
+            .as_deref()
+            .and_then(|binary| agents.values().find(|p| p.binary == binary))
+            .map(|p| p.output_format)
+            .unwrap_or_default();
+        let ws = ws.clone();
+        let app = app.cloned();
+        let event_bus = Arc::clone(&event_bus);
+        let registry = Arc::clone(&registry);
+        let base_path = base_path.to_path_buf();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = reattach_workspace(
+                Some(Arc::clone(&event_bus)),
+                &registry,
+                base_path.clone(),
+                &ws,
+                Some(parser_for(output_format)),
+            ) {
+                eprintln!("[startup] Failed to reattach workspace {}: {e}", ws.workspace_id);
+            }
+            watch_detached_exit(
+                app,
+                Some(event_bus),
+                registry,
+                base_path,
+                ws,
+            )
+            .await;
+        });
     }
 
-    if !result.failed.is_empty() {
-        let _ = app.emit("agents-crashed-on-startup", &result.failed);
+    if let Some(app) = app {
+        if !result.failed.is_empty() {
+            let _ = app.emit("agents-crashed-on-startup", &result.failed);
+        }
     }
 
     result
 }
 
 fn scan_project(
-    _app: &AppHandle,
     base_path: &Path,
     project_id: &str,
     result: &mut ReattachResult,
@@ -93,9 +147,17 @@ fn scan_project(
     };
 
     let running_workspaces = db.with_conn(|conn| {
+        // A restart drops every `maestro question --wait` connection, so no
+        // agent is still paused on one.
+        conn.execute(
+            "UPDATE agent_workspaces SET status = 'running' WHERE status = 'paused'",
+            [],
+        )
+        .map_err(|e| format!("Failed to resume paused workspaces: {e}"))?;
+
         let mut stmt = conn
             .prepare(
-                "SELECT id, card_id, pid, session_id FROM agent_workspaces WHERE status = 'running'",
+                "SELECT id, card_id, pid, session_id, agent_type, pid_start_time FROM agent_workspaces WHERE status = 'running'",
             )
             .map_err(|e| format!("Failed to query running workspaces: {e}"))?;
 
@@ -106,6 +168,8 @@ fn scan_project(
                     row.get::<_, String>(1)?,
                     row.get::<_, Option<i64>>(2)?,
                     row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, Option<i64>>(5)?,
                 ))
             })
             .map_err(|e| format!("Failed to read workspaces: {e}"))?;
@@ -119,11 +183,11 @@ fn scan_project(
         Err(_) => return,
     };
 
-    for (workspace_id, card_id, pid, session_id) in running_workspaces {
+    for (workspace_id, card_id, pid, session_id, agent_type, pid_start_time) in running_workspaces {
         let pid = match pid {
             Some(p) if p > 0 => p as u32,
             _ => {
-                mark_workspace_failed(&db, &workspace_id);
+                mark_workspace_failed(&db, base_path, project_id, &workspace_id);
                 result.failed.push(FailedWorkspace {
                     workspace_id,
                     project_id: project_id.to_string(),
@@ -134,15 +198,18 @@ fn scan_project(
             }
         };
 
-        if is_process_alive(pid) {
+        let pid_start_time = pid_start_time.map(|t| t as u64);
+        if is_same_process(pid, pid_start_time) {
             result.reattached.push(ReattachedWorkspace {
                 workspace_id,
                 project_id: project_id.to_string(),
                 card_id,
+                agent_type,
                 pid,
+                pid_start_time,
             });
         } else {
-            mark_workspace_failed(&db, &workspace_id);
+            mark_workspace_failed(&db, base_path, project_id, &workspace_id);
             result.failed.push(FailedWorkspace {
                 workspace_id,
                 project_id: project_id.to_string(),
@@ -153,7 +220,68 @@ fn scan_project(
     }
 }
 
-fn mark_workspace_failed(db: &crate::db::DbConnection, workspace_id: &str) {
+/// Resumes streaming a still-running agent's output from where the last
+/// session left off in its log files, so output written while the app was
+/// away still reaches the transcript, and registers a handle so it can be
+/// stopped and sent input again.
+pub fn reattach_workspace(
+    event_bus: Option<Arc<EventBus>>,
+    registry: &Arc<AgentRegistry>,
+    base_path: PathBuf,
+    workspace: &ReattachedWorkspace,
+    parser: Option<Box<dyn OutputParser>>,
+) -> Result<(), String> {
+    let logs = WorkspaceLogs::new(&base_path, &workspace.project_id, &workspace.workspace_id);
+    if !logs.exists() {
+        return Err("Agent was started without log files".to_string());
+    }
+    let stdin = logs.open_input()?;
+
+    let transcript = reopen_transcript(
+        &base_path,
+        &workspace.project_id,
+        &workspace.card_id,
+        event_bus.clone(),
+    )
+    .unwrap_or_else(|e| {
+        eprintln!("[startup] Failed to reopen transcript for {}: {e}", workspace.workspace_id);
+        None
+    });
+
+    start_stdout_streaming_inner(
+        None,
+        event_bus.clone(),
+        workspace.workspace_id.clone(),
+        tail_log(logs.stdout.clone(), workspace.pid, workspace.pid_start_time, resume_position(&logs.stdout)),
+        None,
+        parser,
+        transcript.clone(),
+    );
+    start_stderr_streaming_inner(
+        None,
+        event_bus,
+        workspace.workspace_id.clone(),
+        tail_log(logs.stderr.clone(), workspace.pid, workspace.pid_start_time, resume_position(&logs.stderr)),
+        None,
+        transcript.clone(),
+    );
+
+    let (stdin_tx, stdin_rx) = tokio::sync::mpsc::channel::<String>(64);
+    start_stdin_forwarding(stdin, stdin_rx, None, transcript);
+
+    registry.insert(AgentHandle {
+        workspace_id: workspace.workspace_id.clone(),
+        project_id: workspace.project_id.clone(),
+        stdin_tx,
+        pid: workspace.pid,
+        pty: None,
+    });
+
+    Ok(())
+}
+
+fn mark_workspace_failed(db: &crate::db::DbConnection, base_path: &Path, project_id: &str, workspace_id: &str) {
+    WorkspaceLogs::new(base_path, project_id, workspace_id).remove();
     let completed_at = chrono::Utc::now().to_rfc3339();
     let _ = db.with_conn(|conn| {
         conn.execute(
@@ -168,6 +296,8 @@ fn mark_workspace_failed(db: &crate::db::DbConnection, workspace_id: &str) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::executor::MaestroEvent;
+    use crate::fixtures::block_on;
 
     #[test]
     fn test_is_process_alive_self() {
@@ -179,4 +309,65 @@ mod tests {
     fn test_is_process_alive_nonexistent() {
         assert!(!is_process_alive(99999));
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reattach_resumes_output_and_input() {
+        let dir = tempfile::tempdir().unwrap();
+        block_on(async {
+            let logs = WorkspaceLogs::new(dir.path(), "p", "ws-1");
+            let (stdin, stdout, stderr) = logs.create().unwrap();
+            let mut child = tokio::process::Command::new("sh")
+                .arg("-c")
+                .arg("echo before; read line; echo \"got $line\"")
+                .stdin(stdin)
+                .stdout(stdout)
+                .stderr(stderr)
+                .spawn()
+                .unwrap();
+            let pid = child.id().unwrap();
+            let reaper = tokio::spawn(async move { child.wait().await });
+
+            for _ in 0..100 {
+                if std::fs::read_to_string(&logs.stdout).unwrap() == "before\n" {
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            }
+
+            let event_bus = Arc::new(EventBus::new());
+            let registry = Arc::new(AgentRegistry::new());
+            let mut rx = event_bus.subscribe_maestro();
+            let workspace = ReattachedWorkspace {
+                workspace_id: "ws-1".to_string(),
+                project_id: "p".to_string(),
+                card_id: "card-1".to_string(),
+                agent_type: None,
+                pid,
+                pid_start_time: None,
+            };
+            reattach_workspace(Some(Arc::clone(&event_bus)), &registry, dir.path().to_path_buf(), &workspace, None)
+                .unwrap();
+
+            let stdin_tx = registry.get_stdin_tx("ws-1").unwrap();
+            stdin_tx.send("hello".to_string()).await.unwrap();
+
+            let mut lines = Vec::new();
+            while let Ok(Ok(event)) =
+                tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv()).await
+            {
+                if let MaestroEvent::AgentOutput(output) = event.event {
+                    lines.push(output.line);
+                    if lines.len() == 2 {
+                        break;
+                    }
+                }
+            }
+            reaper.await.unwrap().unwrap();
+
+            // Nothing was streamed before reattaching, so output from while
+            // the app was away comes first.
+            assert_eq!(lines, vec!["before".to_string(), "got hello".to_string()]);
+        });
+    }
 }
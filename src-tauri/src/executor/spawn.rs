@@ -1,31 +1,69 @@
+use std::io::Write;
+use std::path::Path;
 use std::process::Stdio;
+
 use tokio::process::{Child, Command};
 
+#[cfg(unix)]
+use crate::config::global::AgentLimits;
+
 use super::context::AgentContext;
+#[cfg(unix)]
+use super::process_group::become_group_leader;
+#[cfg(unix)]
+use super::pty::PtyPair;
 
 pub struct SpawnedAgent {
     pub child: Child,
     pub pid: u32,
 }
 
-pub fn spawn_agent(ctx: &AgentContext) -> Result<SpawnedAgent, String> {
+/// Spawns the agent on the given stdio, usually from `WorkspaceLogs::create`.
+pub fn spawn_agent(ctx: &AgentContext, stdio: (Stdio, Stdio, Stdio)) -> Result<SpawnedAgent, String> {
+    spawn_with_stdio(ctx, stdio, false)
+}
+
+/// Spawns the agent on the slave side of `pty`, which becomes its
+/// controlling terminal.
+#[cfg(unix)]
+pub fn spawn_agent_pty(ctx: &AgentContext, pty: &PtyPair) -> Result<SpawnedAgent, String> {
+    spawn_with_stdio(ctx, pty.slave_stdio()?, true)
+}
+
+fn spawn_with_stdio(
+    ctx: &AgentContext,
+    (stdin, stdout, stderr): (Stdio, Stdio, Stdio),
+    controlling_tty: bool,
+) -> Result<SpawnedAgent, String> {
     std::fs::create_dir_all(&ctx.working_dir)
         .map_err(|e| format!("Failed to create working directory: {e}"))?;
+    if let Some(ref path) = ctx.prompt_file {
+        write_prompt_file(path, &ctx.system_prompt)?;
+    }
 
     let mut cmd = Command::new(&ctx.binary);
     cmd.args(&ctx.args)
         .current_dir(&ctx.working_dir)
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
+        .stdin(stdin)
+        .stdout(stdout)
+        .stderr(stderr);
 
+    if controlling_tty && std::env::var_os("TERM").is_none() {
+        cmd.env("TERM", "xterm-256color");
+    }
     for (key, value) in &ctx.env {
         cmd.env(key, value);
     }
 
-    let child = cmd
-        .spawn()
-        .map_err(|e| format!("Failed to spawn agent process '{}': {e}", ctx.binary))?;
+    #[cfg(unix)]
+    configure_child(&mut cmd, &ctx.limits, controlling_tty);
+
+    let child = cmd.spawn().map_err(|e| {
+        if let Some(ref path) = ctx.prompt_file {
+            let _ = std::fs::remove_file(path);
+        }
+        format!("Failed to spawn agent process '{}': {e}", ctx.binary)
+    })?;
 
     let pid = child
         .id()
@@ -33,3 +71,58 @@ pub fn spawn_agent(ctx: &AgentContext) -> Result<SpawnedAgent, String> {
 
     Ok(SpawnedAgent { child, pid })
 }
+
+/// Writes the system prompt where only the user can read it.
+fn write_prompt_file(path: &Path, prompt: &str) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to write prompt file: {e}"))?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(prompt.as_bytes()))
+        .map_err(|e| format!("Failed to write prompt file: {e}"))
+}
+
+/// Puts the child in its own session and applies `RLIMIT_AS` / `RLIMIT_CPU`
+/// between fork and exec. With `controlling_tty`, the terminal on stdin is
+/// made the new session's controlling terminal.
+#[cfg(unix)]
+fn configure_child(cmd: &mut Command, limits: &AgentLimits, controlling_tty: bool) {
+    let memory_bytes = limits.max_memory_mb.map(|mb| mb.saturating_mul(1024 * 1024));
+    let cpu_secs = limits.max_cpu_secs;
+
+    // SAFETY: the hook only calls setsid, ioctl and setrlimit, which are
+    // async-signal-safe.
+    unsafe {
+        cmd.pre_exec(move || {
+            become_group_leader()?;
+            if controlling_tty && libc::ioctl(0, libc::TIOCSCTTY as _, 0) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            let set = |resource, value: u64| {
+                let limit = libc::rlimit {
+                    rlim_cur: value as libc::rlim_t,
+                    rlim_max: value as libc::rlim_t,
+                };
+                if libc::setrlimit(resource, &limit) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            };
+            if let Some(bytes) = memory_bytes {
+                set(libc::RLIMIT_AS, bytes)?;
+            }
+            if let Some(secs) = cpu_secs {
+                set(libc::RLIMIT_CPU, secs)?;
+            }
+            Ok(())
+        });
+    }
+}
@@ -1,14 +1,17 @@
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use regex::Regex;
 use tauri::{AppHandle, Emitter};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::process::ChildStderr;
-use tokio::process::ChildStdin;
-use tokio::process::ChildStdout;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
 use tokio::sync::mpsc;
 
 use serde::{Deserialize, Serialize};
 
+use crate::commands::projects::open_project_db;
+
+use super::parser::OutputParser;
+use super::transcript::TranscriptSink;
 use super::{AgentEvent, EventBus, MaestroEvent};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,24 +21,115 @@ pub struct AgentOutputEvent {
     pub line: String,
 }
 
-pub fn start_stdout_streaming(
+/// Watches an agent's output for its session ID and records it on the
+/// workspace row so the run can later be resumed.
+#[derive(Clone)]
+pub struct SessionCapture {
+    base_path: PathBuf,
+    project_id: String,
+    pattern: Option<Regex>,
+}
+
+impl SessionCapture {
+    pub fn new(
+        base_path: PathBuf,
+        project_id: &str,
+        pattern: Option<&str>,
+    ) -> Result<Self, String> {
+        let pattern = match pattern {
+            Some(p) => Some(Regex::new(p).map_err(|e| format!("Invalid session_id_pattern: {e}"))?),
+            None => None,
+        };
+        Ok(Self {
+            base_path,
+            project_id: project_id.to_string(),
+            pattern,
+        })
+    }
+
+    fn persist(
+        &self,
+        event_bus: Option<&Arc<EventBus>>,
+        workspace_id: &str,
+        session_id: &str,
+    ) {
+        let db = match open_project_db(&self.base_path, &self.project_id) {
+            Ok(db) => db,
+            Err(_) => return,
+        };
+        let updated = db.with_conn(|conn| {
+            conn.execute(
+                "UPDATE agent_workspaces SET session_id = ?1 WHERE id = ?2",
+                rusqlite::params![session_id, workspace_id],
+            )
+            .map_err(|e| format!("Failed to record session id: {e}"))
+        });
+        if let (Ok(rows), Some(bus)) = (updated, event_bus) {
+            if rows > 0 {
+                bus.emit_maestro(MaestroEvent::WorkspacesChanged {
+                    project_id: self.project_id.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// Extracts a session ID from a single line of agent output.
+///
+/// JSON lines with a top-level string `session_id` (Claude's stream-json
+/// format) are always recognized. Otherwise the optional pattern is applied
+/// and its first capture group is used.
+pub fn extract_session_id(line: &str, pattern: Option<&Regex>) -> Option<String> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('{') {
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(trimmed) {
+            if let Some(id) = value.get("session_id").and_then(|v| v.as_str()) {
+                if !id.is_empty() {
+                    return Some(id.to_string());
+                }
+            }
+        }
+    }
+
+    let captures = pattern?.captures(line)?;
+    captures
+        .get(1)
+        .map(|m| m.as_str().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+pub fn start_stdout_streaming<R: AsyncRead + Unpin + Send + 'static>(
     app: AppHandle,
     workspace_id: String,
-    stdout: ChildStdout,
+    stdout: R,
 ) {
-    start_stdout_streaming_inner(Some(app), None, workspace_id, stdout);
+    start_stdout_streaming_inner(Some(app), None, workspace_id, stdout, None, None, None);
 }
 
-pub fn start_stdout_streaming_inner(
+pub fn start_stdout_streaming_inner<R: AsyncRead + Unpin + Send + 'static>(
     app: Option<AppHandle>,
     event_bus: Option<Arc<EventBus>>,
     workspace_id: String,
-    stdout: ChildStdout,
+    stdout: R,
+    session: Option<SessionCapture>,
+    mut parser: Option<Box<dyn OutputParser>>,
+    transcript: Option<TranscriptSink>,
 ) {
     tokio::spawn(async move {
         let reader = BufReader::new(stdout);
         let mut lines = reader.lines();
+        let mut last_session_id: Option<String> = None;
         while let Ok(Some(line)) = lines.next_line().await {
+            if let Some(ref capture) = session {
+                record_session_id(capture, event_bus.as_ref(), &workspace_id, &line, &mut last_session_id);
+            }
+            if let Some(ref sink) = transcript {
+                sink.agent(&line);
+            }
+            let parsed = match parser.as_mut() {
+                Some(p) => p.parse_line(&workspace_id, &line),
+                None => Vec::new(),
+            };
             let event = AgentOutputEvent {
                 workspace_id: workspace_id.clone(),
                 stream: "stdout".to_string(),
@@ -48,28 +142,45 @@ pub fn start_stdout_streaming_inner(
             if let Some(ref handle) = app {
                 let _ = handle.emit(&format!("agent-output-{}", workspace_id), &event);
             }
+            for typed in parsed {
+                if let Some(ref handle) = app {
+                    let _ = handle.emit(&format!("{}-{}", typed.event_type(), workspace_id), &typed);
+                }
+                if let Some(ref bus) = event_bus {
+                    bus.emit_maestro(typed);
+                }
+            }
         }
     });
 }
 
-pub fn start_stderr_streaming(
+pub fn start_stderr_streaming<R: AsyncRead + Unpin + Send + 'static>(
     app: AppHandle,
     workspace_id: String,
-    stderr: ChildStderr,
+    stderr: R,
 ) {
-    start_stderr_streaming_inner(Some(app), None, workspace_id, stderr);
+    start_stderr_streaming_inner(Some(app), None, workspace_id, stderr, None, None);
 }
 
-pub fn start_stderr_streaming_inner(
+pub fn start_stderr_streaming_inner<R: AsyncRead + Unpin + Send + 'static>(
     app: Option<AppHandle>,
     event_bus: Option<Arc<EventBus>>,
     workspace_id: String,
-    stderr: ChildStderr,
+    stderr: R,
+    session: Option<SessionCapture>,
+    transcript: Option<TranscriptSink>,
 ) {
     tokio::spawn(async move {
         let reader = BufReader::new(stderr);
         let mut lines = reader.lines();
+        let mut last_session_id: Option<String> = None;
         while let Ok(Some(line)) = lines.next_line().await {
+            if let Some(ref capture) = session {
+                record_session_id(capture, event_bus.as_ref(), &workspace_id, &line, &mut last_session_id);
+            }
+            if let Some(ref sink) = transcript {
+                sink.agent(&line);
+            }
             let event = AgentOutputEvent {
                 workspace_id: workspace_id.clone(),
                 stream: "stderr".to_string(),
@@ -86,12 +197,43 @@ pub fn start_stderr_streaming_inner(
     });
 }
 
-pub fn start_stdin_forwarding(
-    mut stdin: ChildStdin,
+fn record_session_id(
+    capture: &SessionCapture,
+    event_bus: Option<&Arc<EventBus>>,
+    workspace_id: &str,
+    line: &str,
+    last_session_id: &mut Option<String>,
+) {
+    let Some(session_id) = extract_session_id(line, capture.pattern.as_ref()) else {
+        return;
+    };
+    if last_session_id.as_deref() == Some(session_id.as_str()) {
+        return;
+    }
+    capture.persist(event_bus, workspace_id, &session_id);
+    *last_session_id = Some(session_id);
+}
+
+/// Writes input lines to the agent, recording each in the transcript. A
+/// `prompt` is written first and left out of the transcript, since it's the
+/// system prompt rather than anything the user said.
+pub fn start_stdin_forwarding<W: AsyncWrite + Unpin + Send + 'static>(
+    mut stdin: W,
     mut rx: mpsc::Receiver<String>,
+    prompt: Option<String>,
+    transcript: Option<TranscriptSink>,
 ) {
     tokio::spawn(async move {
+        if let Some(prompt) = prompt {
+            let data = format!("{prompt}\n");
+            if stdin.write_all(data.as_bytes()).await.is_err() || stdin.flush().await.is_err() {
+                return;
+            }
+        }
         while let Some(line) = rx.recv().await {
+            if let Some(ref sink) = transcript {
+                sink.user(&line);
+            }
             let data = format!("{line}\n");
             if stdin.write_all(data.as_bytes()).await.is_err() {
                 break;
@@ -102,3 +244,69 @@ pub fn start_stdin_forwarding(
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_extract_session_id_from_stream_json() {
+        let line = r#"{"type":"system","subtype":"init","session_id":"abc-123","model":"sonnet"}"#;
+        assert_eq!(extract_session_id(line, None), Some("abc-123".to_string()));
+    }
+
+    #[test]
+    fn test_extract_session_id_ignores_plain_text() {
+        assert_eq!(extract_session_id("Working on the task...", None), None);
+        assert_eq!(extract_session_id(r#"{"type":"assistant"}"#, None), None);
+        assert_eq!(extract_session_id(r#"{"session_id":""}"#, None), None);
+    }
+
+    #[test]
+    fn test_extract_session_id_with_pattern() {
+        let pattern = Regex::new(r"session id: ([0-9a-f-]+)").unwrap();
+        assert_eq!(
+            extract_session_id("session id: 0199a2b4-77c1", Some(&pattern)),
+            Some("0199a2b4-77c1".to_string())
+        );
+        assert_eq!(extract_session_id("no session here", Some(&pattern)), None);
+    }
+
+    #[test]
+    fn test_session_capture_rejects_invalid_pattern() {
+        let result = SessionCapture::new(PathBuf::from("/tmp"), "p", Some("(unclosed"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_session_capture_persists_to_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let base_path = dir.path().to_path_buf();
+        let project_id = uuid::Uuid::new_v4().to_string();
+        let db = fixtures::create_project_db(&base_path, &project_id);
+        db.with_conn(|conn| {
+            let card_id = fixtures::insert_card(conn, &project_id, "Card", "");
+            fixtures::insert_running_workspace(conn, "ws-1", &card_id, "claude");
+            Ok(())
+        })
+        .unwrap();
+
+        let capture = SessionCapture::new(base_path.clone(), &project_id, None).unwrap();
+        let mut last = None;
+        record_session_id(&capture, None, "ws-1", r#"{"session_id":"sess-42"}"#, &mut last);
+        assert_eq!(last.as_deref(), Some("sess-42"));
+
+        let stored: Option<String> = db
+            .with_conn(|conn| {
+                conn.query_row(
+                    "SELECT session_id FROM agent_workspaces WHERE id = 'ws-1'",
+                    [],
+                    |row| row.get(0),
+                )
+                .map_err(|e| format!("{e}"))
+            })
+            .unwrap();
+        assert_eq!(stored.as_deref(), Some("sess-42"));
+    }
+}
@@ -0,0 +1,316 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use rusqlite::OptionalExtension;
+use tokio::sync::mpsc;
+use tokio::time::{interval, Duration, MissedTickBehavior};
+
+use crate::commands::projects::open_project_db;
+use crate::db::DbConnection;
+
+use super::{EventBus, MaestroEvent};
+
+const BATCH_SIZE: usize = 100;
+const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone)]
+pub struct TranscriptEntry {
+    pub role: &'static str,
+    pub content: String,
+    pub timestamp: String,
+}
+
+/// Cheap handle the streaming tasks use to append lines to a run's
+/// conversation. The conversation is closed once every sink is dropped.
+#[derive(Clone)]
+pub struct TranscriptSink {
+    tx: mpsc::UnboundedSender<TranscriptEntry>,
+}
+
+impl TranscriptSink {
+    pub fn agent(&self, content: &str) {
+        self.push("agent", content);
+    }
+
+    pub fn user(&self, content: &str) {
+        self.push("user", content);
+    }
+
+    /// Blank lines are dropped, so a flush only happens (and only tells the
+    /// UI the conversation changed) when there is something to show.
+    fn push(&self, role: &'static str, content: &str) {
+        if content.trim().is_empty() {
+            return;
+        }
+        let _ = self.tx.send(TranscriptEntry {
+            role,
+            content: content.to_string(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        });
+    }
+}
+
+/// Opens a `conversations` row for an agent run and spawns a writer that
+/// appends everything sent through the returned sink in batches.
+pub fn start_transcript(
+    base_path: &Path,
+    project_id: &str,
+    card_id: &str,
+    agent_type: &str,
+    event_bus: Option<Arc<EventBus>>,
+) -> Result<TranscriptSink, String> {
+    let db = open_project_db(base_path, project_id)?;
+    let conversation_id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+
+    db.with_conn(|conn| {
+        conn.execute(
+            "INSERT INTO conversations (id, card_id, agent_type, started_at) \
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![conversation_id, card_id, agent_type, now],
+        )
+        .map_err(|e| format!("Failed to create conversation: {e}"))?;
+        Ok(())
+    })?;
+
+    if let Some(ref bus) = event_bus {
+        bus.emit_maestro(MaestroEvent::ConversationsChanged {
+            project_id: project_id.to_string(),
+        });
+    }
+
+    Ok(spawn_writer(db, conversation_id, project_id, event_bus))
+}
+
+/// Resumes writing to the card's latest unfinished conversation, left open
+/// when the app exited while its agent kept running.
+pub fn reopen_transcript(
+    base_path: &Path,
+    project_id: &str,
+    card_id: &str,
+    event_bus: Option<Arc<EventBus>>,
+) -> Result<Option<TranscriptSink>, String> {
+    let db = open_project_db(base_path, project_id)?;
+    let conversation_id = db.with_conn(|conn| {
+        conn.query_row(
+            "SELECT id FROM conversations WHERE card_id = ?1 AND ended_at IS NULL \
+             ORDER BY started_at DESC LIMIT 1",
+            rusqlite::params![card_id],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to find open conversation: {e}"))
+    })?;
+
+    Ok(conversation_id.map(|id| spawn_writer(db, id, project_id, event_bus)))
+}
+
+fn spawn_writer(
+    db: DbConnection,
+    conversation_id: String,
+    project_id: &str,
+    event_bus: Option<Arc<EventBus>>,
+) -> TranscriptSink {
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(write_transcript(
+        db,
+        conversation_id,
+        project_id.to_string(),
+        rx,
+        event_bus,
+    ));
+    TranscriptSink { tx }
+}
+
+async fn write_transcript(
+    db: DbConnection,
+    conversation_id: String,
+    project_id: String,
+    mut rx: mpsc::UnboundedReceiver<TranscriptEntry>,
+    event_bus: Option<Arc<EventBus>>,
+) {
+    let mut pending: Vec<TranscriptEntry> = Vec::new();
+    let mut ticker = interval(FLUSH_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            entry = rx.recv() => match entry {
+                Some(entry) => {
+                    pending.push(entry);
+                    if pending.len() >= BATCH_SIZE {
+                        flush(&db, &conversation_id, &project_id, &mut pending, event_bus.as_ref());
+                    }
+                }
+                None => break,
+            },
+            _ = ticker.tick() => {
+                flush(&db, &conversation_id, &project_id, &mut pending, event_bus.as_ref());
+            }
+        }
+    }
+
+    flush(&db, &conversation_id, &project_id, &mut pending, event_bus.as_ref());
+
+    let ended_at = chrono::Utc::now().to_rfc3339();
+    let _ = db.with_conn(|conn| {
+        conn.execute(
+            "UPDATE conversations SET ended_at = ?1 WHERE id = ?2",
+            rusqlite::params![ended_at, conversation_id],
+        )
+        .map_err(|e| format!("Failed to close conversation: {e}"))
+    });
+    if let Some(bus) = event_bus {
+        bus.emit_maestro(MaestroEvent::ConversationsChanged { project_id });
+    }
+}
+
+fn flush(
+    db: &DbConnection,
+    conversation_id: &str,
+    project_id: &str,
+    pending: &mut Vec<TranscriptEntry>,
+    event_bus: Option<&Arc<EventBus>>,
+) {
+    if pending.is_empty() {
+        return;
+    }
+    let entries = std::mem::take(pending);
+    let result = db.with_conn(|conn| {
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to begin transaction: {e}"))?;
+        {
+            let mut stmt = tx
+                .prepare(
+                    "INSERT INTO conversation_messages (id, conversation_id, role, content, timestamp) \
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                )
+                .map_err(|e| format!("Failed to prepare insert: {e}"))?;
+            for entry in &entries {
+                let id = uuid::Uuid::new_v4().to_string();
+                stmt.execute(rusqlite::params![
+                    id,
+                    conversation_id,
+                    entry.role,
+                    entry.content,
+                    entry.timestamp
+                ])
+                .map_err(|e| format!("Failed to insert message: {e}"))?;
+            }
+        }
+        tx.commit()
+            .map_err(|e| format!("Failed to commit transcript: {e}"))
+    });
+
+    match result {
+        Ok(()) => {
+            if let Some(bus) = event_bus {
+                bus.emit_maestro(MaestroEvent::ConversationsChanged {
+                    project_id: project_id.to_string(),
+                });
+            }
+        }
+        Err(e) => eprintln!("[transcript] {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn test_transcript_persists_messages_and_closes_conversation() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_id = uuid::Uuid::new_v4().to_string();
+        let db = fixtures::create_project_db(dir.path(), &project_id);
+        let card_id = db
+            .with_conn(|conn| Ok(fixtures::insert_card(conn, &project_id, "Card", "")))
+            .unwrap();
+
+        fixtures::block_on(async {
+            let sink = start_transcript(dir.path(), &project_id, &card_id, "claude", None).unwrap();
+            sink.agent("hello");
+            sink.user("do the thing");
+            sink.agent("done");
+            drop(sink);
+
+            for _ in 0..50 {
+                let ended: Option<String> = db
+                    .with_conn(|conn| {
+                        conn.query_row("SELECT ended_at FROM conversations", [], |row| row.get(0))
+                            .map_err(|e| format!("{e}"))
+                    })
+                    .unwrap();
+                if ended.is_some() {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        });
+
+        let messages: Vec<(String, String)> = db
+            .with_conn(|conn| {
+                let mut stmt = conn
+                    .prepare("SELECT role, content FROM conversation_messages ORDER BY timestamp, rowid")
+                    .map_err(|e| format!("{e}"))?;
+                let rows = stmt
+                    .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                    .map_err(|e| format!("{e}"))?;
+                rows.collect::<Result<Vec<_>, _>>().map_err(|e| format!("{e}"))
+            })
+            .unwrap();
+        assert_eq!(
+            messages,
+            vec![
+                ("agent".to_string(), "hello".to_string()),
+                ("user".to_string(), "do the thing".to_string()),
+                ("agent".to_string(), "done".to_string()),
+            ]
+        );
+
+        let ended: Option<String> = db
+            .with_conn(|conn| {
+                conn.query_row("SELECT ended_at FROM conversations", [], |row| row.get(0))
+                    .map_err(|e| format!("{e}"))
+            })
+            .unwrap();
+        assert!(ended.is_some());
+    }
+
+    #[test]
+    fn test_blank_lines_do_not_signal_a_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_id = uuid::Uuid::new_v4().to_string();
+        let db = fixtures::create_project_db(dir.path(), &project_id);
+        let card_id = db
+            .with_conn(|conn| Ok(fixtures::insert_card(conn, &project_id, "Card", "")))
+            .unwrap();
+        let bus = Arc::new(EventBus::new());
+
+        let count = fixtures::block_on(async {
+            let sink = start_transcript(dir.path(), &project_id, &card_id, "claude", Some(Arc::clone(&bus))).unwrap();
+            let mut rx = bus.subscribe_maestro();
+            sink.agent("");
+            sink.agent("   ");
+            tokio::time::sleep(FLUSH_INTERVAL * 2).await;
+            assert!(rx.try_recv().is_err());
+
+            sink.agent("hello");
+            tokio::time::sleep(FLUSH_INTERVAL * 2).await;
+            assert!(matches!(
+                rx.try_recv().map(|e| e.event),
+                Ok(MaestroEvent::ConversationsChanged { .. })
+            ));
+            assert!(rx.try_recv().is_err());
+
+            db.with_conn(|conn| {
+                conn.query_row("SELECT COUNT(*) FROM conversation_messages", [], |row| row.get::<_, i64>(0))
+                    .map_err(|e| format!("{e}"))
+            })
+            .unwrap()
+        });
+        assert_eq!(count, 1);
+    }
+}
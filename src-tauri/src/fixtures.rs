@@ -0,0 +1,112 @@
+//! Seeding and runtime helpers shared by the unit tests.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use rusqlite::Connection;
+
+use crate::commands::config::ConfigState;
+use crate::commands::projects::{open_project_db, seed_default_statuses};
+use crate::config::global::GlobalConfig;
+use crate::db::DbConnection;
+use crate::executor::{AgentRegistry, EventBus};
+use crate::http::server::AppState;
+use crate::ipc::server::IpcServer;
+
+pub fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+/// Default config with its storage in a fresh temp dir.
+pub fn config_state() -> (ConfigState, tempfile::TempDir) {
+    let dir = tempfile::tempdir().unwrap();
+    let config_path = dir.path().join("config.toml");
+    let mut config = GlobalConfig::default();
+    config.storage.base_path = dir.path().to_str().unwrap().to_string();
+    config.save(&config_path).unwrap();
+
+    let state = ConfigState {
+        config: Mutex::new(config),
+        config_path,
+    };
+    (state, dir)
+}
+
+/// What the HTTP handlers run against, on top of `config_state`.
+pub fn app_state() -> (AppState, tempfile::TempDir) {
+    let (config, dir) = config_state();
+    let http_config = config.with_config(|c| Ok(c.http_server.clone())).unwrap();
+    let state = AppState::new(
+        Arc::new(config),
+        Arc::new(AgentRegistry::new()),
+        Arc::new(IpcServer::new()),
+        Arc::new(EventBus::new()),
+        http_config,
+    );
+    (state, dir)
+}
+
+/// Adds the project row and its default statuses.
+pub fn seed_project(conn: &Connection, project_id: &str) {
+    let now = chrono::Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO projects (id, name, agent_config, created_at, updated_at) VALUES (?1, 'Test', '{}', ?2, ?2)",
+        rusqlite::params![project_id, now],
+    )
+    .unwrap();
+    seed_default_statuses(conn, project_id).unwrap();
+}
+
+/// Creates a seeded project database where the commands look for it.
+pub fn create_project_db(base_path: &Path, project_id: &str) -> DbConnection {
+    let db = open_project_db(base_path, project_id).unwrap();
+    db.with_conn(|conn| {
+        seed_project(conn, project_id);
+        Ok(())
+    })
+    .unwrap();
+    db
+}
+
+/// A seeded project database in a fresh temp dir.
+pub fn setup_test_db() -> (tempfile::TempDir, DbConnection, String) {
+    let dir = tempfile::tempdir().unwrap();
+    let project_id = uuid::Uuid::new_v4().to_string();
+    let db = create_project_db(dir.path(), &project_id);
+    (dir, db, project_id)
+}
+
+pub fn status_id(conn: &Connection, project_id: &str, group: &str) -> String {
+    conn.query_row(
+        "SELECT id FROM statuses WHERE project_id = ?1 AND \"group\" = ?2 LIMIT 1",
+        rusqlite::params![project_id, group],
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+/// Adds a card to the project's backlog.
+pub fn insert_card(conn: &Connection, project_id: &str, title: &str, description: &str) -> String {
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = chrono::Utc::now().to_rfc3339();
+    conn.execute(
+        "INSERT INTO cards (id, project_id, status_id, title, description, sort_order, created_at, updated_at) \
+         VALUES (?1, ?2, ?3, ?4, ?5, 0, ?6, ?6)",
+        rusqlite::params![id, project_id, status_id(conn, project_id, "Backlog"), title, description, now],
+    )
+    .unwrap();
+    id
+}
+
+pub fn insert_running_workspace(conn: &Connection, workspace_id: &str, card_id: &str, agent_type: &str) {
+    conn.execute(
+        "INSERT INTO agent_workspaces (id, card_id, agent_type, status, attached_at) \
+         VALUES (?1, ?2, ?3, 'running', ?4)",
+        rusqlite::params![workspace_id, card_id, agent_type, chrono::Utc::now().to_rfc3339()],
+    )
+    .unwrap();
+}
@@ -0,0 +1,138 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::commands::agent::start_launch_queue_worker;
+use crate::commands::config::ConfigState;
+use crate::commands::projects::list_projects_inner;
+use crate::executor::reattach::startup_scan;
+use crate::executor::buffer::SequencedEvent;
+use crate::executor::{AgentRegistry, EventBus, MaestroEvent};
+use crate::http::server::{start_http_server, AppState};
+use crate::ipc::server::IpcServer;
+
+#[derive(Debug, Default)]
+pub struct ServerOptions {
+    /// Global config file; defaults to the one the desktop app uses.
+    pub config_path: Option<PathBuf>,
+    /// Built frontend (`npm run build`) to serve alongside the API.
+    pub static_dir: Option<PathBuf>,
+}
+
+/// Runs everything the desktop app does in the background — executor,
+/// process monitor, IPC sockets — behind the HTTP/WebSocket API, without a
+/// Tauri window. Must be called from inside a multi-threaded tokio runtime.
+pub async fn run_server(options: ServerOptions) -> Result<(), String> {
+    tauri::async_runtime::set(tokio::runtime::Handle::current());
+
+    let config = Arc::new(match options.config_path {
+        Some(path) => ConfigState::load_from(path)?,
+        None => ConfigState::load()?,
+    });
+    let (base_path, agents, http_config) = config.with_config(|c| {
+        Ok((c.resolve_base_path(), c.agents.clone(), c.http_server.clone()))
+    })?;
+
+    let registry = Arc::new(AgentRegistry::new());
+    let event_bus = Arc::new(EventBus::new());
+    let ipc = Arc::new(IpcServer::new());
+
+    let scan_result = startup_scan(
+        None,
+        Arc::clone(&event_bus),
+        Arc::clone(&registry),
+        &base_path,
+        &agents,
+    );
+    for ws in &scan_result.reattached {
+        eprintln!(
+            "[startup] Re-attached to workspace {} (pid {})",
+            ws.workspace_id, ws.pid
+        );
+    }
+    for ws in &scan_result.failed {
+        eprintln!(
+            "[startup] Workspace {} marked as failed (no live process)",
+            ws.workspace_id
+        );
+    }
+
+    start_launch_queue_worker(
+        None,
+        Arc::clone(&event_bus),
+        Arc::clone(&config),
+        Arc::clone(&registry),
+    );
+
+    let project_ids = list_projects_inner(&config)?.into_iter().map(|p| p.id).collect();
+    start_project_sockets(&ipc, &event_bus, &config, &registry, project_ids).await;
+    start_socket_worker(
+        Arc::clone(&ipc),
+        Arc::clone(&event_bus),
+        Arc::clone(&config),
+        Arc::clone(&registry),
+    );
+
+    let state = AppState::new(config, registry, ipc, event_bus, http_config);
+    start_http_server(state, options.static_dir).await
+}
+
+async fn start_project_sockets(
+    ipc: &IpcServer,
+    event_bus: &Arc<EventBus>,
+    config: &Arc<ConfigState>,
+    registry: &Arc<AgentRegistry>,
+    project_ids: Vec<String>,
+) {
+    for project_id in project_ids {
+        if let Err(e) = ipc
+            .ensure_started(
+                None,
+                Some(Arc::clone(event_bus)),
+                Arc::clone(config),
+                Arc::clone(registry),
+                project_id.clone(),
+            )
+            .await
+        {
+            eprintln!("[ipc] Failed to start socket for project {project_id}: {e}");
+        }
+    }
+}
+
+/// Without a window there is nobody to call `start_ipc_server`, so sockets
+/// are started for projects created after startup, and for any project an
+/// agent is launched in.
+fn start_socket_worker(
+    ipc: Arc<IpcServer>,
+    event_bus: Arc<EventBus>,
+    config: Arc<ConfigState>,
+    registry: Arc<AgentRegistry>,
+) {
+    tokio::spawn(async move {
+        let mut rx = event_bus.subscribe_maestro();
+        loop {
+            let project_ids = match rx.recv().await {
+                Ok(SequencedEvent {
+                    event: MaestroEvent::WorkspacesChanged { project_id },
+                    ..
+                }) => vec![project_id],
+                Ok(SequencedEvent {
+                    event: MaestroEvent::ProjectsChanged,
+                    ..
+                })
+                | Err(RecvError::Lagged(_)) => match list_projects_inner(&config) {
+                    Ok(projects) => projects.into_iter().map(|p| p.id).collect(),
+                    Err(e) => {
+                        eprintln!("[ipc] Failed to list projects: {e}");
+                        continue;
+                    }
+                },
+                Ok(_) => continue,
+                Err(RecvError::Closed) => break,
+            };
+            start_project_sockets(&ipc, &event_bus, &config, &registry, project_ids).await;
+        }
+    });
+}
@@ -1,35 +1,374 @@
 use std::sync::Arc;
 
 use axum::{
-    body::Body,
-    extract::Request,
-    http::{header::AUTHORIZATION, StatusCode},
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{
+        header::{AUTHORIZATION, CONTENT_LENGTH},
+        Method, StatusCode,
+    },
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
+use chrono::{DateTime, Utc};
+use serde_json::Value;
 
-use crate::config::global::HttpServerConfig;
+use super::error::{extract_optional_arg, AppError};
+use super::server::AppState;
+use crate::commands::history::Actor;
+use crate::commands::tokens::{hash_token, touch_api_token};
+use crate::commands::COMMAND_NAMES;
+use crate::config::global::{ApiToken, HttpServerConfig, TokenScope};
+use crate::executor::{AgentRegistry, MaestroEvent};
+
+/// Same as axum's default limit for `Json` bodies.
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Commands that don't read or change any one project's data, so
+/// project-restricted tokens may call them.
+const GLOBAL_COMMANDS: &[&str] = &[
+    "get_global_config",
+    "resolve_config",
+    "get_status_group_defaults",
+    "get_local_ip",
+    "list_projects",
+    "generate_branch_name",
+    "get_claude_worktree_path",
+];
+
+/// Who a request authenticated as. Added to the request's extensions.
+#[derive(Debug, Clone)]
+pub enum Grant {
+    /// The primary `auth_token`, or any caller when auth is off.
+    Full,
+    Token(Arc<ApiToken>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Read,
+    Write,
+    Agent,
+    /// Server config and token management; primary token only.
+    Admin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Target {
+    Project(String),
+    Global,
+    AllProjects,
+}
+
+impl Grant {
+    pub fn allows(&self, access: Access) -> bool {
+        match self {
+            Grant::Full => true,
+            Grant::Token(token) => match access {
+                Access::Read => token.has_scope(TokenScope::Read),
+                Access::Write => token.has_scope(TokenScope::Write),
+                Access::Agent => token.has_scope(TokenScope::Agent),
+                Access::Admin => false,
+            },
+        }
+    }
+
+    pub fn allows_project(&self, project_id: &str) -> bool {
+        match self {
+            Grant::Full => true,
+            Grant::Token(token) => token.allows_project(project_id),
+        }
+    }
+
+    /// Who card history records for requests made with this grant.
+    pub fn actor(&self) -> Actor {
+        match self {
+            Grant::Full => Actor::User,
+            Grant::Token(token) => Actor::Token {
+                id: token.id.clone(),
+                name: token.name.clone(),
+            },
+        }
+    }
+
+    /// Whether a `/ws/events` subscriber may see `event`.
+    pub fn allows_event(&self, event: &MaestroEvent, registry: &AgentRegistry) -> bool {
+        let Grant::Token(token) = self else {
+            return true;
+        };
+        if token.projects.is_empty() {
+            return true;
+        }
+        match event {
+            MaestroEvent::ProjectsChanged | MaestroEvent::ConfigChanged => true,
+            _ => event
+                .project_id(registry)
+                .is_some_and(|project_id| token.allows_project(&project_id)),
+        }
+    }
+
+    fn authorize(&self, access: Access, target: &Target) -> Result<(), AppError> {
+        if !self.allows(access) {
+            let scope = match access {
+                Access::Read => "read",
+                Access::Write => "write",
+                Access::Agent => "agent",
+                Access::Admin => {
+                    return Err(AppError::Forbidden(
+                        "Only the primary auth token can do this".to_string(),
+                    ))
+                }
+            };
+            return Err(AppError::Forbidden(format!("Token lacks the {scope} scope")));
+        }
+        let allowed = match (self, target) {
+            (Grant::Full, _) | (_, Target::Global) => true,
+            (Grant::Token(token), Target::AllProjects) => token.projects.is_empty(),
+            (_, Target::Project(project_id)) => self.allows_project(project_id),
+        };
+        if !allowed {
+            return Err(AppError::Forbidden(
+                "Token is not allowed to access this project".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Response bodies trimmed down for scoped tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResponseFilter {
+    Projects,
+    GlobalConfig,
+}
+
+impl ResponseFilter {
+    fn apply(self, grant: &Grant, value: &mut Value) {
+        match self {
+            ResponseFilter::Projects => {
+                if let Value::Array(projects) = value {
+                    projects.retain(|p| p["id"].as_str().is_some_and(|id| grant.allows_project(id)));
+                }
+            }
+            ResponseFilter::GlobalConfig => {
+                let last_project = value["last_project_id"].as_str().unwrap_or_default();
+                if !grant.allows_project(last_project) {
+                    value["last_project_id"] = Value::String(String::new());
+                }
+                if let Some(Value::Array(agents)) = value.get_mut("agents") {
+                    for agent in agents {
+                        agent["env_vars"] = Value::Null;
+                    }
+                }
+            }
+        }
+    }
+}
 
 pub async fn auth_middleware(
-    config: Arc<HttpServerConfig>,
+    State(state): State<AppState>,
     request: Request,
     next: Next,
-) -> Result<Response<Body>, StatusCode> {
-    if !config.requires_auth() {
-        return Ok(next.run(request).await);
+) -> Response {
+    let grant = match authenticate(&state, &request) {
+        Ok(grant) => grant,
+        Err(status) => return status.into_response(),
+    };
+    if let Grant::Token(token) = &grant {
+        if let Err(e) = touch_api_token(&state.config, &token.id, Utc::now()) {
+            eprintln!("[http] {e}");
+        }
+    }
+
+    let (mut request, filter) = match authorize(&state, &grant, request).await {
+        Ok(authorized) => authorized,
+        Err(e) => return e.into_response(),
+    };
+    request.extensions_mut().insert(grant.clone());
+    let response = next.run(request).await;
+
+    match filter {
+        Some(filter) if response.status().is_success() => {
+            filter_response(&grant, filter, response).await
+        }
+        _ => response,
     }
+}
 
-    let expected_token = &config.auth_token;
-    if expected_token.is_empty() {
+fn authenticate(state: &AppState, request: &Request) -> Result<Grant, StatusCode> {
+    let http = state
+        .config
+        .with_config(|c| Ok(c.http_server.clone()))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !http.requires_auth() {
+        return Ok(Grant::Full);
+    }
+    if http.auth_token.is_empty() && http.tokens.is_empty() {
         return Err(StatusCode::INTERNAL_SERVER_ERROR);
     }
 
-    let token = extract_token(&request);
+    let token = extract_token(request).ok_or(StatusCode::UNAUTHORIZED)?;
+    match_token(&http, token, Utc::now()).ok_or(StatusCode::UNAUTHORIZED)
+}
+
+fn match_token(http: &HttpServerConfig, token: &str, now: DateTime<Utc>) -> Option<Grant> {
+    let presented = hash_token(token);
+    if !http.auth_token.is_empty() && constant_time_eq(&presented, &hash_token(&http.auth_token)) {
+        return Some(Grant::Full);
+    }
+    http.tokens
+        .iter()
+        .find(|t| constant_time_eq(&presented, &t.token_hash))
+        .filter(|t| !t.is_expired(now))
+        .map(|t| Grant::Token(Arc::new(t.clone())))
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a
+            .bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+async fn authorize(
+    state: &AppState,
+    grant: &Grant,
+    request: Request,
+) -> Result<(Request, Option<ResponseFilter>), AppError> {
+    if matches!(grant, Grant::Full) {
+        return Ok((request, None));
+    }
+
+    let path = request.uri().path().to_string();
+    if let Some(command) = path.strip_prefix("/api/") {
+        if request.method() == Method::POST && COMMAND_NAMES.contains(&command) {
+            // The project a command touches is in its JSON body.
+            let (parts, body) = request.into_parts();
+            let bytes = to_bytes(body, MAX_BODY_BYTES)
+                .await
+                .map_err(|e| AppError::BadRequest(format!("Failed to read request body: {e}")))?;
+            let args: Value = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+            let access = command_access(command).unwrap_or(Access::Admin);
+            grant.authorize(access, &command_target(command, &args, &state.registry))?;
+            let filter = match command {
+                "list_projects" => Some(ResponseFilter::Projects),
+                "get_global_config" => Some(ResponseFilter::GlobalConfig),
+                _ => None,
+            };
+            return Ok((Request::from_parts(parts, Body::from(bytes)), filter));
+        }
+
+        let (access, target) = rest_access(request.method(), command, &state.registry);
+        grant.authorize(access, &target)?;
+        let filter = (request.method() == Method::GET && command == "projects")
+            .then_some(ResponseFilter::Projects);
+        return Ok((request, filter));
+    }
 
-    match token {
-        Some(t) if t == expected_token => Ok(next.run(request).await),
-        _ => Err(StatusCode::UNAUTHORIZED),
+    if let Some(workspace_id) = path.strip_prefix("/ws/agent/") {
+        let target = state
+            .registry
+            .workspace_project(workspace_id)
+            .map_or(Target::AllProjects, Target::Project);
+        grant.authorize(Access::Read, &target)?;
+    } else if path == "/ws/events" {
+        grant.authorize(Access::Read, &Target::Global)?;
     }
+    Ok((request, None))
+}
+
+async fn filter_response(grant: &Grant, filter: ResponseFilter, response: Response) -> Response {
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, usize::MAX).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let Ok(mut value) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    filter.apply(grant, &mut value);
+    parts.headers.remove(CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(value.to_string()))
+}
+
+/// The access level each command needs. `None` for unknown commands.
+pub fn command_access(command: &str) -> Option<Access> {
+    let access = match command {
+        "get_global_config" | "resolve_config" | "get_status_group_defaults" | "get_local_ip"
+        | "get_project" | "list_projects" | "list_statuses" | "get_card" | "list_cards"
+        | "list_sub_cards" | "list_card_dependencies" | "list_questions" | "count_unresolved_questions" | "read_artifact"
+        | "list_artifacts" | "list_linked_directories" | "list_conversations" | "list_messages"
+        | "count_conversation_messages" | "generate_branch_name" | "check_worktree_exists"
+        | "get_card_worktree" | "get_claude_worktree_path" | "list_workspaces" | "get_workspace"
+        | "list_running_workspaces" | "get_changed_files" | "get_file_diff" | "get_review_count" | "search" | "get_card_history"
+        | "list_card_logs" => {
+            Access::Read
+        }
+
+        "set_last_project" | "create_project" | "update_project" | "delete_project"
+        | "create_status" | "update_status" | "delete_status" | "reorder_statuses"
+        | "create_card" | "update_card" | "delete_card" | "move_card" | "reorder_cards"
+        | "add_card_dependency" | "remove_card_dependency"
+        | "create_question" | "resolve_question" | "unresolve_question" | "delete_question"
+        | "create_artifact" | "update_artifact" | "delete_artifact" | "add_linked_directory"
+        | "remove_linked_directory" | "create_conversation" | "create_message"
+        | "create_worktree" | "send_back_card" | "approve_card" | "create_pr" => Access::Write,
+
+        "set_status_auto_run" | "launch_agent" | "send_agent_input" | "write_agent_terminal"
+        | "resize_agent_terminal" | "stop_agent" | "resume_agent" | "stop_all_agents"
+        | "archive_card_workspaces" | "set_launch_priority" | "start_ipc_server"
+        | "stop_ipc_server" => Access::Agent,
+
+        "update_global_config" | "create_agent_profile" | "update_agent_profile"
+        | "delete_agent_profile" | "update_status_group_defaults" | "get_http_server_config"
+        | "update_http_server_config" | "regenerate_auth_token" | "create_api_token"
+        | "list_api_tokens" | "revoke_api_token" => Access::Admin,
+
+        _ => return None,
+    };
+    Some(access)
+}
+
+fn command_target(command: &str, args: &Value, registry: &AgentRegistry) -> Target {
+    let arg = |key: &str| extract_optional_arg::<String>(args, key).ok().flatten();
+    let project_id = match command {
+        "get_project" | "update_project" | "delete_project" => arg("id"),
+        _ => arg("project_id"),
+    };
+    let workspace_project = arg("workspace_id").and_then(|id| registry.project_of(&id));
+
+    match (project_id, workspace_project) {
+        (Some(project), Some(workspace)) if project != workspace => Target::AllProjects,
+        (Some(project), _) | (None, Some(project)) => Target::Project(project),
+        (None, None) if GLOBAL_COMMANDS.contains(&command) => Target::Global,
+        (None, None) => Target::AllProjects,
+    }
+}
+
+/// Access for the REST routes, from the method and the path below `/api/`.
+/// A running agent is checked against the project it belongs to, not just
+/// the one in the path.
+fn rest_access(method: &Method, path: &str, registry: &AgentRegistry) -> (Access, Target) {
+    let segments: Vec<&str> = path.split('/').collect();
+    let access = if method == Method::GET {
+        Access::Read
+    } else if segments.contains(&"workspaces") || segments.contains(&"auto-run") {
+        Access::Agent
+    } else {
+        Access::Write
+    };
+    let target = match segments.as_slice() {
+        ["projects", project_id, "workspaces", workspace_id, ..] => match registry.project_of(workspace_id) {
+            Some(owner) if owner != *project_id => Target::AllProjects,
+            _ => Target::Project(project_id.to_string()),
+        },
+        ["projects", project_id, ..] => Target::Project(project_id.to_string()),
+        // The event stream filters events by the token's projects itself.
+        ["projects"] | ["openapi.json"] | ["events"] if method == Method::GET => Target::Global,
+        _ => Target::AllProjects,
+    };
+    (access, target)
 }
 
 fn extract_token(request: &Request) -> Option<&str> {
@@ -55,6 +394,7 @@ fn extract_token(request: &Request) -> Option<&str> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::executor::AgentHandle;
 
     fn make_request_with_header(token: &str) -> Request {
         Request::builder()
@@ -78,6 +418,185 @@ mod tests {
             .unwrap()
     }
 
+    fn api_token(scopes: &[TokenScope], projects: &[&str]) -> ApiToken {
+        ApiToken {
+            id: "t1".to_string(),
+            name: "teammate".to_string(),
+            token_hash: hash_token("scoped-secret"),
+            scopes: scopes.to_vec(),
+            projects: projects.iter().map(|p| p.to_string()).collect(),
+            created_at: Utc::now().to_rfc3339(),
+            expires_at: None,
+            last_used_at: None,
+        }
+    }
+
+    fn grant(scopes: &[TokenScope], projects: &[&str]) -> Grant {
+        Grant::Token(Arc::new(api_token(scopes, projects)))
+    }
+
+    #[test]
+    fn test_match_token() {
+        let mut http = HttpServerConfig {
+            bind_address: "0.0.0.0".to_string(),
+            auth_token: "primary-secret".to_string(),
+            ..Default::default()
+        };
+        http.tokens.push(api_token(&[TokenScope::Read], &[]));
+        let now = Utc::now();
+
+        assert!(matches!(match_token(&http, "primary-secret", now), Some(Grant::Full)));
+        assert!(matches!(match_token(&http, "scoped-secret", now), Some(Grant::Token(_))));
+        assert!(match_token(&http, "wrong", now).is_none());
+
+        http.tokens[0].expires_at = Some((now - chrono::Duration::minutes(1)).to_rfc3339());
+        assert!(match_token(&http, "scoped-secret", now).is_none());
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq("abc", "abc"));
+        assert!(!constant_time_eq("abc", "abd"));
+        assert!(!constant_time_eq("abc", "abcd"));
+    }
+
+    #[test]
+    fn test_every_command_has_access_level() {
+        for command in COMMAND_NAMES {
+            assert!(command_access(command).is_some(), "{command} has no access level");
+        }
+    }
+
+    #[test]
+    fn test_scopes() {
+        let read = grant(&[TokenScope::Read], &[]);
+        assert!(read.authorize(Access::Read, &Target::AllProjects).is_ok());
+        assert!(read.authorize(Access::Write, &Target::Global).is_err());
+        assert!(read.authorize(Access::Agent, &Target::Global).is_err());
+
+        let agent = grant(&[TokenScope::Agent], &[]);
+        assert!(agent.allows(Access::Read));
+        assert!(agent.allows(Access::Agent));
+        assert!(!agent.allows(Access::Write));
+        assert!(!agent.allows(Access::Admin));
+        assert!(Grant::Full.allows(Access::Admin));
+    }
+
+    #[test]
+    fn test_project_restricted_token() {
+        let registry = AgentRegistry::new();
+        let scoped = grant(&[TokenScope::Write], &["p1"]);
+        let allowed = |command: &str, args: Value| {
+            let access = command_access(command).unwrap();
+            scoped
+                .authorize(access, &command_target(command, &args, &registry))
+                .is_ok()
+        };
+
+        assert!(allowed("list_cards", serde_json::json!({ "projectId": "p1" })));
+        assert!(!allowed("list_cards", serde_json::json!({ "projectId": "p2" })));
+        assert!(allowed("delete_project", serde_json::json!({ "id": "p1" })));
+        assert!(!allowed("delete_project", serde_json::json!({ "id": "p2" })));
+        assert!(allowed("list_projects", serde_json::json!({})));
+        assert!(!allowed("create_project", serde_json::json!({ "name": "new" })));
+        assert!(!allowed("list_running_workspaces", serde_json::json!({})));
+        assert!(!allowed("update_global_config", serde_json::json!({})));
+
+        let (access, target) = rest_access(&Method::PATCH, "projects/p2/cards/c1", &registry);
+        assert_eq!(access, Access::Write);
+        assert!(scoped.authorize(access, &target).is_err());
+        let (access, target) = rest_access(&Method::POST, "projects/p1/cards/c1/workspaces", &registry);
+        assert_eq!(access, Access::Agent);
+        assert_eq!(target, Target::Project("p1".to_string()));
+    }
+
+    #[test]
+    fn test_rest_access_checks_the_agents_own_project() {
+        let registry = AgentRegistry::new();
+        registry.insert(AgentHandle {
+            workspace_id: "ws-2".to_string(),
+            project_id: "p2".to_string(),
+            stdin_tx: tokio::sync::mpsc::channel(1).0,
+            pid: 0,
+            pty: None,
+        });
+        let scoped = grant(&[TokenScope::Agent], &["p1"]);
+
+        let (access, target) = rest_access(&Method::POST, "projects/p1/workspaces/ws-2/input", &registry);
+        assert!(scoped.authorize(access, &target).is_err());
+        let (access, target) = rest_access(&Method::POST, "projects/p2/workspaces/ws-2/stop", &registry);
+        assert!(scoped.authorize(access, &target).is_err());
+        let (access, target) = rest_access(&Method::POST, "projects/p1/workspaces/ws-1/stop", &registry);
+        assert!(scoped.authorize(access, &target).is_ok());
+    }
+
+    #[test]
+    fn test_response_filters() {
+        let scoped = grant(&[TokenScope::Read], &["p1"]);
+
+        let mut projects = serde_json::json!([{ "id": "p1" }, { "id": "p2" }]);
+        ResponseFilter::Projects.apply(&scoped, &mut projects);
+        assert_eq!(projects, serde_json::json!([{ "id": "p1" }]));
+
+        let mut config = serde_json::json!({
+            "last_project_id": "p2",
+            "agents": [{ "name": "claude-code", "env_vars": { "API_KEY": "secret" } }],
+        });
+        ResponseFilter::GlobalConfig.apply(&scoped, &mut config);
+        assert_eq!(config["last_project_id"], "");
+        assert!(config["agents"][0]["env_vars"].is_null());
+    }
+
+    #[test]
+    fn test_allows_event() {
+        let registry = AgentRegistry::new();
+        let scoped = grant(&[TokenScope::Read], &["p1"]);
+        let changed = |project_id: &str| MaestroEvent::CardsChanged {
+            project_id: project_id.to_string(),
+        };
+
+        assert!(scoped.allows_event(&changed("p1"), &registry));
+        assert!(!scoped.allows_event(&changed("p2"), &registry));
+        assert!(scoped.allows_event(&MaestroEvent::ProjectsChanged, &registry));
+        assert!(Grant::Full.allows_event(&changed("p2"), &registry));
+
+        let log = |project_id: &str| {
+            MaestroEvent::AgentLog(crate::executor::AgentLogEvent {
+                project_id: project_id.to_string(),
+                card_id: "c1".to_string(),
+                workspace_id: None,
+                level: "info".to_string(),
+                message: "Tests pass".to_string(),
+                data: None,
+                timestamp: String::new(),
+            })
+        };
+        assert!(scoped.allows_event(&log("p1"), &registry));
+        assert!(!scoped.allows_event(&log("p2"), &registry));
+    }
+
+    #[test]
+    fn test_allows_exit_of_agent_no_longer_running() {
+        let registry = AgentRegistry::new();
+        registry.insert(crate::executor::AgentHandle {
+            workspace_id: "ws1".to_string(),
+            project_id: "p1".to_string(),
+            stdin_tx: tokio::sync::mpsc::channel(1).0,
+            pid: 0,
+            pty: None,
+        });
+        registry.remove("ws1");
+        let exit = MaestroEvent::AgentExit(crate::executor::lifecycle::AgentExitEvent {
+            workspace_id: "ws1".to_string(),
+            exit_code: Some(0),
+            status: "completed".to_string(),
+            reason: None,
+        });
+
+        assert!(grant(&[TokenScope::Read], &["p1"]).allows_event(&exit, &registry));
+        assert!(!grant(&[TokenScope::Read], &["p2"]).allows_event(&exit, &registry));
+    }
+
     #[test]
     fn test_extract_token_from_header() {
         let request = make_request_with_header("my-secret-token");
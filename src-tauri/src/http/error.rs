@@ -8,6 +8,7 @@ use serde_json::json;
 #[derive(Debug)]
 pub enum AppError {
     BadRequest(String),
+    Forbidden(String),
     NotFound(String),
     Internal(String),
 }
@@ -16,6 +17,7 @@ impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, message) = match self {
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
+            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
             AppError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
         };
@@ -31,14 +33,34 @@ impl From<String> for AppError {
     }
 }
 
+/// Looks up a command argument. The frontend sends camelCase keys, as Tauri
+/// expects, so those are accepted alongside the snake_case parameter name.
+fn lookup_arg<'a>(args: &'a serde_json::Value, key: &str) -> Option<&'a serde_json::Value> {
+    args.get(key)
+        .or_else(|| args.get(snake_to_camel(key)))
+        .filter(|value| !value.is_null())
+}
+
+fn snake_to_camel(key: &str) -> String {
+    let mut parts = key.split('_');
+    let mut camel = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            camel.extend(first.to_uppercase());
+            camel.push_str(chars.as_str());
+        }
+    }
+    camel
+}
+
 pub fn extract_arg<T: serde::de::DeserializeOwned>(
     args: &serde_json::Value,
     key: &str,
 ) -> Result<T, AppError> {
-    if args.get(key).is_none() || args[key].is_null() {
-        return Err(AppError::BadRequest(format!("missing required arg: {key}")));
-    }
-    serde_json::from_value(args[key].clone())
+    let value = lookup_arg(args, key)
+        .ok_or_else(|| AppError::BadRequest(format!("missing required arg: {key}")))?;
+    serde_json::from_value(value.clone())
         .map_err(|_| AppError::BadRequest(format!("invalid arg: {key}")))
 }
 
@@ -46,11 +68,37 @@ pub fn extract_optional_arg<T: serde::de::DeserializeOwned>(
     args: &serde_json::Value,
     key: &str,
 ) -> Result<Option<T>, AppError> {
-    if args.get(key).is_none() || args[key].is_null() {
+    let Some(value) = lookup_arg(args, key) else {
         return Ok(None);
-    }
+    };
     Ok(Some(
-        serde_json::from_value(args[key].clone())
+        serde_json::from_value(value.clone())
             .map_err(|_| AppError::BadRequest(format!("invalid arg: {key}")))?,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_arg_accepts_snake_and_camel_case() {
+        let snake = serde_json::json!({ "project_id": "p1" });
+        let camel = serde_json::json!({ "projectId": "p1" });
+        assert_eq!(extract_arg::<String>(&snake, "project_id").unwrap(), "p1");
+        assert_eq!(extract_arg::<String>(&camel, "project_id").unwrap(), "p1");
+    }
+
+    #[test]
+    fn test_extract_arg_missing_or_null() {
+        let args = serde_json::json!({ "cardId": null });
+        assert!(matches!(extract_arg::<String>(&args, "card_id"), Err(AppError::BadRequest(_))));
+        assert_eq!(extract_optional_arg::<String>(&args, "card_id").unwrap(), None);
+    }
+
+    #[test]
+    fn test_snake_to_camel() {
+        assert_eq!(snake_to_camel("in_progress_status_id"), "inProgressStatusId");
+        assert_eq!(snake_to_camel("name"), "name");
+    }
+}
@@ -1,5 +1,10 @@
 pub mod auth;
 pub mod error;
+pub mod openapi;
+pub mod rest;
 pub mod routes;
 pub mod server;
+pub mod sse;
+pub mod static_files;
+pub mod tls;
 pub mod websocket;
@@ -0,0 +1,213 @@
+use std::any::TypeId;
+
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+
+pub type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+/// One REST route, as listed in `rest_api!`.
+pub struct Endpoint {
+    pub tag: &'static str,
+    pub method: &'static str,
+    /// Axum-style path relative to `/api`, e.g. `/projects/:project_id`.
+    pub path: &'static str,
+    pub operation_id: &'static str,
+    pub summary: &'static str,
+    pub request: Option<SchemaFn>,
+    /// A struct whose fields are the route's query-string parameters.
+    pub query: Option<SchemaFn>,
+    /// `None` for routes that answer `204 No Content`.
+    pub response: Option<SchemaFn>,
+}
+
+pub fn schema_for<T: JsonSchema>(gen: &mut SchemaGenerator) -> Schema {
+    gen.subschema_for::<T>()
+}
+
+/// The struct's own schema rather than a reference to it, so its fields
+/// can be listed as parameters.
+pub fn params_schema_for<T: JsonSchema>(gen: &mut SchemaGenerator) -> Schema {
+    T::json_schema(gen)
+}
+
+pub fn response_schema<T: JsonSchema + 'static>() -> Option<SchemaFn> {
+    if TypeId::of::<T>() == TypeId::of::<()>() {
+        None
+    } else {
+        Some(schema_for::<T>)
+    }
+}
+
+pub fn openapi_document(endpoints: &[Endpoint]) -> Value {
+    let mut gen = SchemaSettings::openapi3().into_generator();
+    let mut paths = Map::new();
+
+    for endpoint in endpoints {
+        let mut operation = json!({
+            "operationId": endpoint.operation_id,
+            "summary": endpoint.summary,
+            "tags": [endpoint.tag],
+            "responses": {
+                "default": {
+                    "description": "Error",
+                    "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Error" } } }
+                }
+            }
+        });
+
+        let mut parameters: Vec<Value> = path_params(endpoint.path)
+            .map(|name| json!({ "name": name, "in": "path", "required": true, "schema": { "type": "string" } }))
+            .collect();
+        if let Some(query) = endpoint.query {
+            parameters.extend(query_params(&query(&mut gen)));
+        }
+        if !parameters.is_empty() {
+            operation["parameters"] = Value::Array(parameters);
+        }
+
+        if let Some(request) = endpoint.request {
+            operation["requestBody"] = json!({
+                "required": true,
+                "content": { "application/json": { "schema": request(&mut gen) } }
+            });
+        }
+
+        let (status, response) = match endpoint.response {
+            Some(response) => (
+                "200",
+                json!({
+                    "description": "OK",
+                    "content": { "application/json": { "schema": response(&mut gen) } }
+                }),
+            ),
+            None => ("204", json!({ "description": "No Content" })),
+        };
+        operation["responses"][status] = response;
+
+        let item = paths
+            .entry(openapi_path(endpoint.path))
+            .or_insert_with(|| json!({}));
+        item[endpoint.method] = operation;
+    }
+
+    let mut schemas: Map<String, Value> = gen
+        .definitions()
+        .iter()
+        .map(|(name, schema)| (name.clone(), serde_json::to_value(schema).unwrap()))
+        .collect();
+    schemas.insert(
+        "Error".to_string(),
+        json!({
+            "type": "object",
+            "properties": { "error": { "type": "string" } },
+            "required": ["error"]
+        }),
+    );
+
+    json!({
+        "openapi": "3.0.3",
+        "info": { "title": "Maestro API", "version": env!("CARGO_PKG_VERSION") },
+        "servers": [{ "url": "/api" }],
+        "paths": paths,
+        "components": {
+            "schemas": schemas,
+            "securitySchemes": { "bearerAuth": { "type": "http", "scheme": "bearer" } }
+        },
+        "security": [{ "bearerAuth": [] }]
+    })
+}
+
+fn query_params(schema: &Schema) -> Vec<Value> {
+    let Schema::Object(object) = schema else {
+        return Vec::new();
+    };
+    let Some(validation) = &object.object else {
+        return Vec::new();
+    };
+    validation
+        .properties
+        .iter()
+        .map(|(name, property)| {
+            let mut param = json!({
+                "name": name,
+                "in": "query",
+                "required": validation.required.contains(name),
+                "schema": property,
+            });
+            let description = match property {
+                Schema::Object(p) => p.metadata.as_ref().and_then(|m| m.description.clone()),
+                Schema::Bool(_) => None,
+            };
+            if let Some(description) = description {
+                param["description"] = description.into();
+            }
+            param
+        })
+        .collect()
+}
+
+fn path_params(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter_map(|segment| segment.strip_prefix(':'))
+}
+
+/// `/projects/:project_id` -> `/projects/{project_id}`
+pub(crate) fn openapi_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| match segment.strip_prefix(':') {
+            Some(name) => format!("{{{name}}}"),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::projects::ProjectSummary;
+
+    #[test]
+    fn test_openapi_path() {
+        assert_eq!(openapi_path("/projects/:project_id/cards/:id"), "/projects/{project_id}/cards/{id}");
+        assert_eq!(openapi_path("/projects"), "/projects");
+    }
+
+    #[test]
+    fn test_document_lists_operations_and_schemas() {
+        let endpoints = [
+            Endpoint {
+                tag: "projects",
+                method: "post",
+                path: "/projects/:id",
+                operation_id: "create_project",
+                summary: "Create a project",
+                request: Some(schema_for::<ProjectSummary>),
+                query: None,
+                response: response_schema::<ProjectSummary>(),
+            },
+            Endpoint {
+                tag: "projects",
+                method: "delete",
+                path: "/projects/:id",
+                operation_id: "delete_project",
+                summary: "Delete a project",
+                request: None,
+                query: None,
+                response: response_schema::<()>(),
+            },
+        ];
+        let doc = openapi_document(&endpoints);
+
+        let item = &doc["paths"]["/projects/{id}"];
+        assert_eq!(item["post"]["operationId"], "create_project");
+        assert_eq!(item["post"]["parameters"][0]["name"], "id");
+        assert_eq!(
+            item["post"]["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/ProjectSummary"
+        );
+        assert!(item["delete"]["responses"]["204"].is_object());
+        assert!(doc["components"]["schemas"]["ProjectSummary"]["properties"]["name"].is_object());
+    }
+}
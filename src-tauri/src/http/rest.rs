@@ -0,0 +1,882 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+    routing, Json, Router,
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::auth::Grant;
+use super::error::AppError;
+use super::openapi::{params_schema_for, response_schema, schema_for, Endpoint, SchemaFn};
+use super::server::AppState;
+use crate::commands::{
+    agent::{
+        get_workspace_inner, launch_agent_inner, list_running_workspaces_inner,
+        list_workspaces_inner, resume_agent_inner, send_agent_input_inner, spawn_auto_run,
+        stop_agent_inner, AgentWorkspace,
+    },
+    artifacts::{
+        create_artifact_inner, delete_artifact_inner, list_artifacts_inner, read_artifact_inner,
+        update_artifact_inner, Artifact,
+    },
+    cards::{
+        create_card_inner, delete_card_inner, get_card_inner, list_cards_inner,
+        list_sub_cards_inner, move_card_inner, update_card_inner, CardInput, CardWithStatus,
+    },
+    dependencies::{
+        add_card_dependency_inner, list_card_dependencies_inner, remove_card_dependency_inner,
+        CardDependencies,
+    },
+    history::{get_card_history_inner, CardEvent},
+    logs::{list_card_logs_inner, CardLog, LogLevel},
+    projects::{
+        create_project_inner, delete_project_inner, get_project_inner, list_projects_inner,
+        update_project_inner, Project, ProjectSummary,
+    },
+    questions::{
+        create_question_inner, delete_question_inner, list_questions_inner,
+        resolve_question_inner, unresolve_question_inner, OpenQuestion,
+    },
+    statuses::{
+        create_status_inner, delete_status_inner, list_statuses_inner, set_status_auto_run_inner,
+        update_status_inner, AutoRunPolicy, Status,
+    },
+};
+use crate::executor::MaestroEvent;
+
+/// Lists the REST routes once; expands to both the router and the endpoint
+/// table `/api/openapi.json` is generated from. `-> ()` routes answer 204;
+/// `[Query]` after the handler documents its query-string parameters.
+macro_rules! rest_api {
+    ($(
+        $tag:literal {
+            $($method:ident $path:literal => $handler:ident $(($body:ty))? $([$query:ty])? -> $response:ty, $summary:literal;)*
+        }
+    )*) => {
+        pub fn rest_routes() -> Router<AppState> {
+            Router::new()
+                $($(.route($path, routing::$method($handler)))*)*
+        }
+
+        pub fn rest_endpoints() -> Vec<Endpoint> {
+            vec![$($(Endpoint {
+                tag: $tag,
+                method: stringify!($method),
+                path: $path,
+                operation_id: stringify!($handler),
+                summary: $summary,
+                request: None $(.or(Some(schema_for::<$body> as SchemaFn)))?,
+                query: None $(.or(Some(params_schema_for::<$query> as SchemaFn)))?,
+                response: response_schema::<$response>(),
+            }),*),*]
+        }
+    };
+}
+
+rest_api! {
+    "projects" {
+        get "/projects" => list_projects -> Vec<ProjectSummary>, "List projects";
+        post "/projects" => create_project(CreateProjectRequest) -> Project, "Create a project";
+        get "/projects/:project_id" => get_project -> Project, "Get a project";
+        patch "/projects/:project_id" => update_project(UpdateProjectRequest) -> Project, "Update a project";
+        delete "/projects/:project_id" => delete_project -> (), "Delete a project and its data";
+    }
+    "statuses" {
+        get "/projects/:project_id/statuses" => list_statuses -> Vec<Status>, "List a project's statuses";
+        post "/projects/:project_id/statuses" => create_status(CreateStatusRequest) -> Status, "Create a status";
+        patch "/projects/:project_id/statuses/:id" => update_status(UpdateStatusRequest) -> Status, "Update a status";
+        put "/projects/:project_id/statuses/:id/auto-run" => set_status_auto_run(Option<AutoRunPolicy>) -> Status, "Set or clear a status's auto-run policy";
+        delete "/projects/:project_id/statuses/:id" => delete_status -> (), "Delete a status";
+    }
+    "cards" {
+        get "/projects/:project_id/cards" => list_cards -> Vec<CardWithStatus>, "List a project's cards";
+        post "/projects/:project_id/cards" => create_card(CreateCardRequest) -> CardWithStatus, "Create a card";
+        get "/projects/:project_id/cards/:id" => get_card -> CardWithStatus, "Get a card";
+        patch "/projects/:project_id/cards/:id" => update_card(UpdateCardRequest) -> CardWithStatus, "Update a card";
+        delete "/projects/:project_id/cards/:id" => delete_card -> (), "Delete a card";
+        get "/projects/:project_id/cards/:id/children" => list_sub_cards -> Vec<CardWithStatus>, "List a card's sub-cards";
+        post "/projects/:project_id/cards/:id/move" => move_card(MoveCardRequest) -> CardWithStatus, "Move a card to a status and position";
+        get "/projects/:project_id/cards/:id/dependencies" => list_card_dependencies -> CardDependencies, "List the cards a card waits on and blocks";
+        post "/projects/:project_id/cards/:id/dependencies" => add_card_dependency(AddDependencyRequest) -> CardDependencies, "Make a card wait on another";
+        delete "/projects/:project_id/cards/:id/dependencies/:depends_on_id" => remove_card_dependency -> CardDependencies, "Remove a dependency";
+        get "/projects/:project_id/cards/:id/history" => get_card_history -> Vec<CardEvent>, "List who changed a card and how, oldest first";
+        get "/projects/:project_id/cards/:id/logs" => list_card_logs [CardLogsQuery] -> Vec<CardLog>, "List the progress notes agents logged on a card";
+    }
+    "questions" {
+        get "/projects/:project_id/cards/:id/questions" => list_questions -> Vec<OpenQuestion>, "List a card's questions";
+        post "/projects/:project_id/cards/:id/questions" => create_question(CreateQuestionRequest) -> OpenQuestion, "Ask a question on a card";
+        post "/projects/:project_id/questions/:id/resolve" => resolve_question(ResolveQuestionRequest) -> OpenQuestion, "Resolve a question";
+        post "/projects/:project_id/questions/:id/unresolve" => unresolve_question -> OpenQuestion, "Reopen a question";
+        delete "/projects/:project_id/questions/:id" => delete_question -> (), "Delete a question";
+    }
+    "artifacts" {
+        get "/projects/:project_id/cards/:id/artifacts" => list_artifacts -> Vec<Artifact>, "List a card's artifacts";
+        post "/projects/:project_id/cards/:id/artifacts" => create_artifact(CreateArtifactRequest) -> Artifact, "Create an artifact";
+        get "/projects/:project_id/artifacts/:id" => read_artifact -> String, "Read an artifact's content";
+        put "/projects/:project_id/artifacts/:id" => update_artifact(UpdateArtifactRequest) -> Artifact, "Replace an artifact's content";
+        delete "/projects/:project_id/artifacts/:id" => delete_artifact -> (), "Delete an artifact";
+    }
+    "workspaces" {
+        get "/workspaces/running" => list_running_workspaces -> Vec<AgentWorkspace>, "List running agents across projects";
+        get "/projects/:project_id/cards/:id/workspaces" => list_workspaces -> Vec<AgentWorkspace>, "List a card's agent workspaces";
+        post "/projects/:project_id/cards/:id/workspaces" => launch_agent(LaunchAgentRequest) -> AgentWorkspace, "Launch an agent on a card";
+        get "/projects/:project_id/workspaces/:id" => get_workspace -> AgentWorkspace, "Get an agent workspace";
+        post "/projects/:project_id/workspaces/:id/stop" => stop_agent -> AgentWorkspace, "Stop a running agent";
+        post "/projects/:project_id/workspaces/:id/resume" => resume_agent -> AgentWorkspace, "Resume a finished agent's session";
+        post "/projects/:project_id/workspaces/:id/input" => send_agent_input(AgentInputRequest) -> (), "Send a line of input to a running agent";
+    }
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateProjectRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateProjectRequest {
+    pub name: Option<String>,
+    pub agent_config: Option<serde_json::Value>,
+    pub base_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateStatusRequest {
+    pub group: String,
+    pub name: String,
+    pub is_default: Option<bool>,
+    pub status_prompts: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateStatusRequest {
+    pub name: Option<String>,
+    pub is_default: Option<bool>,
+    pub status_prompts: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateCardRequest {
+    pub title: String,
+    pub description: Option<String>,
+    pub labels: Option<Vec<String>>,
+    pub parent_id: Option<String>,
+    pub status_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateCardRequest {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub labels: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MoveCardRequest {
+    pub status_id: String,
+    pub sort_order: i32,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateQuestionRequest {
+    pub question: String,
+    /// `user` or `agent`; defaults to `user`.
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ResolveQuestionRequest {
+    pub resolution: Option<String>,
+    /// `user` or `agent`; defaults to `user`.
+    pub resolved_by: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AddDependencyRequest {
+    /// The card that has to be Completed first.
+    pub depends_on_id: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateArtifactRequest {
+    pub name: String,
+    pub content: String,
+    /// `user` or `agent`; defaults to `user`.
+    pub created_by: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateArtifactRequest {
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LaunchAgentRequest {
+    pub status_id: String,
+    pub worktree_path: Option<String>,
+    pub branch_name: Option<String>,
+    pub repo_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AgentInputRequest {
+    pub text: String,
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct CardLogsQuery {
+    /// Only logs at this level or above.
+    pub level: Option<LogLevel>,
+    /// Only logs written by this agent workspace.
+    pub workspace_id: Option<String>,
+}
+
+type ApiResult<T> = Result<Json<T>, AppError>;
+
+// ============================================================================
+// Projects
+// ============================================================================
+
+async fn list_projects(State(state): State<AppState>) -> ApiResult<Vec<ProjectSummary>> {
+    Ok(Json(list_projects_inner(&state.config)?))
+}
+
+async fn create_project(
+    State(state): State<AppState>,
+    Json(body): Json<CreateProjectRequest>,
+) -> ApiResult<Project> {
+    let result = create_project_inner(&state.config, &body.name)?;
+    state.event_bus.emit_maestro(MaestroEvent::ProjectsChanged);
+    Ok(Json(result))
+}
+
+async fn get_project(
+    State(state): State<AppState>,
+    Path(project_id): Path<String>,
+) -> ApiResult<Project> {
+    Ok(Json(get_project_inner(&state.config, &project_id)?))
+}
+
+async fn update_project(
+    State(state): State<AppState>,
+    Path(project_id): Path<String>,
+    Json(body): Json<UpdateProjectRequest>,
+) -> ApiResult<Project> {
+    let result = update_project_inner(
+        &state.config,
+        &project_id,
+        body.name,
+        body.agent_config,
+        body.base_path,
+    )?;
+    state.event_bus.emit_maestro(MaestroEvent::ProjectsChanged);
+    Ok(Json(result))
+}
+
+async fn delete_project(
+    State(state): State<AppState>,
+    Path(project_id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    delete_project_inner(&state.config, &project_id)?;
+    state.event_bus.emit_maestro(MaestroEvent::ProjectsChanged);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Statuses
+// ============================================================================
+
+async fn list_statuses(
+    State(state): State<AppState>,
+    Path(project_id): Path<String>,
+) -> ApiResult<Vec<Status>> {
+    Ok(Json(list_statuses_inner(&state.config, &project_id)?))
+}
+
+async fn create_status(
+    State(state): State<AppState>,
+    Path(project_id): Path<String>,
+    Json(body): Json<CreateStatusRequest>,
+) -> ApiResult<Status> {
+    let result = create_status_inner(
+        &state.config,
+        &project_id,
+        &body.group,
+        &body.name,
+        body.is_default,
+        body.status_prompts,
+    )?;
+    state.event_bus.emit_maestro(MaestroEvent::StatusesChanged { project_id });
+    Ok(Json(result))
+}
+
+async fn update_status(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+    Json(body): Json<UpdateStatusRequest>,
+) -> ApiResult<Status> {
+    let result = update_status_inner(
+        &state.config,
+        &project_id,
+        &id,
+        body.name,
+        body.is_default,
+        body.status_prompts,
+    )?;
+    state.event_bus.emit_maestro(MaestroEvent::StatusesChanged { project_id });
+    Ok(Json(result))
+}
+
+async fn set_status_auto_run(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+    Json(auto_run): Json<Option<AutoRunPolicy>>,
+) -> ApiResult<Status> {
+    let result = set_status_auto_run_inner(&state.config, &project_id, &id, auto_run)?;
+    state.event_bus.emit_maestro(MaestroEvent::StatusesChanged { project_id });
+    Ok(Json(result))
+}
+
+async fn delete_status(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    delete_status_inner(&state.config, &project_id, &id)?;
+    state.event_bus.emit_maestro(MaestroEvent::StatusesChanged { project_id });
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Cards
+// ============================================================================
+
+async fn list_cards(
+    State(state): State<AppState>,
+    Path(project_id): Path<String>,
+) -> ApiResult<Vec<CardWithStatus>> {
+    Ok(Json(list_cards_inner(&state.config, &project_id)?))
+}
+
+async fn create_card(
+    State(state): State<AppState>,
+    Extension(grant): Extension<Grant>,
+    Path(project_id): Path<String>,
+    Json(body): Json<CreateCardRequest>,
+) -> ApiResult<CardWithStatus> {
+    let card = CardInput {
+        title: body.title,
+        description: body.description,
+        labels: body.labels,
+        parent_id: body.parent_id,
+        status_id: body.status_id,
+    };
+    let result = create_card_inner(&state.config, &project_id, card, &grant.actor())?;
+    state.event_bus.emit_maestro(MaestroEvent::CardsChanged { project_id });
+    Ok(Json(result))
+}
+
+async fn get_card(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+) -> ApiResult<CardWithStatus> {
+    Ok(Json(get_card_inner(&state.config, &project_id, &id)?))
+}
+
+async fn update_card(
+    State(state): State<AppState>,
+    Extension(grant): Extension<Grant>,
+    Path((project_id, id)): Path<(String, String)>,
+    Json(body): Json<UpdateCardRequest>,
+) -> ApiResult<CardWithStatus> {
+    let result = update_card_inner(
+        &state.config,
+        &project_id,
+        &id,
+        body.title,
+        body.description,
+        body.labels,
+        &grant.actor(),
+    )?;
+    state.event_bus.emit_maestro(MaestroEvent::CardsChanged { project_id });
+    Ok(Json(result))
+}
+
+async fn delete_card(
+    State(state): State<AppState>,
+    Extension(grant): Extension<Grant>,
+    Path((project_id, id)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    delete_card_inner(&state.config, &project_id, &id, &grant.actor())?;
+    state.event_bus.emit_maestro(MaestroEvent::CardsChanged { project_id });
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_sub_cards(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+) -> ApiResult<Vec<CardWithStatus>> {
+    Ok(Json(list_sub_cards_inner(&state.config, &project_id, &id)?))
+}
+
+async fn move_card(
+    State(state): State<AppState>,
+    Extension(grant): Extension<Grant>,
+    Path((project_id, id)): Path<(String, String)>,
+    Json(body): Json<MoveCardRequest>,
+) -> ApiResult<CardWithStatus> {
+    let previous_status_id = get_card_inner(&state.config, &project_id, &id)?.status_id;
+    let result = move_card_inner(&state.config, &project_id, &id, &body.status_id, body.sort_order, &grant.actor())?;
+    state.event_bus.emit_maestro(MaestroEvent::CardsChanged {
+        project_id: project_id.clone(),
+    });
+    if result.status_id != previous_status_id {
+        spawn_auto_run(
+            None,
+            Some(Arc::clone(&state.event_bus)),
+            Arc::clone(&state.config),
+            Arc::clone(&state.registry),
+            project_id,
+            id,
+        );
+    }
+    Ok(Json(result))
+}
+
+async fn list_card_dependencies(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+) -> ApiResult<CardDependencies> {
+    Ok(Json(list_card_dependencies_inner(&state.config, &project_id, &id)?))
+}
+
+async fn add_card_dependency(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+    Json(body): Json<AddDependencyRequest>,
+) -> ApiResult<CardDependencies> {
+    let result = add_card_dependency_inner(&state.config, &project_id, &id, &body.depends_on_id)?;
+    state.event_bus.emit_maestro(MaestroEvent::CardsChanged { project_id });
+    Ok(Json(result))
+}
+
+async fn remove_card_dependency(
+    State(state): State<AppState>,
+    Path((project_id, id, depends_on_id)): Path<(String, String, String)>,
+) -> ApiResult<CardDependencies> {
+    let result = remove_card_dependency_inner(&state.config, &project_id, &id, &depends_on_id)?;
+    state.event_bus.emit_maestro(MaestroEvent::CardsChanged { project_id });
+    Ok(Json(result))
+}
+
+async fn get_card_history(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+) -> ApiResult<Vec<CardEvent>> {
+    Ok(Json(get_card_history_inner(&state.config, &project_id, &id)?))
+}
+
+async fn list_card_logs(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+    Query(query): Query<CardLogsQuery>,
+) -> ApiResult<Vec<CardLog>> {
+    Ok(Json(list_card_logs_inner(
+        &state.config,
+        &project_id,
+        &id,
+        query.level,
+        query.workspace_id.as_deref(),
+    )?))
+}
+
+// ============================================================================
+// Questions
+// ============================================================================
+
+async fn list_questions(
+    State(state): State<AppState>,
+    Path((project_id, card_id)): Path<(String, String)>,
+) -> ApiResult<Vec<OpenQuestion>> {
+    Ok(Json(list_questions_inner(&state.config, &project_id, &card_id)?))
+}
+
+async fn create_question(
+    State(state): State<AppState>,
+    Path((project_id, card_id)): Path<(String, String)>,
+    Json(body): Json<CreateQuestionRequest>,
+) -> ApiResult<OpenQuestion> {
+    let source = body.source.as_deref().unwrap_or("user");
+    let result = create_question_inner(&state.config, &project_id, &card_id, &body.question, source)?;
+    state.event_bus.emit_maestro(MaestroEvent::QuestionsChanged { project_id });
+    Ok(Json(result))
+}
+
+async fn resolve_question(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+    Json(body): Json<ResolveQuestionRequest>,
+) -> ApiResult<OpenQuestion> {
+    let resolved_by = body.resolved_by.as_deref().unwrap_or("user");
+    let result = resolve_question_inner(&state.config, &project_id, &id, body.resolution, resolved_by)?;
+    state.event_bus.emit_maestro(MaestroEvent::QuestionsChanged { project_id });
+    Ok(Json(result))
+}
+
+async fn unresolve_question(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+) -> ApiResult<OpenQuestion> {
+    let result = unresolve_question_inner(&state.config, &project_id, &id)?;
+    state.event_bus.emit_maestro(MaestroEvent::QuestionsChanged { project_id });
+    Ok(Json(result))
+}
+
+async fn delete_question(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    delete_question_inner(&state.config, &project_id, &id)?;
+    state.event_bus.emit_maestro(MaestroEvent::QuestionsChanged { project_id });
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Artifacts
+// ============================================================================
+
+async fn list_artifacts(
+    State(state): State<AppState>,
+    Path((project_id, card_id)): Path<(String, String)>,
+) -> ApiResult<Vec<Artifact>> {
+    Ok(Json(list_artifacts_inner(&state.config, &project_id, &card_id)?))
+}
+
+async fn create_artifact(
+    State(state): State<AppState>,
+    Path((project_id, card_id)): Path<(String, String)>,
+    Json(body): Json<CreateArtifactRequest>,
+) -> ApiResult<Artifact> {
+    let created_by = body.created_by.as_deref().unwrap_or("user");
+    let result = create_artifact_inner(
+        &state.config,
+        &project_id,
+        &card_id,
+        &body.name,
+        &body.content,
+        created_by,
+    )?;
+    state.event_bus.emit_maestro(MaestroEvent::ArtifactsChanged { project_id });
+    Ok(Json(result))
+}
+
+async fn read_artifact(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+) -> ApiResult<String> {
+    Ok(Json(read_artifact_inner(&state.config, &project_id, &id)?))
+}
+
+async fn update_artifact(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+    Json(body): Json<UpdateArtifactRequest>,
+) -> ApiResult<Artifact> {
+    let result = update_artifact_inner(&state.config, &project_id, &id, &body.content)?;
+    state.event_bus.emit_maestro(MaestroEvent::ArtifactsChanged { project_id });
+    Ok(Json(result))
+}
+
+async fn delete_artifact(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    delete_artifact_inner(&state.config, &project_id, &id)?;
+    state.event_bus.emit_maestro(MaestroEvent::ArtifactsChanged { project_id });
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// Workspaces
+// ============================================================================
+
+async fn list_running_workspaces(State(state): State<AppState>) -> ApiResult<Vec<AgentWorkspace>> {
+    Ok(Json(list_running_workspaces_inner(&state.config)?))
+}
+
+async fn list_workspaces(
+    State(state): State<AppState>,
+    Path((project_id, card_id)): Path<(String, String)>,
+) -> ApiResult<Vec<AgentWorkspace>> {
+    Ok(Json(list_workspaces_inner(&state.config, &project_id, &card_id)?))
+}
+
+async fn launch_agent(
+    State(state): State<AppState>,
+    Path((project_id, card_id)): Path<(String, String)>,
+    Json(body): Json<LaunchAgentRequest>,
+) -> ApiResult<AgentWorkspace> {
+    state.ensure_ipc(&project_id).await;
+    let result = launch_agent_inner(
+        None,
+        Some(Arc::clone(&state.event_bus)),
+        &state.config,
+        &state.registry,
+        &project_id,
+        &card_id,
+        &body.status_id,
+        body.worktree_path,
+        body.branch_name,
+        body.repo_path,
+    )
+    .await?;
+    state.event_bus.emit_maestro(MaestroEvent::WorkspacesChanged { project_id });
+    Ok(Json(result))
+}
+
+async fn get_workspace(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+) -> ApiResult<AgentWorkspace> {
+    Ok(Json(get_workspace_inner(&state.config, &project_id, &id)?))
+}
+
+/// A running agent is only reachable through the project it belongs to.
+fn check_agent_project(state: &AppState, project_id: &str, id: &str) -> Result<(), AppError> {
+    match state.registry.project_of(id) {
+        Some(owner) if owner != project_id => Err(AppError::NotFound(format!(
+            "Workspace {id} not found in project {project_id}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+async fn stop_agent(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+) -> ApiResult<AgentWorkspace> {
+    check_agent_project(&state, &project_id, &id)?;
+    let result = stop_agent_inner(&state.config, &state.registry, &project_id, &id).await?;
+    state.event_bus.emit_maestro(MaestroEvent::WorkspacesChanged { project_id });
+    Ok(Json(result))
+}
+
+async fn resume_agent(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+) -> ApiResult<AgentWorkspace> {
+    let card_id = get_workspace_inner(&state.config, &project_id, &id)?.card_id;
+    state.ensure_ipc(&project_id).await;
+    let result = resume_agent_inner(
+        None,
+        Some(Arc::clone(&state.event_bus)),
+        &state.config,
+        &state.registry,
+        &project_id,
+        &id,
+        &card_id,
+    )
+    .await?;
+    state.event_bus.emit_maestro(MaestroEvent::WorkspacesChanged { project_id });
+    Ok(Json(result))
+}
+
+async fn send_agent_input(
+    State(state): State<AppState>,
+    Path((project_id, id)): Path<(String, String)>,
+    Json(body): Json<AgentInputRequest>,
+) -> Result<StatusCode, AppError> {
+    check_agent_project(&state, &project_id, &id)?;
+    send_agent_input_inner(&state.registry, &id, &body.text).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::AgentHandle;
+    use crate::fixtures::{app_state, block_on};
+    use crate::http::openapi::{openapi_document, openapi_path};
+    use crate::http::routes::api_routes;
+    use serde_json::Value;
+
+    #[test]
+    fn test_routes_do_not_conflict() {
+        let _ = api_routes();
+    }
+
+    #[test]
+    fn test_openapi_covers_every_endpoint() {
+        let endpoints = rest_endpoints();
+        let doc = openapi_document(&endpoints);
+        for endpoint in &endpoints {
+            assert!(
+                doc["paths"][&openapi_path(endpoint.path)][endpoint.method].is_object(),
+                "{} {} missing from openapi.json",
+                endpoint.method,
+                endpoint.path
+            );
+        }
+        for schema in ["Project", "CardWithStatus", "CreateCardRequest", "AgentWorkspace", "Error"] {
+            assert!(doc["components"]["schemas"][schema].is_object(), "{schema} schema missing");
+        }
+
+        let params = &doc["paths"]["/projects/{project_id}/cards/{id}/logs"]["get"]["parameters"];
+        let query: Vec<&Value> = params.as_array().unwrap().iter().filter(|p| p["in"] == "query").collect();
+        assert_eq!(query.len(), 2);
+        assert_eq!(query[0]["name"], "level");
+        assert_eq!(query[0]["required"], false);
+        assert_eq!(query[0]["description"], "Only logs at this level or above.");
+        assert_eq!(query[1]["name"], "workspace_id");
+    }
+
+    #[test]
+    fn test_card_lifecycle_over_rest() {
+        let (state, _dir) = app_state();
+
+        block_on(async {
+            let Json(project) = create_project(
+                State(state.clone()),
+                Json(CreateProjectRequest { name: "Rest".to_string() }),
+            )
+            .await
+            .unwrap();
+            let Json(statuses) = list_statuses(State(state.clone()), Path(project.id.clone()))
+                .await
+                .unwrap();
+
+            let Json(card) = create_card(
+                State(state.clone()),
+                Extension(Grant::Full),
+                Path(project.id.clone()),
+                Json(CreateCardRequest {
+                    title: "Write docs".to_string(),
+                    description: None,
+                    labels: Some(vec!["docs".to_string()]),
+                    parent_id: None,
+                    status_id: None,
+                }),
+            )
+            .await
+            .unwrap();
+
+            let Json(updated) = update_card(
+                State(state.clone()),
+                Extension(Grant::Full),
+                Path((project.id.clone(), card.id.clone())),
+                Json(UpdateCardRequest {
+                    title: Some("Write more docs".to_string()),
+                    description: None,
+                    labels: None,
+                }),
+            )
+            .await
+            .unwrap();
+            assert_eq!(updated.title, "Write more docs");
+            assert_eq!(updated.labels, vec!["docs"]);
+
+            let target = statuses.iter().find(|s| s.id != card.status_id).unwrap();
+            let Json(moved) = move_card(
+                State(state.clone()),
+                Extension(Grant::Full),
+                Path((project.id.clone(), card.id.clone())),
+                Json(MoveCardRequest {
+                    status_id: target.id.clone(),
+                    sort_order: 0,
+                }),
+            )
+            .await
+            .unwrap();
+            assert_eq!(moved.status_id, target.id);
+
+            let status = delete_card(
+                State(state.clone()),
+                Extension(Grant::Full),
+                Path((project.id.clone(), card.id.clone())),
+            )
+            .await
+            .unwrap();
+            assert_eq!(status, StatusCode::NO_CONTENT);
+            let Json(cards) = list_cards(State(state.clone()), Path(project.id.clone()))
+                .await
+                .unwrap();
+            assert!(cards.is_empty());
+
+            let Json(history) = get_card_history(State(state.clone()), Path((project.id.clone(), card.id.clone())))
+                .await
+                .unwrap();
+            let types: Vec<&str> = history.iter().map(|e| e.event_type.as_str()).collect();
+            assert_eq!(types, vec!["created", "updated", "moved", "deleted"]);
+            assert!(history.iter().all(|e| e.actor_type == "user"));
+        });
+    }
+
+    #[test]
+    fn test_card_logs_filter_by_query() {
+        let (state, _dir) = app_state();
+
+        block_on(async {
+            let project = create_project_inner(&state.config, "Logs").unwrap();
+            let input = CardInput {
+                title: "Card".to_string(),
+                ..Default::default()
+            };
+            let card = create_card_inner(&state.config, &project.id, input, &crate::commands::history::Actor::User).unwrap();
+            let base_path = state.config.with_config(|c| Ok(c.resolve_base_path())).unwrap();
+            crate::commands::projects::open_project_db(&base_path, &project.id)
+                .unwrap()
+                .with_conn(|conn| {
+                    crate::commands::logs::insert_card_log(conn, &card.id, None, LogLevel::Info, "started", None)?;
+                    crate::commands::logs::insert_card_log(conn, &card.id, None, LogLevel::Error, "failed", None)
+                })
+                .unwrap();
+
+            let list = |query: CardLogsQuery| {
+                list_card_logs(State(state.clone()), Path((project.id.clone(), card.id.clone())), Query(query))
+            };
+            let Json(all) = list(CardLogsQuery::default()).await.unwrap();
+            assert_eq!(all.len(), 2);
+            let Json(errors) = list(CardLogsQuery {
+                level: Some(LogLevel::Warn),
+                workspace_id: None,
+            })
+            .await
+            .unwrap();
+            assert_eq!(errors.len(), 1);
+            assert_eq!(errors[0].message, "failed");
+            let Json(other) = list(CardLogsQuery {
+                level: None,
+                workspace_id: Some("ws-1".to_string()),
+            })
+            .await
+            .unwrap();
+            assert!(other.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_agent_routes_reject_another_projects_workspace() {
+        let (state, _dir) = app_state();
+        state.registry.insert(AgentHandle {
+            workspace_id: "ws-b".to_string(),
+            project_id: "project-b".to_string(),
+            stdin_tx: tokio::sync::mpsc::channel(1).0,
+            pid: 0,
+            pty: None,
+        });
+
+        block_on(async {
+            let stopped = stop_agent(State(state.clone()), Path(("project-a".to_string(), "ws-b".to_string()))).await;
+            assert!(matches!(stopped, Err(AppError::NotFound(_))));
+            let sent = send_agent_input(
+                State(state.clone()),
+                Path(("project-a".to_string(), "ws-b".to_string())),
+                Json(AgentInputRequest { text: "hi".to_string() }),
+            )
+            .await;
+            assert!(matches!(sent, Err(AppError::NotFound(_))));
+        });
+        assert!(state.registry.has("ws-b"));
+    }
+}
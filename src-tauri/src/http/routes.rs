@@ -1,19 +1,26 @@
+use std::sync::Arc;
+
 use axum::{
-    extract::{Path, State},
-    routing::post,
+    extract::{Extension, Path, State},
+    routing::{get, post},
     Json, Router,
 };
 use serde_json::Value;
 
+use super::auth::Grant;
 use super::error::{extract_arg, extract_optional_arg, AppError};
+use super::openapi::openapi_document;
+use super::rest::{rest_endpoints, rest_routes};
 use super::server::AppState;
+use super::sse::sse_events_handler;
+use crate::config::global::TokenScope;
 use crate::executor::MaestroEvent;
 
 use crate::commands::{
     agent::{
-        get_workspace_inner, launch_agent_inner, list_running_workspaces_inner,
-        list_workspaces_inner, resume_agent_inner, send_agent_input_inner, stop_agent_inner,
-        stop_all_agents_inner,
+        archive_card_workspaces_inner, get_workspace_inner, launch_agent_inner, list_running_workspaces_inner,
+        list_workspaces_inner, resize_agent_terminal_inner, resume_agent_inner, send_agent_input_inner,
+        set_launch_priority_inner, spawn_auto_run, stop_agent_inner, stop_all_agents_inner, write_agent_terminal_inner,
     },
     artifacts::{
         create_artifact_inner, delete_artifact_inner, list_artifacts_inner, read_artifact_inner,
@@ -21,16 +28,28 @@ use crate::commands::{
     },
     cards::{
         create_card_inner, delete_card_inner, get_card_inner, list_cards_inner,
-        list_sub_cards_inner, move_card_inner, reorder_cards_inner, update_card_inner,
+        list_sub_cards_inner, move_card_inner, reorder_cards_inner, update_card_inner, CardInput,
+    },
+    config::{
+        create_agent_profile_inner, delete_agent_profile_inner, get_global_config_inner,
+        get_http_server_config_inner, get_local_ip_inner, get_status_group_defaults_inner,
+        regenerate_auth_token_inner, resolve_config_inner, set_last_project_inner,
+        update_agent_profile_inner, update_global_config_inner, update_http_server_config_inner,
+        update_status_group_defaults_inner, AgentProfileInput, GlobalConfigUpdate,
+        HttpServerConfigUpdate, StatusGroupConfigInput,
     },
-    config::{get_global_config_inner, resolve_config_inner, set_last_project_inner},
     conversations::{
         count_conversation_messages_inner, create_conversation_inner, create_message_inner,
         list_conversations_inner, list_messages_inner,
     },
+    dependencies::{
+        add_card_dependency_inner, list_card_dependencies_inner, remove_card_dependency_inner,
+    },
     directories::{
         add_linked_directory_inner, list_linked_directories_inner, remove_linked_directory_inner,
     },
+    history::{get_card_history_inner, Actor},
+    logs::{list_card_logs_inner, LogLevel},
     projects::{
         create_project_inner, delete_project_inner, get_project_inner, list_projects_inner,
         update_project_inner,
@@ -39,10 +58,16 @@ use crate::commands::{
         count_unresolved_questions_inner, create_question_inner, delete_question_inner,
         list_questions_inner, resolve_question_inner, unresolve_question_inner,
     },
+    review::{
+        approve_card_inner, create_pr_inner, get_changed_files_inner, get_file_diff_inner,
+        get_review_count_inner, send_back_card_inner,
+    },
+    search::search_inner,
     statuses::{
         create_status_inner, delete_status_inner, list_statuses_inner, reorder_statuses_inner,
-        update_status_inner,
+        set_status_auto_run_inner, update_status_inner, AutoRunPolicy,
     },
+    tokens::{create_api_token_inner, list_api_tokens_inner, revoke_api_token_inner},
     worktrees::{
         check_worktree_exists_inner, create_worktree_inner, generate_branch_name_inner,
         get_card_worktree_inner, get_claude_worktree_path_inner,
@@ -50,15 +75,24 @@ use crate::commands::{
 };
 
 pub fn api_routes() -> Router<AppState> {
-    Router::new().route("/{command}", post(handle_command))
+    Router::new()
+        .route("/openapi.json", get(openapi_json))
+        .route("/events", get(sse_events_handler))
+        .route("/:command", post(handle_command))
+        .merge(rest_routes())
+}
+
+async fn openapi_json() -> Json<Value> {
+    Json(openapi_document(&rest_endpoints()))
 }
 
 async fn handle_command(
     State(state): State<AppState>,
+    Extension(grant): Extension<Grant>,
     Path(command): Path<String>,
     Json(args): Json<Value>,
 ) -> Result<Json<Value>, AppError> {
-    let result = dispatch_command(&state, &command, &args).await?;
+    let result = dispatch_command(&state, &command, &args, &grant.actor()).await?;
     Ok(Json(result))
 }
 
@@ -66,12 +100,28 @@ async fn dispatch_command(
     state: &AppState,
     command: &str,
     args: &Value,
+    actor: &Actor,
 ) -> Result<Value, AppError> {
     match command {
         // Config commands
         "get_global_config" => dispatch_config_get_global(state, args),
         "set_last_project" => dispatch_config_set_last_project(state, args),
         "resolve_config" => dispatch_config_resolve(state, args),
+        "update_global_config" => dispatch_config_update_global(state, args),
+        "create_agent_profile" => dispatch_config_create_agent_profile(state, args),
+        "update_agent_profile" => dispatch_config_update_agent_profile(state, args),
+        "delete_agent_profile" => dispatch_config_delete_agent_profile(state, args),
+        "get_status_group_defaults" => dispatch_config_get_status_group_defaults(state, args),
+        "update_status_group_defaults" => dispatch_config_update_status_group_defaults(state, args),
+        "get_http_server_config" => dispatch_config_get_http_server(state, args),
+        "update_http_server_config" => dispatch_config_update_http_server(state, args),
+        "regenerate_auth_token" => dispatch_config_regenerate_auth_token(state, args),
+        "get_local_ip" => dispatch_config_get_local_ip(state, args),
+
+        // API token commands
+        "create_api_token" => dispatch_tokens_create(state, args),
+        "list_api_tokens" => dispatch_tokens_list(state, args),
+        "revoke_api_token" => dispatch_tokens_revoke(state, args),
 
         // Project commands
         "create_project" => dispatch_projects_create(state, args),
@@ -84,19 +134,27 @@ async fn dispatch_command(
         "list_statuses" => dispatch_statuses_list(state, args),
         "create_status" => dispatch_statuses_create(state, args),
         "update_status" => dispatch_statuses_update(state, args),
+        "set_status_auto_run" => dispatch_statuses_set_auto_run(state, args),
         "delete_status" => dispatch_statuses_delete(state, args),
         "reorder_statuses" => dispatch_statuses_reorder(state, args),
 
         // Card commands
-        "create_card" => dispatch_cards_create(state, args),
+        "create_card" => dispatch_cards_create(state, args, actor),
         "get_card" => dispatch_cards_get(state, args),
-        "update_card" => dispatch_cards_update(state, args),
-        "delete_card" => dispatch_cards_delete(state, args),
+        "update_card" => dispatch_cards_update(state, args, actor),
+        "delete_card" => dispatch_cards_delete(state, args, actor),
         "list_cards" => dispatch_cards_list(state, args),
         "list_sub_cards" => dispatch_cards_list_sub(state, args),
-        "move_card" => dispatch_cards_move(state, args),
+        "move_card" => dispatch_cards_move(state, args, actor),
         "reorder_cards" => dispatch_cards_reorder(state, args),
 
+        // Card dependency commands
+        "add_card_dependency" => dispatch_dependencies_add(state, args),
+        "remove_card_dependency" => dispatch_dependencies_remove(state, args),
+        "list_card_dependencies" => dispatch_dependencies_list(state, args),
+        "get_card_history" => dispatch_history_get(state, args),
+        "list_card_logs" => dispatch_logs_list(state, args),
+
         // Question commands
         "create_question" => dispatch_questions_create(state, args),
         "list_questions" => dispatch_questions_list(state, args),
@@ -134,12 +192,31 @@ async fn dispatch_command(
         // Agent commands (async)
         "launch_agent" => dispatch_agent_launch(state, args).await,
         "send_agent_input" => dispatch_agent_send_input(state, args).await,
+        "write_agent_terminal" => dispatch_agent_write_terminal(state, args).await,
+        "resize_agent_terminal" => dispatch_agent_resize_terminal(state, args),
         "stop_agent" => dispatch_agent_stop(state, args).await,
         "resume_agent" => dispatch_agent_resume(state, args).await,
         "stop_all_agents" => dispatch_agent_stop_all(state, args).await,
         "list_workspaces" => dispatch_agent_list_workspaces(state, args),
         "get_workspace" => dispatch_agent_get_workspace(state, args),
         "list_running_workspaces" => dispatch_agent_list_running(state, args),
+        "archive_card_workspaces" => dispatch_agent_archive_card_workspaces(state, args),
+        "set_launch_priority" => dispatch_agent_set_launch_priority(state, args),
+
+        // IPC commands
+        "start_ipc_server" => dispatch_ipc_start(state, args).await,
+        "stop_ipc_server" => dispatch_ipc_stop(state, args).await,
+
+        // Review commands
+        "get_changed_files" => dispatch_review_get_changed_files(state, args),
+        "get_file_diff" => dispatch_review_get_file_diff(state, args),
+        "send_back_card" => dispatch_review_send_back(state, args, actor),
+        "approve_card" => dispatch_review_approve(state, args, actor),
+        "create_pr" => dispatch_review_create_pr(state, args),
+        "get_review_count" => dispatch_review_get_count(state, args),
+
+        // Search commands
+        "search" => dispatch_search(state, args),
 
         _ => Err(AppError::NotFound(format!("Unknown command: {command}"))),
     }
@@ -168,6 +245,106 @@ fn dispatch_config_resolve(state: &AppState, args: &Value) -> Result<Value, AppE
     Ok(serde_json::to_value(result).unwrap())
 }
 
+fn dispatch_config_update_global(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let update: GlobalConfigUpdate = extract_arg(args, "update")?;
+    let result = update_global_config_inner(&state.config, update)?;
+    state.event_bus.emit_maestro(MaestroEvent::ConfigChanged);
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_config_create_agent_profile(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let profile: AgentProfileInput = extract_arg(args, "profile")?;
+    let result = create_agent_profile_inner(&state.config, profile)?;
+    state.event_bus.emit_maestro(MaestroEvent::ConfigChanged);
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_config_update_agent_profile(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let name: String = extract_arg(args, "name")?;
+    let profile: AgentProfileInput = extract_arg(args, "profile")?;
+    let result = update_agent_profile_inner(&state.config, &name, profile)?;
+    state.event_bus.emit_maestro(MaestroEvent::ConfigChanged);
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_config_delete_agent_profile(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let name: String = extract_arg(args, "name")?;
+    let result = delete_agent_profile_inner(&state.config, &name)?;
+    state.event_bus.emit_maestro(MaestroEvent::ConfigChanged);
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_config_get_status_group_defaults(state: &AppState, _args: &Value) -> Result<Value, AppError> {
+    let result = get_status_group_defaults_inner(&state.config)?;
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_config_update_status_group_defaults(
+    state: &AppState,
+    args: &Value,
+) -> Result<Value, AppError> {
+    let status_group: String = extract_arg(args, "status_group")?;
+    let group_config: StatusGroupConfigInput = extract_arg(args, "group_config")?;
+    let result = update_status_group_defaults_inner(&state.config, &status_group, group_config)?;
+    state.event_bus.emit_maestro(MaestroEvent::ConfigChanged);
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_config_get_http_server(state: &AppState, _args: &Value) -> Result<Value, AppError> {
+    let result = get_http_server_config_inner(&state.config)?;
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_config_update_http_server(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let update: HttpServerConfigUpdate = extract_arg(args, "update")?;
+    let result = update_http_server_config_inner(&state.config, update)?;
+    state.event_bus.emit_maestro(MaestroEvent::ConfigChanged);
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_config_regenerate_auth_token(state: &AppState, _args: &Value) -> Result<Value, AppError> {
+    let result = regenerate_auth_token_inner(&state.config)?;
+    state.event_bus.emit_maestro(MaestroEvent::ConfigChanged);
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_config_get_local_ip(_state: &AppState, _args: &Value) -> Result<Value, AppError> {
+    let result = get_local_ip_inner()?;
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+// ============================================================================
+// API token dispatchers
+// ============================================================================
+
+fn dispatch_tokens_create(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let name: String = extract_arg(args, "name")?;
+    let scopes: Vec<TokenScope> = extract_arg(args, "scopes")?;
+    let projects: Option<Vec<String>> = extract_optional_arg(args, "projects")?;
+    let expires_in_days: Option<u32> = extract_optional_arg(args, "expires_in_days")?;
+    let result = create_api_token_inner(
+        &state.config,
+        &name,
+        scopes,
+        projects.unwrap_or_default(),
+        expires_in_days,
+    )?;
+    state.event_bus.emit_maestro(MaestroEvent::ConfigChanged);
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_tokens_list(state: &AppState, _args: &Value) -> Result<Value, AppError> {
+    let result = list_api_tokens_inner(&state.config)?;
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_tokens_revoke(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let id: String = extract_arg(args, "id")?;
+    revoke_api_token_inner(&state.config, &id)?;
+    state.event_bus.emit_maestro(MaestroEvent::ConfigChanged);
+    Ok(serde_json::json!(null))
+}
+
 // ============================================================================
 // Project dispatchers
 // ============================================================================
@@ -245,6 +422,17 @@ fn dispatch_statuses_update(state: &AppState, args: &Value) -> Result<Value, App
     Ok(serde_json::to_value(result).unwrap())
 }
 
+fn dispatch_statuses_set_auto_run(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    let id: String = extract_arg(args, "id")?;
+    let auto_run: Option<AutoRunPolicy> = extract_optional_arg(args, "auto_run")?;
+    let result = set_status_auto_run_inner(&state.config, &project_id, &id, auto_run)?;
+    state.event_bus.emit_maestro(MaestroEvent::StatusesChanged {
+        project_id: project_id.clone(),
+    });
+    Ok(serde_json::to_value(result).unwrap())
+}
+
 fn dispatch_statuses_delete(state: &AppState, args: &Value) -> Result<Value, AppError> {
     let project_id: String = extract_arg(args, "project_id")?;
     let id: String = extract_arg(args, "id")?;
@@ -270,22 +458,16 @@ fn dispatch_statuses_reorder(state: &AppState, args: &Value) -> Result<Value, Ap
 // Card dispatchers
 // ============================================================================
 
-fn dispatch_cards_create(state: &AppState, args: &Value) -> Result<Value, AppError> {
+fn dispatch_cards_create(state: &AppState, args: &Value, actor: &Actor) -> Result<Value, AppError> {
     let project_id: String = extract_arg(args, "project_id")?;
-    let title: String = extract_arg(args, "title")?;
-    let description: Option<String> = extract_optional_arg(args, "description")?;
-    let labels: Option<Vec<String>> = extract_optional_arg(args, "labels")?;
-    let parent_id: Option<String> = extract_optional_arg(args, "parent_id")?;
-    let status_id: Option<String> = extract_optional_arg(args, "status_id")?;
-    let result = create_card_inner(
-        &state.config,
-        &project_id,
-        &title,
-        description,
-        labels,
-        parent_id,
-        status_id,
-    )?;
+    let card = CardInput {
+        title: extract_arg(args, "title")?,
+        description: extract_optional_arg(args, "description")?,
+        labels: extract_optional_arg(args, "labels")?,
+        parent_id: extract_optional_arg(args, "parent_id")?,
+        status_id: extract_optional_arg(args, "status_id")?,
+    };
+    let result = create_card_inner(&state.config, &project_id, card, actor)?;
     state.event_bus.emit_maestro(MaestroEvent::CardsChanged {
         project_id: project_id.clone(),
     });
@@ -299,23 +481,23 @@ fn dispatch_cards_get(state: &AppState, args: &Value) -> Result<Value, AppError>
     Ok(serde_json::to_value(result).unwrap())
 }
 
-fn dispatch_cards_update(state: &AppState, args: &Value) -> Result<Value, AppError> {
+fn dispatch_cards_update(state: &AppState, args: &Value, actor: &Actor) -> Result<Value, AppError> {
     let project_id: String = extract_arg(args, "project_id")?;
     let id: String = extract_arg(args, "id")?;
     let title: Option<String> = extract_optional_arg(args, "title")?;
     let description: Option<String> = extract_optional_arg(args, "description")?;
     let labels: Option<Vec<String>> = extract_optional_arg(args, "labels")?;
-    let result = update_card_inner(&state.config, &project_id, &id, title, description, labels)?;
+    let result = update_card_inner(&state.config, &project_id, &id, title, description, labels, actor)?;
     state.event_bus.emit_maestro(MaestroEvent::CardsChanged {
         project_id: project_id.clone(),
     });
     Ok(serde_json::to_value(result).unwrap())
 }
 
-fn dispatch_cards_delete(state: &AppState, args: &Value) -> Result<Value, AppError> {
+fn dispatch_cards_delete(state: &AppState, args: &Value, actor: &Actor) -> Result<Value, AppError> {
     let project_id: String = extract_arg(args, "project_id")?;
     let id: String = extract_arg(args, "id")?;
-    delete_card_inner(&state.config, &project_id, &id)?;
+    delete_card_inner(&state.config, &project_id, &id, actor)?;
     state.event_bus.emit_maestro(MaestroEvent::CardsChanged {
         project_id: project_id.clone(),
     });
@@ -335,16 +517,27 @@ fn dispatch_cards_list_sub(state: &AppState, args: &Value) -> Result<Value, AppE
     Ok(serde_json::to_value(result).unwrap())
 }
 
-fn dispatch_cards_move(state: &AppState, args: &Value) -> Result<Value, AppError> {
+fn dispatch_cards_move(state: &AppState, args: &Value, actor: &Actor) -> Result<Value, AppError> {
     let project_id: String = extract_arg(args, "project_id")?;
     let id: String = extract_arg(args, "id")?;
     let target_status_id: String = extract_arg(args, "target_status_id")?;
     let target_sort_order: i32 = extract_arg(args, "target_sort_order")?;
+    let previous_status_id = get_card_inner(&state.config, &project_id, &id)?.status_id;
     let result =
-        move_card_inner(&state.config, &project_id, &id, &target_status_id, target_sort_order)?;
+        move_card_inner(&state.config, &project_id, &id, &target_status_id, target_sort_order, actor)?;
     state.event_bus.emit_maestro(MaestroEvent::CardsChanged {
         project_id: project_id.clone(),
     });
+    if result.status_id != previous_status_id {
+        spawn_auto_run(
+            None,
+            Some(Arc::clone(&state.event_bus)),
+            Arc::clone(&state.config),
+            Arc::clone(&state.registry),
+            project_id,
+            id,
+        );
+    }
     Ok(serde_json::to_value(result).unwrap())
 }
 
@@ -359,6 +552,61 @@ fn dispatch_cards_reorder(state: &AppState, args: &Value) -> Result<Value, AppEr
     Ok(serde_json::to_value(result).unwrap())
 }
 
+// ============================================================================
+// Card dependency dispatchers
+// ============================================================================
+
+fn dispatch_dependencies_add(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    let card_id: String = extract_arg(args, "card_id")?;
+    let depends_on_id: String = extract_arg(args, "depends_on_id")?;
+    let result = add_card_dependency_inner(&state.config, &project_id, &card_id, &depends_on_id)?;
+    state.event_bus.emit_maestro(MaestroEvent::CardsChanged {
+        project_id: project_id.clone(),
+    });
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_dependencies_remove(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    let card_id: String = extract_arg(args, "card_id")?;
+    let depends_on_id: String = extract_arg(args, "depends_on_id")?;
+    let result = remove_card_dependency_inner(&state.config, &project_id, &card_id, &depends_on_id)?;
+    state.event_bus.emit_maestro(MaestroEvent::CardsChanged {
+        project_id: project_id.clone(),
+    });
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_dependencies_list(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    let card_id: String = extract_arg(args, "card_id")?;
+    let result = list_card_dependencies_inner(&state.config, &project_id, &card_id)?;
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_history_get(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    let card_id: String = extract_arg(args, "card_id")?;
+    let result = get_card_history_inner(&state.config, &project_id, &card_id)?;
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_logs_list(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    let card_id: String = extract_arg(args, "card_id")?;
+    let min_level: Option<LogLevel> = extract_optional_arg(args, "min_level")?;
+    let workspace_id: Option<String> = extract_optional_arg(args, "workspace_id")?;
+    let result = list_card_logs_inner(
+        &state.config,
+        &project_id,
+        &card_id,
+        min_level,
+        workspace_id.as_deref(),
+    )?;
+    Ok(serde_json::to_value(result).unwrap())
+}
+
 // ============================================================================
 // Question dispatchers
 // ============================================================================
@@ -619,6 +867,7 @@ async fn dispatch_agent_launch(state: &AppState, args: &Value) -> Result<Value,
     let branch_name: Option<String> = extract_optional_arg(args, "branch_name")?;
     let repo_path: Option<String> = extract_optional_arg(args, "repo_path")?;
 
+    state.ensure_ipc(&project_id).await;
     let result = launch_agent_inner(
         None,
         Some(state.event_bus.clone()),
@@ -645,6 +894,21 @@ async fn dispatch_agent_send_input(state: &AppState, args: &Value) -> Result<Val
     Ok(serde_json::json!(null))
 }
 
+async fn dispatch_agent_write_terminal(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let workspace_id: String = extract_arg(args, "workspace_id")?;
+    let data: Vec<u8> = extract_arg(args, "data")?;
+    write_agent_terminal_inner(&state.registry, &workspace_id, data).await?;
+    Ok(serde_json::json!(null))
+}
+
+fn dispatch_agent_resize_terminal(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let workspace_id: String = extract_arg(args, "workspace_id")?;
+    let cols: u16 = extract_arg(args, "cols")?;
+    let rows: u16 = extract_arg(args, "rows")?;
+    resize_agent_terminal_inner(&state.registry, &workspace_id, cols, rows)?;
+    Ok(serde_json::json!(null))
+}
+
 async fn dispatch_agent_stop(state: &AppState, args: &Value) -> Result<Value, AppError> {
     let project_id: String = extract_arg(args, "project_id")?;
     let workspace_id: String = extract_arg(args, "workspace_id")?;
@@ -660,6 +924,7 @@ async fn dispatch_agent_resume(state: &AppState, args: &Value) -> Result<Value,
     let workspace_id: String = extract_arg(args, "workspace_id")?;
     let card_id: String = extract_arg(args, "card_id")?;
 
+    state.ensure_ipc(&project_id).await;
     let result = resume_agent_inner(
         None,
         Some(state.event_bus.clone()),
@@ -699,3 +964,161 @@ fn dispatch_agent_list_running(state: &AppState, _args: &Value) -> Result<Value,
     let result = list_running_workspaces_inner(&state.config)?;
     Ok(serde_json::to_value(result).unwrap())
 }
+
+fn dispatch_agent_archive_card_workspaces(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    let card_id: String = extract_arg(args, "card_id")?;
+    archive_card_workspaces_inner(&state.config, &project_id, &card_id)?;
+    state.event_bus.emit_maestro(MaestroEvent::WorkspacesChanged {
+        project_id: project_id.clone(),
+    });
+    Ok(serde_json::json!(null))
+}
+
+fn dispatch_agent_set_launch_priority(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    let workspace_id: String = extract_arg(args, "workspace_id")?;
+    let priority: i64 = extract_arg(args, "priority")?;
+    set_launch_priority_inner(&state.config, &project_id, &workspace_id, priority)?;
+    state.event_bus.emit_maestro(MaestroEvent::WorkspacesChanged {
+        project_id: project_id.clone(),
+    });
+    Ok(serde_json::json!(null))
+}
+
+// ============================================================================
+// IPC dispatchers (async)
+// ============================================================================
+
+async fn dispatch_ipc_start(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    let socket_path = state
+        .ipc
+        .start(
+            None,
+            Some(Arc::clone(&state.event_bus)),
+            Arc::clone(&state.config),
+            Arc::clone(&state.registry),
+            project_id,
+        )
+        .await?;
+    Ok(serde_json::to_value(socket_path.to_string_lossy()).unwrap())
+}
+
+async fn dispatch_ipc_stop(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    state.ipc.stop_project(&project_id).await;
+    Ok(serde_json::json!(null))
+}
+
+// ============================================================================
+// Review dispatchers
+// ============================================================================
+
+fn dispatch_review_get_changed_files(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    let card_id: String = extract_arg(args, "card_id")?;
+    let result = get_changed_files_inner(&state.config, &project_id, &card_id)?;
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_review_get_file_diff(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    let card_id: String = extract_arg(args, "card_id")?;
+    let file_path: String = extract_arg(args, "file_path")?;
+    let result = get_file_diff_inner(&state.config, &project_id, &card_id, &file_path)?;
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_review_send_back(state: &AppState, args: &Value, actor: &Actor) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    let card_id: String = extract_arg(args, "card_id")?;
+    let feedback: String = extract_arg(args, "feedback")?;
+    let in_progress_status_id: String = extract_arg(args, "in_progress_status_id")?;
+    send_back_card_inner(&state.config, &project_id, &card_id, &feedback, &in_progress_status_id, actor)?;
+    state.event_bus.emit_maestro(MaestroEvent::CardsChanged {
+        project_id: project_id.clone(),
+    });
+    spawn_auto_run(
+        None,
+        Some(Arc::clone(&state.event_bus)),
+        Arc::clone(&state.config),
+        Arc::clone(&state.registry),
+        project_id,
+        card_id,
+    );
+    Ok(serde_json::json!(null))
+}
+
+fn dispatch_review_approve(state: &AppState, args: &Value, actor: &Actor) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    let card_id: String = extract_arg(args, "card_id")?;
+    let completed_status_id: String = extract_arg(args, "completed_status_id")?;
+    approve_card_inner(&state.config, &project_id, &card_id, &completed_status_id, actor)?;
+    state.event_bus.emit_maestro(MaestroEvent::CardsChanged {
+        project_id: project_id.clone(),
+    });
+    spawn_auto_run(
+        None,
+        Some(Arc::clone(&state.event_bus)),
+        Arc::clone(&state.config),
+        Arc::clone(&state.registry),
+        project_id,
+        card_id,
+    );
+    Ok(serde_json::json!(null))
+}
+
+fn dispatch_review_create_pr(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    let card_id: String = extract_arg(args, "card_id")?;
+    let title: String = extract_arg(args, "title")?;
+    let body: String = extract_arg(args, "body")?;
+    let result = create_pr_inner(&state.config, &project_id, &card_id, &title, &body)?;
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+fn dispatch_review_get_count(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    let card_id: String = extract_arg(args, "card_id")?;
+    let result = get_review_count_inner(&state.config, &project_id, &card_id)?;
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+// ============================================================================
+// Search dispatchers
+// ============================================================================
+
+fn dispatch_search(state: &AppState, args: &Value) -> Result<Value, AppError> {
+    let project_id: String = extract_arg(args, "project_id")?;
+    let query: String = extract_arg(args, "query")?;
+    let limit: Option<u32> = extract_optional_arg(args, "limit")?;
+    let result = search_inner(&state.config, &project_id, &query, limit)?;
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::COMMAND_NAMES;
+    use crate::fixtures::{app_state, block_on};
+
+    #[test]
+    fn test_every_command_is_dispatched() {
+        let (state, _dir) = app_state();
+        for command in COMMAND_NAMES {
+            let result = block_on(dispatch_command(&state, command, &serde_json::json!({}), &Actor::User));
+            assert!(
+                !matches!(result, Err(AppError::NotFound(_))),
+                "{command} is not exposed over HTTP"
+            );
+        }
+    }
+
+    #[test]
+    fn test_unknown_command_is_not_found() {
+        let (state, _dir) = app_state();
+        let result = block_on(dispatch_command(&state, "no_such_command", &serde_json::json!({}), &Actor::User));
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
+}
@@ -1,19 +1,23 @@
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use axum::middleware;
 use axum::routing::get;
 use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
 use tokio::net::TcpListener;
 use tower_http::cors::{Any, CorsLayer};
 
-use crate::commands::config::ConfigState;
+use crate::commands::config::{get_local_ip_address, ConfigState};
 use crate::config::global::HttpServerConfig;
 use crate::executor::{AgentRegistry, EventBus};
 use crate::ipc::server::IpcServer;
 
 use super::auth::auth_middleware;
 use super::routes::api_routes;
+use super::static_files::static_routes;
+use super::tls;
 use super::websocket::{ws_agent_handler, ws_events_handler};
 
 #[derive(Clone)]
@@ -28,25 +32,50 @@ pub struct AppState {
 
 impl AppState {
     pub fn new(
-        config: ConfigState,
+        config: Arc<ConfigState>,
         registry: Arc<AgentRegistry>,
         ipc: Arc<IpcServer>,
-        event_bus: EventBus,
+        event_bus: Arc<EventBus>,
         http_config: HttpServerConfig,
     ) -> Self {
-        let server_url = format!("http://{}:{}", http_config.bind_address, http_config.port);
+        let server_url = format!(
+            "{}://{}:{}",
+            http_config.scheme(),
+            http_config.bind_address,
+            http_config.port
+        );
         Self {
-            config: Arc::new(config),
+            config,
             registry,
             ipc,
-            event_bus: Arc::new(event_bus),
+            event_bus,
             server_url,
             http_config: Arc::new(http_config),
         }
     }
+
+    /// Starts the project's IPC socket before an agent launches in it, so
+    /// the agent is handed a socket to talk back on.
+    pub async fn ensure_ipc(&self, project_id: &str) {
+        if let Err(e) = self
+            .ipc
+            .ensure_started(
+                None,
+                Some(Arc::clone(&self.event_bus)),
+                Arc::clone(&self.config),
+                Arc::clone(&self.registry),
+                project_id.to_string(),
+            )
+            .await
+        {
+            eprintln!("[ipc] Failed to start socket for project {project_id}: {e}");
+        }
+    }
 }
 
-pub async fn start_http_server(state: AppState) -> Result<(), String> {
+/// Serves the API and websockets, plus the built frontend when `static_dir`
+/// is given.
+pub async fn start_http_server(state: AppState, static_dir: Option<PathBuf>) -> Result<(), String> {
     let http_config = state.http_config.clone();
     let bind_address = http_config.bind_address.clone();
     let port = http_config.port;
@@ -60,16 +89,24 @@ pub async fn start_http_server(state: AppState) -> Result<(), String> {
         CorsLayer::permissive()
     };
 
-    let app = Router::new()
+    let tls = if http_config.tls.enabled {
+        let base_path = state.config.with_config(|c| Ok(c.resolve_base_path()))?;
+        let hosts = tls::self_signed_hosts(&bind_address, get_local_ip_address());
+        Some(tls::load_or_create(&http_config.tls, &base_path, hosts)?)
+    } else {
+        None
+    };
+
+    let mut app = Router::new()
         .nest("/api", api_routes())
         .route("/ws/events", get(ws_events_handler))
         .route("/ws/agent/:workspace_id", get(ws_agent_handler))
-        .layer(middleware::from_fn(move |req, next| {
-            let config = http_config.clone();
-            auth_middleware(config, req, next)
-        }))
-        .layer(cors)
-        .with_state(state);
+        .layer(middleware::from_fn_with_state(state.clone(), auth_middleware));
+    // The frontend is public; it asks for the token before calling the API.
+    if let Some(dir) = static_dir {
+        app = app.merge(static_routes(dir));
+    }
+    let app = app.layer(cors).with_state(state);
 
     let addr: SocketAddr = format!("{}:{}", bind_address, port)
         .parse()
@@ -78,14 +115,33 @@ pub async fn start_http_server(state: AppState) -> Result<(), String> {
         .await
         .map_err(|e| format!("Failed to bind HTTP server: {e}"))?;
 
-    eprintln!("[http] Server listening on http://{addr}");
+    eprintln!("[http] Server listening on {}://{addr}", http_config.scheme());
 
     if bind_address == "0.0.0.0" {
         eprintln!("[http] ⚠️  WARNING: Server is accessible from other devices on your network.");
         eprintln!("[http] Make sure you trust your network. Auth token is required for all requests.");
+        if tls.is_none() {
+            eprintln!("[http] TLS is off, so the auth token is sent in plain text. Set [http_server.tls] enabled = true to serve HTTPS.");
+        }
     }
 
-    axum::serve(listener, app)
+    let Some(tls) = tls else {
+        return axum::serve(listener, app)
+            .await
+            .map_err(|e| format!("HTTP server error: {e}"));
+    };
+
+    eprintln!("[http] TLS certificate fingerprint (SHA-256): {}", tls::fingerprint(&tls.cert_pem)?);
+    // Only the ring provider is compiled in; installing it twice is harmless.
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let rustls_config = RustlsConfig::from_pem(tls.cert_pem, tls.key_pem)
+        .await
+        .map_err(|e| format!("Failed to load TLS certificate: {e}"))?;
+    let listener = listener
+        .into_std()
+        .map_err(|e| format!("Failed to bind HTTP server: {e}"))?;
+    axum_server::from_tcp_rustls(listener, rustls_config)
+        .serve(app.into_make_service())
         .await
         .map_err(|e| format!("HTTP server error: {e}"))
 }
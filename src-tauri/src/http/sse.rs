@@ -0,0 +1,222 @@
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::extract::{Extension, Query, State};
+use axum::http::HeaderMap;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures_util::stream::{self, Stream};
+use serde::Deserialize;
+use tokio::sync::broadcast;
+
+use crate::executor::buffer::SequencedEvent;
+use crate::executor::AgentRegistry;
+
+use super::auth::Grant;
+use super::server::AppState;
+use super::websocket::{EventFilter, WebSocketEvent};
+
+/// Query for `GET /api/events`. The filters are comma-separated lists with
+/// the same meaning as a `/ws/events` subscription.
+#[derive(Debug, Default, Deserialize)]
+pub struct EventStreamQuery {
+    projects: Option<String>,
+    event_types: Option<String>,
+    workspaces: Option<String>,
+    /// For clients that can't set the `Last-Event-ID` header.
+    last_event_id: Option<u64>,
+}
+
+impl EventStreamQuery {
+    fn filter(&self) -> EventFilter {
+        let list = |value: &Option<String>| -> Vec<String> {
+            value
+                .as_deref()
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        EventFilter {
+            projects: list(&self.projects),
+            event_types: list(&self.event_types),
+            workspaces: list(&self.workspaces),
+        }
+    }
+}
+
+struct EventStream {
+    pending: VecDeque<Event>,
+    rx: broadcast::Receiver<SequencedEvent>,
+    grant: Grant,
+    registry: Arc<AgentRegistry>,
+    filter: EventFilter,
+    last_seq: u64,
+}
+
+impl EventStream {
+    fn wants(&self, event: &SequencedEvent) -> bool {
+        self.grant.allows_event(&event.event, &self.registry)
+            && self.filter.matches(&event.event, &self.registry)
+    }
+
+    async fn next_event(&mut self) -> Option<Event> {
+        if let Some(event) = self.pending.pop_front() {
+            return Some(event);
+        }
+        loop {
+            match self.rx.recv().await {
+                Ok(event) => {
+                    if event.seq <= self.last_seq || !self.wants(&event) {
+                        continue;
+                    }
+                    self.last_seq = event.seq;
+                    return Some(sse_event(&event));
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    return Some(notice("events-lagged", serde_json::json!({ "missed": missed })));
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+fn sse_event(event: &SequencedEvent) -> Event {
+    let data = WebSocketEvent::from_maestro_event(event.seq, &event.event);
+    Event::default()
+        .id(event.seq.to_string())
+        .data(serde_json::to_string(&data).unwrap_or_default())
+}
+
+/// Notices carry no id, so they never move the client's resume cursor.
+fn notice(event_type: &str, data: serde_json::Value) -> Event {
+    let notice = WebSocketEvent::server_notice(event_type, None, data);
+    Event::default().data(serde_json::to_string(&notice).unwrap_or_default())
+}
+
+fn last_event_id(headers: &HeaderMap, query: &EventStreamQuery) -> Option<u64> {
+    headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse().ok())
+        .or(query.last_event_id)
+}
+
+/// Server-Sent Events version of `/ws/events`, for clients that can't hold
+/// a WebSocket open. Each event's id is its sequence number; reconnecting
+/// with `Last-Event-ID` replays what was missed while it's still buffered.
+pub async fn sse_events_handler(
+    State(state): State<AppState>,
+    Extension(grant): Extension<Grant>,
+    Query(query): Query<EventStreamQuery>,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let mut stream = EventStream {
+        pending: VecDeque::new(),
+        rx: state.event_bus.subscribe_maestro(),
+        grant,
+        registry: state.registry.clone(),
+        filter: query.filter(),
+        last_seq: 0,
+    };
+
+    if let Some(since) = last_event_id(&headers, &query) {
+        let (replay, rx) = state.event_bus.subscribe_since(since);
+        stream.rx = rx;
+        if replay.truncated {
+            // Some events are gone; the client should refetch what it shows.
+            stream
+                .pending
+                .push_back(notice("replay-truncated", serde_json::Value::Null));
+        }
+        for event in &replay.events {
+            stream.last_seq = event.seq;
+            if stream.wants(event) {
+                stream.pending.push_back(sse_event(event));
+            }
+        }
+    }
+
+    let events = stream::unfold(stream, |mut stream| async move {
+        let event = stream.next_event().await?;
+        Some((Ok(event), stream))
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::{EventBus, MaestroEvent};
+    use crate::fixtures::block_on;
+
+    fn cards_changed(project_id: &str) -> MaestroEvent {
+        MaestroEvent::CardsChanged {
+            project_id: project_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_query_filter_splits_lists() {
+        let query = EventStreamQuery {
+            projects: Some("p1, p2".to_string()),
+            event_types: Some("cards-changed,".to_string()),
+            ..Default::default()
+        };
+        let filter = query.filter();
+        assert_eq!(filter.projects, vec!["p1", "p2"]);
+        assert_eq!(filter.event_types, vec!["cards-changed"]);
+        assert!(filter.workspaces.is_empty());
+    }
+
+    #[test]
+    fn test_header_cursor_wins_over_query() {
+        let query = EventStreamQuery {
+            last_event_id: Some(3),
+            ..Default::default()
+        };
+        let mut headers = HeaderMap::new();
+        assert_eq!(last_event_id(&headers, &query), Some(3));
+        headers.insert("last-event-id", "7".parse().unwrap());
+        assert_eq!(last_event_id(&headers, &query), Some(7));
+    }
+
+    #[test]
+    fn test_stream_resumes_and_skips_replayed_events() {
+        let bus = EventBus::new();
+        bus.emit_maestro(cards_changed("p1"));
+        bus.emit_maestro(cards_changed("p2"));
+        bus.emit_maestro(cards_changed("p1"));
+
+        let (replay, rx) = bus.subscribe_since(1);
+        let mut stream = EventStream {
+            pending: VecDeque::new(),
+            rx,
+            grant: Grant::Full,
+            registry: Arc::new(AgentRegistry::new()),
+            filter: EventFilter {
+                projects: vec!["p1".to_string()],
+                ..Default::default()
+            },
+            last_seq: 0,
+        };
+        let replayed: Vec<u64> = replay
+            .events
+            .iter()
+            .filter(|e| stream.wants(e))
+            .map(|e| e.seq)
+            .collect();
+        assert_eq!(replayed, vec![3]);
+        stream.last_seq = 3;
+
+        bus.emit_maestro(cards_changed("p2"));
+        bus.emit_maestro(cards_changed("p1"));
+        block_on(async {
+            assert!(stream.next_event().await.is_some());
+            assert_eq!(stream.last_seq, 5);
+        });
+    }
+}
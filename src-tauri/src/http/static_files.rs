@@ -0,0 +1,110 @@
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use axum::{
+    extract::Request,
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+
+use super::server::AppState;
+
+const HTTP_URL_SCRIPT: &str =
+    "<script>window.__MAESTRO_HTTP_URL__ = window.location.origin;</script>";
+
+/// Serves the built frontend from `dir`. Unknown paths fall back to
+/// `index.html` so client-side routes survive a reload.
+pub fn static_routes(dir: PathBuf) -> Router<AppState> {
+    let dir = Arc::new(dir);
+    let index_dir = Arc::clone(&dir);
+    Router::new()
+        .route("/", get(move || serve_index(index_dir)))
+        .fallback(move |request: Request| serve_file(dir, request))
+}
+
+async fn serve_file(dir: Arc<PathBuf>, request: Request) -> Response {
+    let Some(path) = resolve_path(&dir, request.uri().path()) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => ([(CONTENT_TYPE, content_type(&path))], bytes).into_response(),
+        Err(_) => serve_index(dir).await,
+    }
+}
+
+async fn serve_index(dir: Arc<PathBuf>) -> Response {
+    match tokio::fs::read_to_string(dir.join("index.html")).await {
+        Ok(html) => (
+            [(CONTENT_TYPE, "text/html; charset=utf-8")],
+            inject_http_url(&html),
+        )
+            .into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Tells the frontend to talk to this server over HTTP instead of Tauri.
+fn inject_http_url(html: &str) -> String {
+    match html.find("</head>") {
+        Some(pos) => format!("{}{HTTP_URL_SCRIPT}{}", &html[..pos], &html[pos..]),
+        None => format!("{HTTP_URL_SCRIPT}{html}"),
+    }
+}
+
+fn resolve_path(dir: &Path, uri_path: &str) -> Option<PathBuf> {
+    let relative = Path::new(uri_path.trim_start_matches('/'));
+    if !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+        return None;
+    }
+    Some(dir.join(relative))
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).unwrap_or("") {
+        "html" => "text/html; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "json" | "map" => "application/json",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "txt" => "text/plain; charset=utf-8",
+        "wasm" => "application/wasm",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inject_http_url_before_head_close() {
+        let html = "<html><head><title>Maestro</title></head><body></body></html>";
+        let injected = inject_http_url(html);
+        assert!(injected.contains(&format!("{HTTP_URL_SCRIPT}</head>")));
+    }
+
+    #[test]
+    fn test_resolve_path_rejects_traversal() {
+        let dir = Path::new("/srv/maestro");
+        assert_eq!(
+            resolve_path(dir, "/_app/immutable/start.js"),
+            Some(dir.join("_app/immutable/start.js"))
+        );
+        assert_eq!(resolve_path(dir, "/../etc/passwd"), None);
+        assert_eq!(resolve_path(dir, "/_app/../../secret"), None);
+    }
+
+    #[test]
+    fn test_content_type_by_extension() {
+        assert_eq!(content_type(Path::new("app.js")), "text/javascript; charset=utf-8");
+        assert_eq!(content_type(Path::new("favicon.png")), "image/png");
+        assert_eq!(content_type(Path::new("blob")), "application/octet-stream");
+    }
+}
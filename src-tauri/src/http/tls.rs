@@ -0,0 +1,167 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::CertificateDer;
+use sha2::{Digest, Sha256};
+
+use crate::config::global::{expand_tilde, TlsConfig};
+
+pub struct TlsMaterial {
+    pub cert_pem: Vec<u8>,
+    pub key_pem: Vec<u8>,
+}
+
+/// Where the certificate and key live: the configured paths, or the
+/// self-signed pair under `<base_path>/tls/`.
+pub fn cert_paths(tls: &TlsConfig, base_path: &Path) -> Result<(PathBuf, PathBuf), String> {
+    match (&tls.cert_path, &tls.key_path) {
+        (Some(cert), Some(key)) => Ok((expand_tilde(cert), expand_tilde(key))),
+        (None, None) => {
+            let dir = base_path.join("tls");
+            Ok((dir.join("cert.pem"), dir.join("key.pem")))
+        }
+        _ => Err("tls.cert_path and tls.key_path must be set together".to_string()),
+    }
+}
+
+/// Loads the certificate and key, generating a self-signed pair for `hosts`
+/// on first use when none are configured.
+pub fn load_or_create(
+    tls: &TlsConfig,
+    base_path: &Path,
+    hosts: Vec<String>,
+) -> Result<TlsMaterial, String> {
+    let (cert_path, key_path) = cert_paths(tls, base_path)?;
+    let user_provided = tls.cert_path.is_some();
+    if !user_provided && (!cert_path.exists() || !key_path.exists()) {
+        write_self_signed(&cert_path, &key_path, hosts)?;
+    }
+
+    let cert_pem = std::fs::read(&cert_path)
+        .map_err(|e| format!("Failed to read TLS certificate {}: {e}", cert_path.display()))?;
+    let key_pem = std::fs::read(&key_path)
+        .map_err(|e| format!("Failed to read TLS key {}: {e}", key_path.display()))?;
+    Ok(TlsMaterial { cert_pem, key_pem })
+}
+
+fn write_self_signed(cert_path: &Path, key_path: &Path, hosts: Vec<String>) -> Result<(), String> {
+    let certified = rcgen::generate_simple_self_signed(hosts)
+        .map_err(|e| format!("Failed to generate TLS certificate: {e}"))?;
+
+    if let Some(dir) = cert_path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create TLS directory: {e}"))?;
+    }
+    std::fs::write(cert_path, certified.cert.pem())
+        .map_err(|e| format!("Failed to write TLS certificate: {e}"))?;
+    write_private_key(key_path, &certified.key_pair.serialize_pem())
+}
+
+/// Writes the key to a fresh file that is owner-only from the moment it
+/// exists. A leftover key is removed first, since `mode` only applies to
+/// newly created files.
+fn write_private_key(path: &Path, pem: &str) -> Result<(), String> {
+    if path.exists() {
+        std::fs::remove_file(path).map_err(|e| format!("Failed to replace TLS key: {e}"))?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(pem.as_bytes()))
+        .map_err(|e| format!("Failed to write TLS key: {e}"))
+}
+
+/// Names the self-signed certificate is issued for.
+pub fn self_signed_hosts(bind_address: &str, local_ip: Option<String>) -> Vec<String> {
+    let mut hosts = vec!["localhost".to_string(), "127.0.0.1".to_string()];
+    for host in [Some(bind_address.to_string()), local_ip].into_iter().flatten() {
+        if host != "0.0.0.0" && !hosts.contains(&host) {
+            hosts.push(host);
+        }
+    }
+    hosts
+}
+
+/// SHA-256 of the certificate, as `AB:CD:...`, for checking the
+/// certificate a browser is shown matches this server's.
+pub fn fingerprint(cert_pem: &[u8]) -> Result<String, String> {
+    let der = CertificateDer::from_pem_slice(cert_pem)
+        .map_err(|e| format!("Failed to parse TLS certificate: {e}"))?;
+    Ok(Sha256::digest(der.as_ref())
+        .iter()
+        .map(|b| format!("{b:02X}"))
+        .collect::<Vec<_>>()
+        .join(":"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_signed_cert_is_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let tls = TlsConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let hosts = self_signed_hosts("0.0.0.0", Some("192.168.1.20".to_string()));
+        assert_eq!(hosts, vec!["localhost", "127.0.0.1", "192.168.1.20"]);
+
+        let first = load_or_create(&tls, dir.path(), hosts.clone()).unwrap();
+        assert!(dir.path().join("tls/cert.pem").exists());
+        let second = load_or_create(&tls, dir.path(), hosts).unwrap();
+        assert_eq!(first.cert_pem, second.cert_pem);
+
+        let fp = fingerprint(&first.cert_pem).unwrap();
+        assert_eq!(fp.len(), 32 * 3 - 1);
+        assert_eq!(fp, fingerprint(&second.cert_pem).unwrap());
+    }
+
+    #[test]
+    fn test_user_cert_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let tls = TlsConfig {
+            enabled: true,
+            cert_path: Some(dir.path().join("missing.pem").display().to_string()),
+            key_path: None,
+        };
+        assert!(cert_paths(&tls, dir.path()).is_err());
+
+        let tls = TlsConfig {
+            key_path: Some(dir.path().join("missing.key").display().to_string()),
+            ..tls
+        };
+        // A configured certificate is never generated.
+        assert!(load_or_create(&tls, dir.path(), vec!["localhost".to_string()]).is_err());
+        assert!(!dir.path().join("tls").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_self_signed_key_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("tls/key.pem");
+        std::fs::create_dir_all(key_path.parent().unwrap()).unwrap();
+        std::fs::write(&key_path, "stale").unwrap();
+        std::fs::set_permissions(&key_path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let tls = TlsConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let material = load_or_create(&tls, dir.path(), vec!["localhost".to_string()]).unwrap();
+        assert_ne!(material.key_pem, b"stale");
+        let mode = std::fs::metadata(&key_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
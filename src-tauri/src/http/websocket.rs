@@ -1,61 +1,266 @@
 use std::sync::Arc;
 
 use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
-use axum::extract::{Path, State};
+use axum::extract::{Extension, Path, Query, State};
 use axum::response::IntoResponse;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast;
 
+use crate::commands::agent::{
+    resize_agent_terminal_inner, send_agent_input_inner, write_agent_terminal_inner,
+};
+use crate::executor::buffer::{Replay, SequencedEvent};
 use crate::executor::{AgentRegistry, MaestroEvent};
 
+use super::auth::{Access, Grant};
 use super::server::AppState;
 
 #[derive(Serialize)]
-struct WebSocketEvent {
+pub(crate) struct WebSocketEvent {
+    seq: u64,
     event_type: String,
     scope: Option<String>,
     data: serde_json::Value,
+    /// IDs of the `/ws/events` subscriptions the event matched.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    subscriptions: Vec<String>,
 }
 
 impl WebSocketEvent {
-    fn from_maestro_event(event: &MaestroEvent) -> Self {
+    pub(crate) fn from_maestro_event(seq: u64, event: &MaestroEvent) -> Self {
         WebSocketEvent {
+            seq,
             event_type: event.event_type().to_string(),
             scope: event.scope().map(|s| s.to_string()),
             data: serde_json::to_value(event).unwrap_or(serde_json::Value::Null),
+            subscriptions: Vec::new(),
         }
     }
+
+    /// A server-generated message that isn't a `MaestroEvent`, such as a
+    /// lag or replay-truncation notice.
+    pub(crate) fn server_notice(event_type: &str, scope: Option<&str>, data: serde_json::Value) -> Self {
+        WebSocketEvent {
+            seq: 0,
+            event_type: event_type.to_string(),
+            scope: scope.map(|s| s.to_string()),
+            data,
+            subscriptions: Vec::new(),
+        }
+    }
+
+    fn notice(event_type: &str, scope: Option<&str>, data: serde_json::Value) -> Message {
+        let notice = Self::server_notice(event_type, scope, data);
+        Message::Text(serde_json::to_string(&notice).unwrap_or_default())
+    }
+}
+
+/// Messages a client can send on `/ws/events`. Until the first `subscribe`
+/// every event is delivered; after that, only events matching at least one
+/// subscription. Subscribing with an existing `id` replaces it.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum EventSocketMessage {
+    Subscribe {
+        #[serde(default)]
+        id: String,
+        #[serde(flatten)]
+        filter: EventFilter,
+    },
+    Unsubscribe {
+        #[serde(default)]
+        id: String,
+    },
+}
+
+/// Which events a subscription wants. An empty list matches anything.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct EventFilter {
+    #[serde(default)]
+    pub projects: Vec<String>,
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    /// Only agent events from these workspaces.
+    #[serde(default)]
+    pub workspaces: Vec<String>,
+}
+
+impl EventFilter {
+    pub(crate) fn matches(&self, event: &MaestroEvent, registry: &AgentRegistry) -> bool {
+        let event_type = event.event_type();
+        if !self.event_types.is_empty() && !self.event_types.iter().any(|t| t == event_type) {
+            return false;
+        }
+
+        // Agent events are scoped to a workspace, everything else to a project.
+        let is_agent_event = event_type.starts_with("agent-");
+        let scope = event.scope();
+        if !self.workspaces.is_empty() {
+            let in_workspace = is_agent_event
+                && scope.is_some_and(|ws| self.workspaces.iter().any(|w| w == ws));
+            if !in_workspace {
+                return false;
+            }
+        }
+        if !self.projects.is_empty() {
+            match event.project_id(registry) {
+                Some(project) => return self.projects.contains(&project),
+                // `projects-changed` and `config-changed` concern everyone.
+                None => return !is_agent_event,
+            }
+        }
+        true
+    }
+}
+
+#[derive(Debug, Default)]
+struct Subscriptions {
+    filters: Vec<(String, EventFilter)>,
+    filtered: bool,
+}
+
+impl Subscriptions {
+    fn handle(&mut self, message: EventSocketMessage) {
+        self.filtered = true;
+        match message {
+            EventSocketMessage::Subscribe { id, filter } => {
+                self.filters.retain(|(existing, _)| *existing != id);
+                self.filters.push((id, filter));
+            }
+            EventSocketMessage::Unsubscribe { id } => {
+                self.filters.retain(|(existing, _)| *existing != id);
+            }
+        }
+    }
+
+    /// IDs of the subscriptions `event` matches, or `None` if it shouldn't
+    /// be sent at all.
+    fn matching(&self, event: &MaestroEvent, registry: &AgentRegistry) -> Option<Vec<String>> {
+        if !self.filtered {
+            return Some(Vec::new());
+        }
+        let ids: Vec<String> = self
+            .filters
+            .iter()
+            .filter(|(_, filter)| filter.matches(event, registry))
+            .map(|(id, _)| id.clone())
+            .collect();
+        (!ids.is_empty()).then_some(ids)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct AgentStreamQuery {
+    /// Replay buffered events with a sequence number greater than this.
+    since: Option<u64>,
+    /// Replay everything still buffered for the workspace.
+    #[serde(default)]
+    replay: bool,
+}
+
+/// Messages a client can send on `/ws/agent/:workspace_id`. Text that isn't
+/// one of these is sent to the agent as a line of input; binary frames are
+/// raw terminal input for PTY agents.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AgentSocketMessage {
+    Input { text: String },
+    Resize { cols: u16, rows: u16 },
+}
+
+async fn send_event(socket: &mut WebSocket, event: &SequencedEvent) -> Result<(), ()> {
+    send_subscribed_event(socket, event, Vec::new()).await
+}
+
+async fn send_subscribed_event(
+    socket: &mut WebSocket,
+    event: &SequencedEvent,
+    subscriptions: Vec<String>,
+) -> Result<(), ()> {
+    let mut ws_event = WebSocketEvent::from_maestro_event(event.seq, &event.event);
+    ws_event.subscriptions = subscriptions;
+    let json = match serde_json::to_string(&ws_event) {
+        Ok(j) => j,
+        Err(_) => return Ok(()),
+    };
+    socket.send(Message::Text(json)).await.map_err(|_| ())
+}
+
+/// Like `send_event`, but terminal output goes out as raw binary frames so
+/// it can be written straight into a terminal emulator.
+async fn send_agent_event(socket: &mut WebSocket, event: &SequencedEvent) -> Result<(), ()> {
+    match &event.event {
+        MaestroEvent::AgentTerminalOutput(output) => socket
+            .send(Message::Binary(output.data.clone().into_bytes()))
+            .await
+            .map_err(|_| ()),
+        _ => send_event(socket, event).await,
+    }
 }
 
 pub async fn ws_events_handler(
     ws: WebSocketUpgrade,
     State(state): State<AppState>,
+    Extension(grant): Extension<Grant>,
 ) -> impl IntoResponse {
     let rx = state.event_bus.subscribe_maestro();
-    ws.on_upgrade(move |socket| handle_ws_events(socket, rx))
+    let registry = state.registry.clone();
+    ws.on_upgrade(move |socket| handle_ws_events(socket, rx, grant, registry))
 }
 
-async fn handle_ws_events(mut socket: WebSocket, mut rx: broadcast::Receiver<MaestroEvent>) {
+async fn handle_ws_events(
+    mut socket: WebSocket,
+    mut rx: broadcast::Receiver<SequencedEvent>,
+    grant: Grant,
+    registry: Arc<AgentRegistry>,
+) {
+    let mut subscriptions = Subscriptions::default();
     loop {
         tokio::select! {
             event_result = rx.recv() => {
                 match event_result {
                     Ok(event) => {
-                        let ws_event = WebSocketEvent::from_maestro_event(&event);
-                        let json = match serde_json::to_string(&ws_event) {
-                            Ok(j) => j,
-                            Err(_) => continue,
+                        if !grant.allows_event(&event.event, &registry) {
+                            continue;
+                        }
+                        let Some(ids) = subscriptions.matching(&event.event, &registry) else {
+                            continue;
                         };
-                        if socket.send(Message::Text(json)).await.is_err() {
+                        if send_subscribed_event(&mut socket, &event, ids).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        // The client should refetch whatever it's showing.
+                        let notice = WebSocketEvent::notice(
+                            "events-lagged",
+                            None,
+                            serde_json::json!({ "missed": missed }),
+                        );
+                        if socket.send(notice).await.is_err() {
                             break;
                         }
                     }
-                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
                     Err(broadcast::error::RecvError::Closed) => break,
                 }
             }
             msg = socket.recv() => {
                 match msg {
+                    Some(Ok(Message::Text(text))) => {
+                        match serde_json::from_str::<EventSocketMessage>(&text) {
+                            Ok(message) => subscriptions.handle(message),
+                            Err(e) => {
+                                let notice = WebSocketEvent::notice(
+                                    "subscription-error",
+                                    None,
+                                    serde_json::json!({ "error": e.to_string() }),
+                                );
+                                if socket.send(notice).await.is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                    }
                     Some(Ok(Message::Close(_))) | None => break,
                     _ => {}
                 }
@@ -67,37 +272,57 @@ async fn handle_ws_events(mut socket: WebSocket, mut rx: broadcast::Receiver<Mae
 pub async fn ws_agent_handler(
     ws: WebSocketUpgrade,
     Path(workspace_id): Path<String>,
+    Query(query): Query<AgentStreamQuery>,
     State(state): State<AppState>,
+    Extension(grant): Extension<Grant>,
 ) -> impl IntoResponse {
-    let rx = state.event_bus.subscribe_maestro();
+    let (replay, rx) = if query.replay || query.since.is_some() {
+        state.event_bus.subscribe_workspace(&workspace_id, query.since)
+    } else {
+        (Replay::default(), state.event_bus.subscribe_maestro())
+    };
+    // Read-only tokens can watch an agent but not type into it.
     let registry = state.registry.clone();
-    ws.on_upgrade(move |socket| handle_ws_agent(socket, workspace_id, rx, registry))
+    let control = grant.allows(Access::Agent).then_some(registry);
+    ws.on_upgrade(move |socket| handle_ws_agent(socket, workspace_id, replay, rx, control))
 }
 
 async fn handle_ws_agent(
     mut socket: WebSocket,
     workspace_id: String,
-    mut rx: broadcast::Receiver<MaestroEvent>,
-    registry: Arc<AgentRegistry>,
+    replay: Replay,
+    mut rx: broadcast::Receiver<SequencedEvent>,
+    control: Option<Arc<AgentRegistry>>,
 ) {
+    if replay.truncated {
+        let notice =
+            WebSocketEvent::notice("replay-truncated", Some(&workspace_id), serde_json::Value::Null);
+        if socket.send(notice).await.is_err() {
+            return;
+        }
+    }
+    let mut last_seq = 0;
+    for event in &replay.events {
+        if send_agent_event(&mut socket, event).await.is_err() {
+            return;
+        }
+        last_seq = event.seq;
+    }
+
     loop {
         tokio::select! {
             event_result = rx.recv() => {
                 match event_result {
                     Ok(event) => {
-                        let event_type = event.event_type();
-                        let scope = event.scope();
-                        if scope != Some(workspace_id.as_str())
+                        let event_type = event.event.event_type();
+                        let scope = event.event.scope();
+                        if event.seq <= last_seq
+                            || scope != Some(workspace_id.as_str())
                             || !event_type.starts_with("agent-")
                         {
                             continue;
                         }
-                        let ws_event = WebSocketEvent::from_maestro_event(&event);
-                        let json = match serde_json::to_string(&ws_event) {
-                            Ok(j) => j,
-                            Err(_) => continue,
-                        };
-                        if socket.send(Message::Text(json)).await.is_err() {
+                        if send_agent_event(&mut socket, &event).await.is_err() {
                             break;
                         }
                     }
@@ -108,8 +333,24 @@ async fn handle_ws_agent(
             msg = socket.recv() => {
                 match msg {
                     Some(Ok(Message::Text(text))) => {
-                        if let Some(tx) = registry.get_stdin_tx(&workspace_id) {
-                            let _ = tx.send(text).await;
+                        let Some(registry) = &control else { continue };
+                        let result = match serde_json::from_str::<AgentSocketMessage>(&text) {
+                            Ok(AgentSocketMessage::Input { text }) => {
+                                send_agent_input_inner(registry, &workspace_id, &text).await
+                            }
+                            Ok(AgentSocketMessage::Resize { cols, rows }) => {
+                                resize_agent_terminal_inner(registry, &workspace_id, cols, rows)
+                            }
+                            Err(_) => send_agent_input_inner(registry, &workspace_id, &text).await,
+                        };
+                        if let Err(e) = result {
+                            eprintln!("[ws] {e}");
+                        }
+                    }
+                    Some(Ok(Message::Binary(data))) => {
+                        let Some(registry) = &control else { continue };
+                        if let Err(e) = write_agent_terminal_inner(registry, &workspace_id, data).await {
+                            eprintln!("[ws] {e}");
                         }
                     }
                     Some(Ok(Message::Close(_))) | None => break,
@@ -119,3 +360,158 @@ async fn handle_ws_agent(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::stream::AgentOutputEvent;
+    use crate::fixtures::block_on;
+
+    fn parse(message: serde_json::Value) -> EventSocketMessage {
+        serde_json::from_value(message).unwrap()
+    }
+
+    fn cards_changed(project_id: &str) -> MaestroEvent {
+        MaestroEvent::CardsChanged {
+            project_id: project_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_everything_is_sent_until_first_subscribe() {
+        let registry = AgentRegistry::new();
+        let mut subscriptions = Subscriptions::default();
+        assert_eq!(subscriptions.matching(&cards_changed("p1"), &registry), Some(vec![]));
+
+        subscriptions.handle(parse(serde_json::json!({
+            "type": "subscribe",
+            "id": "board",
+            "projects": ["p1"],
+            "event_types": ["cards-changed", "projects-changed"],
+        })));
+        assert_eq!(
+            subscriptions.matching(&cards_changed("p1"), &registry),
+            Some(vec!["board".to_string()])
+        );
+        assert_eq!(subscriptions.matching(&cards_changed("p2"), &registry), None);
+        assert!(subscriptions.matching(&MaestroEvent::ProjectsChanged, &registry).is_some());
+        assert_eq!(
+            subscriptions.matching(&MaestroEvent::StatusesChanged { project_id: "p1".to_string() }, &registry),
+            None
+        );
+
+        subscriptions.handle(parse(serde_json::json!({ "type": "unsubscribe", "id": "board" })));
+        assert_eq!(subscriptions.matching(&cards_changed("p1"), &registry), None);
+    }
+
+    #[test]
+    fn test_workspace_filter_only_matches_agent_events() {
+        let registry = AgentRegistry::new();
+        let filter = EventFilter {
+            workspaces: vec!["ws1".to_string()],
+            ..Default::default()
+        };
+        let output = |workspace_id: &str| {
+            MaestroEvent::AgentOutput(AgentOutputEvent {
+                workspace_id: workspace_id.to_string(),
+                stream: "stdout".to_string(),
+                line: "hello".to_string(),
+            })
+        };
+        assert!(filter.matches(&output("ws1"), &registry));
+        assert!(!filter.matches(&output("ws2"), &registry));
+        assert!(!filter.matches(&cards_changed("p1"), &registry));
+    }
+
+    #[test]
+    fn test_filters_route_agent_logs() {
+        let registry = AgentRegistry::new();
+        let log = |workspace_id: Option<&str>| {
+            MaestroEvent::AgentLog(crate::executor::AgentLogEvent {
+                project_id: "p1".to_string(),
+                card_id: "c1".to_string(),
+                workspace_id: workspace_id.map(str::to_string),
+                level: "info".to_string(),
+                message: "Tests pass".to_string(),
+                data: None,
+                timestamp: String::new(),
+            })
+        };
+        let project = |project_id: &str| EventFilter {
+            projects: vec![project_id.to_string()],
+            ..Default::default()
+        };
+        let workspace = EventFilter {
+            workspaces: vec!["ws1".to_string()],
+            ..Default::default()
+        };
+
+        assert!(project("p1").matches(&log(Some("ws1")), &registry));
+        assert!(project("p1").matches(&log(None), &registry));
+        assert!(!project("p2").matches(&log(Some("ws1")), &registry));
+        assert!(workspace.matches(&log(Some("ws1")), &registry));
+        assert!(!workspace.matches(&log(Some("ws2")), &registry));
+        assert!(!workspace.matches(&log(None), &registry));
+    }
+
+    #[test]
+    fn test_resubscribing_replaces_filter() {
+        let registry = AgentRegistry::new();
+        let mut subscriptions = Subscriptions::default();
+        subscriptions.handle(parse(serde_json::json!({ "type": "subscribe", "id": "a", "projects": ["p1"] })));
+        subscriptions.handle(parse(serde_json::json!({ "type": "subscribe", "id": "a", "projects": ["p2"] })));
+        assert_eq!(subscriptions.filters.len(), 1);
+        assert_eq!(subscriptions.matching(&cards_changed("p1"), &registry), None);
+        assert!(subscriptions.matching(&cards_changed("p2"), &registry).is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_project_filter_receives_exit_after_agent_leaves_registry() {
+        use crate::config::global::AgentLimits;
+        use crate::executor::lifecycle::{start_lifecycle_monitor_inner, MonitoredAgent};
+        use crate::executor::{AgentHandle, EventBus};
+
+        let dir = tempfile::tempdir().unwrap();
+        let bus = Arc::new(EventBus::new());
+        let registry = Arc::new(AgentRegistry::new());
+        let filter = EventFilter {
+            projects: vec!["p1".to_string()],
+            ..Default::default()
+        };
+
+        let exit = block_on(async {
+            let mut rx = bus.subscribe_maestro();
+            let child = tokio::process::Command::new("true").spawn().unwrap();
+            registry.insert(AgentHandle {
+                workspace_id: "ws1".to_string(),
+                project_id: "p1".to_string(),
+                stdin_tx: tokio::sync::mpsc::channel(1).0,
+                pid: child.id().unwrap(),
+                pty: None,
+            });
+            start_lifecycle_monitor_inner(
+                None,
+                Some(Arc::clone(&bus)),
+                Arc::clone(&registry),
+                child,
+                MonitoredAgent {
+                    workspace_id: "ws1".to_string(),
+                    project_id: "p1".to_string(),
+                    base_path: dir.path().to_path_buf(),
+                    limits: AgentLimits::default(),
+                },
+            );
+            loop {
+                match tokio::time::timeout(std::time::Duration::from_secs(10), rx.recv()).await {
+                    Ok(Ok(event)) if matches!(event.event, MaestroEvent::AgentExit(_)) => break event.event,
+                    Ok(Ok(_)) => continue,
+                    _ => panic!("agent did not exit"),
+                }
+            }
+        });
+
+        assert!(!registry.has("ws1"));
+        assert!(filter.matches(&exit, &registry));
+    }
+}
@@ -1,35 +1,61 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast;
 
+use crate::commands::agent::spawn_auto_run;
+use crate::commands::config::ConfigState;
+use crate::commands::history::{agent_actor, record_card_event, running_workspace_id, status_value, Actor};
+use crate::commands::logs::{insert_card_log, LogLevel};
 use crate::commands::projects::open_project_db;
-use crate::executor::{AgentLogEvent, EventBus, MaestroEvent};
+use crate::commands::questions::get_question_conn;
+use crate::commands::search::{index_artifact_content, search_conn};
+use crate::db::DbConnection;
+use crate::executor::buffer::SequencedEvent;
+use crate::executor::{AgentLogEvent, AgentRegistry, EventBus, MaestroEvent};
 use crate::fs::artifacts::{ensure_artifact_dir, name_to_slug, write_artifact_file};
 use crate::ipc::protocol::{IpcRequest, IpcResponse};
 
-pub fn handle_request(
-    app: &AppHandle,
+/// How long `maestro question --wait` blocks when no timeout is given.
+const DEFAULT_ANSWER_TIMEOUT_SECS: u64 = 3600;
+
+pub async fn handle_request(
+    app: Option<&AppHandle>,
     event_bus: Option<Arc<EventBus>>,
+    config: &Arc<ConfigState>,
+    registry: &Arc<AgentRegistry>,
     base_path: &PathBuf,
     project_id: &str,
     request: IpcRequest,
 ) -> IpcResponse {
     match request.command.as_str() {
-        "question" => handle_question(app, event_bus.as_ref(), base_path, project_id, &request),
+        "question" => {
+            let response = handle_question(app, event_bus.as_ref(), base_path, project_id, &request);
+            let question_id = response.data.as_ref().and_then(|d| d.get("id")).and_then(|v| v.as_str());
+            match (answer_timeout(&request), question_id) {
+                (Some(timeout), Some(question_id)) => {
+                    wait_for_answer(event_bus.as_ref(), base_path, project_id, &request.card_id, question_id, timeout)
+                        .await
+                }
+                _ => response,
+            }
+        }
         "resolve-question" => handle_resolve_question(app, event_bus.as_ref(), base_path, project_id, &request),
         "add-artifact" => handle_add_artifact(app, event_bus.as_ref(), base_path, project_id, &request),
-        "set-status" => handle_set_status(app, event_bus.as_ref(), base_path, project_id, &request),
+        "set-status" => handle_set_status(app, event_bus.as_ref(), config, registry, base_path, project_id, &request),
         "log" => handle_log(app, event_bus.as_ref(), base_path, project_id, &request),
         "get-card" => handle_get_card(base_path, project_id, &request),
         "get-artifacts" => handle_get_artifacts(base_path, project_id, &request),
         "get-parent" => handle_get_parent(base_path, project_id, &request),
+        "search" => handle_search(base_path, project_id, &request),
         other => IpcResponse::error(format!("Unknown command: {other}")),
     }
 }
 
 fn handle_question(
-    app: &AppHandle,
+    app: Option<&AppHandle>,
     event_bus: Option<&Arc<EventBus>>,
     base_path: &PathBuf,
     project_id: &str,
@@ -79,7 +105,9 @@ fn handle_question(
             "created_at": now,
         });
 
-        let _ = app.emit("question-created", &data);
+        if let Some(app) = app {
+            let _ = app.emit("question-created", &data);
+        }
         if let Some(bus) = event_bus {
             bus.emit_maestro(MaestroEvent::QuestionsChanged {
                 project_id: project_id.to_string(),
@@ -91,8 +119,150 @@ fn handle_question(
     .unwrap_or_else(IpcResponse::error)
 }
 
+/// Whether the request blocks until a question is answered, so the server
+/// should watch for the client going away while it's handled.
+pub fn holds_connection(request: &IpcRequest) -> bool {
+    request.command == "question" && answer_timeout(request).is_some()
+}
+
+fn answer_timeout(request: &IpcRequest) -> Option<Duration> {
+    let wait = request.payload.get("wait").and_then(|v| v.as_bool()).unwrap_or(false);
+    let secs = request
+        .payload
+        .get("timeout_secs")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_ANSWER_TIMEOUT_SECS);
+    wait.then(|| Duration::from_secs(secs))
+}
+
+/// Moves an agent workspace between `running` and `paused`, returning
+/// whether its status changed.
+fn set_workspace_paused(conn: &rusqlite::Connection, workspace_id: &str, paused: bool) -> Result<bool, String> {
+    let (from, to) = if paused { ("running", "paused") } else { ("paused", "running") };
+    let changed = conn
+        .execute(
+            "UPDATE agent_workspaces SET status = ?1 WHERE id = ?2 AND status = ?3",
+            rusqlite::params![to, workspace_id, from],
+        )
+        .map_err(|e| format!("Failed to update workspace status: {e}"))?;
+    Ok(changed > 0)
+}
+
+/// Pauses an agent workspace for as long as it's held. Dropping it resumes
+/// the workspace, including when the wait is cancelled because the client
+/// disconnected.
+struct PausedWorkspace<'a> {
+    db: &'a DbConnection,
+    event_bus: Option<&'a Arc<EventBus>>,
+    project_id: &'a str,
+    workspace_id: String,
+}
+
+impl<'a> PausedWorkspace<'a> {
+    fn pause(
+        db: &'a DbConnection,
+        event_bus: Option<&'a Arc<EventBus>>,
+        project_id: &'a str,
+        card_id: &str,
+    ) -> Option<Self> {
+        let workspace_id = db.with_conn(|conn| Ok(running_workspace_id(conn, card_id))).unwrap_or(None)?;
+        if !db.with_conn(|conn| set_workspace_paused(conn, &workspace_id, true)).unwrap_or(false) {
+            return None;
+        }
+        let paused = Self {
+            db,
+            event_bus,
+            project_id,
+            workspace_id,
+        };
+        paused.workspaces_changed();
+        Some(paused)
+    }
+
+    fn workspaces_changed(&self) {
+        if let Some(bus) = self.event_bus {
+            bus.emit_maestro(MaestroEvent::WorkspacesChanged {
+                project_id: self.project_id.to_string(),
+            });
+        }
+    }
+}
+
+impl Drop for PausedWorkspace<'_> {
+    // Only resume what is still paused; the agent may have been stopped or
+    // exited while waiting.
+    fn drop(&mut self) {
+        if self
+            .db
+            .with_conn(|conn| set_workspace_paused(conn, &self.workspace_id, false))
+            .unwrap_or(false)
+        {
+            self.workspaces_changed();
+        }
+    }
+}
+
+/// Resolves on the next change to the project's questions. Without a bus
+/// nothing is announced, so only the deadline ends the wait.
+async fn questions_changed(events: &mut Option<broadcast::Receiver<SequencedEvent>>, project_id: &str) {
+    let Some(events) = events else {
+        return std::future::pending().await;
+    };
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                if matches!(&event.event, MaestroEvent::QuestionsChanged { project_id: changed } if changed == project_id) {
+                    return;
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(_)) => return,
+            Err(broadcast::error::RecvError::Closed) => return std::future::pending().await,
+        }
+    }
+}
+
+/// Holds a `maestro question --wait` connection open until the question is
+/// resolved or `timeout` passes. The card's agent workspace shows as paused
+/// in the meantime.
+async fn wait_for_answer(
+    event_bus: Option<&Arc<EventBus>>,
+    base_path: &Path,
+    project_id: &str,
+    card_id: &str,
+    question_id: &str,
+    timeout: Duration,
+) -> IpcResponse {
+    let mut events = event_bus.map(|bus| bus.subscribe_maestro());
+    let db = match open_project_db(base_path, project_id) {
+        Ok(db) => db,
+        Err(e) => return IpcResponse::error(e),
+    };
+    let _paused = PausedWorkspace::pause(&db, event_bus, project_id, card_id);
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        match db.with_conn(|conn| get_question_conn(conn, question_id)) {
+            Ok(Some(question)) if question.resolved_at.is_some() => {
+                return IpcResponse::success(serde_json::json!(question));
+            }
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return IpcResponse::error(format!("Question {question_id} was deleted before it was answered"));
+            }
+            Err(e) => return IpcResponse::error(e),
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return IpcResponse::error(format!(
+                "Timed out after {}s waiting for an answer to question {question_id}",
+                timeout.as_secs()
+            ));
+        }
+        let _ = tokio::time::timeout_at(deadline, questions_changed(&mut events, project_id)).await;
+    }
+}
+
 fn handle_resolve_question(
-    app: &AppHandle,
+    app: Option<&AppHandle>,
     event_bus: Option<&Arc<EventBus>>,
     base_path: &PathBuf,
     project_id: &str,
@@ -135,7 +305,9 @@ fn handle_resolve_question(
             "resolved_at": now,
         });
 
-        let _ = app.emit("question-resolved", &data);
+        if let Some(app) = app {
+            let _ = app.emit("question-resolved", &data);
+        }
         if let Some(bus) = event_bus {
             bus.emit_maestro(MaestroEvent::QuestionsChanged {
                 project_id: project_id.to_string(),
@@ -148,7 +320,7 @@ fn handle_resolve_question(
 }
 
 fn handle_add_artifact(
-    app: &AppHandle,
+    app: Option<&AppHandle>,
     event_bus: Option<&Arc<EventBus>>,
     base_path: &PathBuf,
     project_id: &str,
@@ -226,6 +398,7 @@ fn handle_add_artifact(
             rusqlite::params![id, request.card_id, name, relative_path, now, now],
         )
         .map_err(|e| format!("Failed to create artifact: {e}"))?;
+        index_artifact_content(conn, &id, content)?;
 
         let data = serde_json::json!({
             "id": id,
@@ -236,7 +409,9 @@ fn handle_add_artifact(
             "created_at": now,
         });
 
-        let _ = app.emit("artifact-added", &data);
+        if let Some(app) = app {
+            let _ = app.emit("artifact-added", &data);
+        }
         if let Some(bus) = event_bus {
             bus.emit_maestro(MaestroEvent::ArtifactsChanged {
                 project_id: project_id.to_string(),
@@ -249,8 +424,10 @@ fn handle_add_artifact(
 }
 
 fn handle_set_status(
-    app: &AppHandle,
+    app: Option<&AppHandle>,
     event_bus: Option<&Arc<EventBus>>,
+    config: &Arc<ConfigState>,
+    registry: &Arc<AgentRegistry>,
     base_path: &PathBuf,
     project_id: &str,
     request: &IpcRequest,
@@ -287,6 +464,9 @@ fn handle_set_status(
             .map_err(|e| format!("Card not found: {e}"))?;
 
         let now = chrono::Utc::now().to_rfc3339();
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to begin transaction: {e}"))?;
 
         // Close gap in old status
         if parent_id.is_some() {
@@ -318,18 +498,44 @@ fn handle_set_status(
         )
         .map_err(|e| format!("Failed to update card status: {e}"))?;
 
+        if status_id != old_status_id {
+            record_card_event(
+                conn,
+                project_id,
+                &request.card_id,
+                &agent_actor(conn, &request.card_id),
+                "moved",
+                Some(status_value(conn, &old_status_id)),
+                Some(status_value(conn, &status_id)),
+            )?;
+        }
+        tx.commit()
+            .map_err(|e| format!("Failed to commit transaction: {e}"))?;
+
         let data = serde_json::json!({
             "card_id": request.card_id,
             "status_id": status_id,
             "status_name": status_name,
         });
 
-        let _ = app.emit("card-status-changed", &data);
+        if let Some(app) = app {
+            let _ = app.emit("card-status-changed", &data);
+        }
         if let Some(bus) = event_bus {
             bus.emit_maestro(MaestroEvent::CardsChanged {
                 project_id: project_id.to_string(),
             });
         }
+        if status_id != old_status_id {
+            spawn_auto_run(
+                app.cloned(),
+                event_bus.cloned(),
+                Arc::clone(config),
+                Arc::clone(registry),
+                project_id.to_string(),
+                request.card_id.clone(),
+            );
+        }
 
         Ok(IpcResponse::success(data))
     })
@@ -337,10 +543,10 @@ fn handle_set_status(
 }
 
 fn handle_log(
-    app: &AppHandle,
+    app: Option<&AppHandle>,
     event_bus: Option<&Arc<EventBus>>,
-    _base_path: &PathBuf,
-    _project_id: &str,
+    base_path: &std::path::Path,
+    project_id: &str,
     request: &IpcRequest,
 ) -> IpcResponse {
     let message = match request.payload.get("message").and_then(|v| v.as_str()) {
@@ -348,23 +554,83 @@ fn handle_log(
         None => return IpcResponse::error("Missing 'message' in payload"),
     };
 
-    let timestamp = chrono::Utc::now().to_rfc3339();
-    let data = serde_json::json!({
-        "card_id": request.card_id,
-        "message": message,
-        "timestamp": timestamp,
-    });
-
-    let _ = app.emit("agent-log", &data);
-    if let Some(bus) = event_bus {
-        bus.emit_maestro(MaestroEvent::AgentLog(AgentLogEvent {
-            card_id: request.card_id.clone(),
-            message: message.to_string(),
-            timestamp,
-        }));
+    let level = match request.payload.get("level").and_then(|v| v.as_str()) {
+        Some(level) => match LogLevel::parse(level) {
+            Ok(level) => level,
+            Err(e) => return IpcResponse::error(e),
+        },
+        None => LogLevel::Info,
+    };
+
+    let fields = request.payload.get("data").filter(|d| !d.is_null());
+    if fields.is_some_and(|d| !d.is_object()) {
+        return IpcResponse::error("'data' must be a JSON object");
     }
 
-    IpcResponse::success(data)
+    let db = match open_project_db(base_path, project_id) {
+        Ok(db) => db,
+        Err(e) => return IpcResponse::error(e),
+    };
+
+    db.with_conn(|conn| {
+        let card_exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM cards WHERE id = ?1 AND project_id = ?2",
+                rusqlite::params![request.card_id, project_id],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to verify card: {e}"))?;
+
+        if !card_exists {
+            return Ok(IpcResponse::error(format!(
+                "Card {} not found",
+                request.card_id
+            )));
+        }
+
+        let workspace_id = running_workspace_id(conn, &request.card_id);
+        let tx = conn
+            .unchecked_transaction()
+            .map_err(|e| format!("Failed to begin transaction: {e}"))?;
+        let log = insert_card_log(
+            conn,
+            &request.card_id,
+            workspace_id.as_deref(),
+            level,
+            message,
+            fields,
+        )?;
+        record_card_event(
+            conn,
+            project_id,
+            &request.card_id,
+            &Actor::Agent { workspace_id },
+            "log",
+            None,
+            Some(serde_json::json!({ "message": message, "level": level.as_str() })),
+        )?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit transaction: {e}"))?;
+
+        let event = AgentLogEvent {
+            project_id: project_id.to_string(),
+            card_id: log.card_id.clone(),
+            workspace_id: log.workspace_id.clone(),
+            level: level.as_str().to_string(),
+            message: log.message.clone(),
+            data: log.data.clone(),
+            timestamp: log.created_at.clone(),
+        };
+        if let Some(app) = app {
+            let _ = app.emit("agent-log", &event);
+        }
+        if let Some(bus) = event_bus {
+            bus.emit_maestro(MaestroEvent::AgentLog(event));
+        }
+
+        Ok(IpcResponse::success(serde_json::json!(log)))
+    })
+    .unwrap_or_else(IpcResponse::error)
 }
 
 fn handle_get_card(
@@ -449,6 +715,29 @@ fn handle_get_artifacts(
     .unwrap_or_else(IpcResponse::error)
 }
 
+fn handle_search(base_path: &std::path::Path, project_id: &str, request: &IpcRequest) -> IpcResponse {
+    let query = match request.payload.get("query").and_then(|v| v.as_str()) {
+        Some(q) => q,
+        None => return IpcResponse::error("Missing 'query' in payload"),
+    };
+    let limit = request
+        .payload
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map(|l| l.min(u32::MAX as u64) as u32);
+
+    let db = match open_project_db(base_path, project_id) {
+        Ok(db) => db,
+        Err(e) => return IpcResponse::error(e),
+    };
+
+    db.with_conn(|conn| {
+        let results = search_conn(conn, project_id, query, limit)?;
+        Ok(IpcResponse::success(serde_json::json!(results)))
+    })
+    .unwrap_or_else(IpcResponse::error)
+}
+
 fn handle_get_parent(
     base_path: &PathBuf,
     project_id: &str,
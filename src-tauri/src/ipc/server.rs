@@ -6,8 +6,9 @@ use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::UnixListener;
 use tokio::sync::Mutex;
 
-use crate::executor::EventBus;
-use crate::ipc::handler::handle_request;
+use crate::commands::config::ConfigState;
+use crate::executor::{AgentRegistry, EventBus};
+use crate::ipc::handler::{handle_request, holds_connection};
 use crate::ipc::protocol::{IpcRequest, IpcResponse};
 
 pub struct IpcServer {
@@ -27,11 +28,13 @@ impl IpcServer {
 
     pub async fn start(
         &self,
-        app: AppHandle,
+        app: Option<AppHandle>,
         event_bus: Option<Arc<EventBus>>,
-        base_path: PathBuf,
+        config: Arc<ConfigState>,
+        registry: Arc<AgentRegistry>,
         project_id: String,
     ) -> Result<PathBuf, String> {
+        let base_path = config.with_config(|c| Ok(c.resolve_base_path()))?;
         let socket_path = Self::socket_path(&project_id);
 
         // Clean up any stale socket file
@@ -47,7 +50,7 @@ impl IpcServer {
 
         let socket_path_clone = socket_path.clone();
         tokio::spawn(async move {
-            accept_loop(listener, app, event_bus, base_path, project_id).await;
+            accept_loop(listener, app, event_bus, config, registry, base_path, project_id).await;
             // Clean up socket file when the loop exits
             let _ = std::fs::remove_file(&socket_path_clone);
         });
@@ -55,6 +58,23 @@ impl IpcServer {
         Ok(socket_path)
     }
 
+    /// Like `start`, but leaves a socket this server is already listening on
+    /// alone.
+    pub async fn ensure_started(
+        &self,
+        app: Option<AppHandle>,
+        event_bus: Option<Arc<EventBus>>,
+        config: Arc<ConfigState>,
+        registry: Arc<AgentRegistry>,
+        project_id: String,
+    ) -> Result<PathBuf, String> {
+        let socket_path = Self::socket_path(&project_id);
+        if socket_path.exists() && self.socket_paths.lock().await.contains(&socket_path) {
+            return Ok(socket_path);
+        }
+        self.start(app, event_bus, config, registry, project_id).await
+    }
+
     pub async fn stop_all(&self) {
         let paths = self.socket_paths.lock().await;
         for path in paths.iter() {
@@ -72,8 +92,10 @@ impl IpcServer {
 
 async fn accept_loop(
     listener: UnixListener,
-    app: AppHandle,
+    app: Option<AppHandle>,
     event_bus: Option<Arc<EventBus>>,
+    config: Arc<ConfigState>,
+    registry: Arc<AgentRegistry>,
     base_path: PathBuf,
     project_id: String,
 ) {
@@ -82,11 +104,13 @@ async fn accept_loop(
             Ok((stream, _addr)) => {
                 let app = app.clone();
                 let event_bus = event_bus.clone();
+                let config = Arc::clone(&config);
+                let registry = Arc::clone(&registry);
                 let base_path = base_path.clone();
                 let project_id = project_id.clone();
 
                 tokio::spawn(async move {
-                    handle_connection(stream, &app, event_bus, &base_path, &project_id).await;
+                    handle_connection(stream, app.as_ref(), event_bus, &config, &registry, &base_path, &project_id).await;
                 });
             }
             Err(e) => {
@@ -102,8 +126,10 @@ async fn accept_loop(
 
 async fn handle_connection(
     stream: tokio::net::UnixStream,
-    app: &AppHandle,
+    app: Option<&AppHandle>,
     event_bus: Option<Arc<EventBus>>,
+    config: &Arc<ConfigState>,
+    registry: &Arc<AgentRegistry>,
     base_path: &Path,
     project_id: &str,
 ) {
@@ -121,7 +147,21 @@ async fn handle_connection(
     }
 
     let response = match serde_json::from_str::<IpcRequest>(&line) {
-        Ok(request) => handle_request(app, event_bus, &base_path.to_path_buf(), project_id, request),
+        Ok(request) => {
+            let holds = holds_connection(&request);
+            let base_path = base_path.to_path_buf();
+            let handled = handle_request(app, event_bus, config, registry, &base_path, project_id, request);
+            if holds {
+                // A waiting client keeps its write half open; EOF means it
+                // gave up, so the wait is dropped with it.
+                tokio::select! {
+                    response = handled => response,
+                    _ = client_gone(&mut buf_reader) => return,
+                }
+            } else {
+                handled.await
+            }
+        }
         Err(e) => IpcResponse::error(format!("Invalid request JSON: {e}")),
     };
 
@@ -134,6 +174,16 @@ async fn handle_connection(
     }
 }
 
+async fn client_gone<R: tokio::io::AsyncBufRead + Unpin>(reader: &mut R) {
+    let mut rest = String::new();
+    loop {
+        match reader.read_line(&mut rest).await {
+            Ok(0) | Err(_) => return,
+            Ok(_) => rest.clear(),
+        }
+    }
+}
+
 fn is_fatal_accept_error(e: &std::io::Error) -> bool {
     matches!(
         e.kind(),
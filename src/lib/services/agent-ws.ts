@@ -10,6 +10,7 @@ export interface AgentExitEvent {
 	workspace_id: string;
 	exit_code: number | null;
 	status: string;
+	reason: string | null;
 }
 
 export interface AgentStreamConnection {
@@ -132,6 +133,7 @@ function connectHttpAgentStream(
 				onExit({
 					workspace_id: workspaceId,
 					exit_code: null,
+					reason: null,
 					status: 'failed'
 				});
 			}
@@ -23,6 +23,18 @@ export async function sendAgentInput(workspaceId: string, text: string): Promise
 	return tauriInvoke<void>('send_agent_input', { workspaceId, text });
 }
 
+export async function writeAgentTerminal(workspaceId: string, data: number[]): Promise<void> {
+	return tauriInvoke<void>('write_agent_terminal', { workspaceId, data });
+}
+
+export async function resizeAgentTerminal(
+	workspaceId: string,
+	cols: number,
+	rows: number
+): Promise<void> {
+	return tauriInvoke<void>('resize_agent_terminal', { workspaceId, cols, rows });
+}
+
 export async function stopAgent(projectId: string, workspaceId: string): Promise<AgentWorkspace> {
 	return tauriInvoke<AgentWorkspace>('stop_agent', { projectId, workspaceId });
 }
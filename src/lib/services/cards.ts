@@ -1,4 +1,4 @@
-import type { CardWithStatus } from '$lib/types/index.js';
+import type { CardWithStatus, CardDependencies, CardEvent, CardLog, LogLevel } from '$lib/types/index.js';
 import { tauriInvoke } from './db.js';
 
 export async function createCard(
@@ -69,3 +69,39 @@ export async function reorderCards(
 ): Promise<CardWithStatus[]> {
 	return tauriInvoke<CardWithStatus[]>('reorder_cards', { projectId, statusId, cardIds });
 }
+
+export async function listCardDependencies(
+	projectId: string,
+	cardId: string
+): Promise<CardDependencies> {
+	return tauriInvoke<CardDependencies>('list_card_dependencies', { projectId, cardId });
+}
+
+export async function addCardDependency(
+	projectId: string,
+	cardId: string,
+	dependsOnId: string
+): Promise<CardDependencies> {
+	return tauriInvoke<CardDependencies>('add_card_dependency', { projectId, cardId, dependsOnId });
+}
+
+export async function removeCardDependency(
+	projectId: string,
+	cardId: string,
+	dependsOnId: string
+): Promise<CardDependencies> {
+	return tauriInvoke<CardDependencies>('remove_card_dependency', { projectId, cardId, dependsOnId });
+}
+
+export async function getCardHistory(projectId: string, cardId: string): Promise<CardEvent[]> {
+	return tauriInvoke<CardEvent[]>('get_card_history', { projectId, cardId });
+}
+
+export async function listCardLogs(
+	projectId: string,
+	cardId: string,
+	minLevel?: LogLevel,
+	workspaceId?: string
+): Promise<CardLog[]> {
+	return tauriInvoke<CardLog[]>('list_card_logs', { projectId, cardId, minLevel, workspaceId });
+}
@@ -6,7 +6,10 @@ import type {
 	HttpServerConfigUpdate,
 	AgentProfileInput,
 	StatusGroupConfigInput,
-	StatusGroupDefaultsResponse
+	StatusGroupDefaultsResponse,
+	ApiTokenInfo,
+	ApiTokenInput,
+	CreatedApiToken
 } from '$lib/types/index.js';
 import { tauriInvoke } from './db.js';
 
@@ -77,6 +80,18 @@ export async function regenerateAuthToken(): Promise<string> {
 	return tauriInvoke<string>('regenerate_auth_token');
 }
 
+export async function createApiToken(input: ApiTokenInput): Promise<CreatedApiToken> {
+	return tauriInvoke<CreatedApiToken>('create_api_token', { ...input });
+}
+
+export async function listApiTokens(): Promise<ApiTokenInfo[]> {
+	return tauriInvoke<ApiTokenInfo[]>('list_api_tokens');
+}
+
+export async function revokeApiToken(id: string): Promise<void> {
+	return tauriInvoke<void>('revoke_api_token', { id });
+}
+
 export async function getLocalIp(): Promise<string> {
 	return tauriInvoke<string>('get_local_ip');
 }
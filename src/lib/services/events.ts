@@ -3,9 +3,12 @@ import { getTransportMode, getWebSocketUrl, type TransportMode } from './db.js';
 
 interface MaestroEvent {
 	event_type: string;
-	payload: unknown;
+	data: { data?: unknown } | null;
 }
 
+/** Events the manager raises itself rather than receiving from the server. */
+const LOCAL_EVENT_PREFIX = '__';
+
 type EventCallback<T> = (payload: T) => void;
 
 export type ConnectionStatus = 'connected' | 'reconnecting' | 'disconnected';
@@ -50,6 +53,7 @@ class WebSocketEventManager {
 					this.reconnectAttempts = 0;
 					this.hasConnectedBefore = true;
 					connectionStatus.set('connected');
+					this.sendSubscription();
 					if (isReconnect) {
 						this.dispatchLocalEvent('__ws_reconnected__', {});
 					}
@@ -83,11 +87,16 @@ class WebSocketEventManager {
 	private handleMessage(data: string): void {
 		try {
 			const event = JSON.parse(data) as MaestroEvent;
+			if (event.event_type === 'events-lagged') {
+				// Some events were dropped; reload as if the socket had reconnected.
+				this.dispatchLocalEvent('__ws_reconnected__', {});
+				return;
+			}
 			const callbacks = this.listeners.get(event.event_type);
 			if (callbacks) {
 				for (const callback of callbacks) {
 					try {
-						callback(event.payload);
+						callback(event.data?.data);
 					} catch (e) {
 						console.error('Error in event callback:', e);
 					}
@@ -98,6 +107,17 @@ class WebSocketEventManager {
 		}
 	}
 
+	/** Asks the server for only the event types something is listening to. */
+	private sendSubscription(): void {
+		if (!this.ws || this.ws.readyState !== WebSocket.OPEN) {
+			return;
+		}
+		const eventTypes = [...this.listeners.keys()].filter(
+			(name) => !name.startsWith(LOCAL_EVENT_PREFIX)
+		);
+		this.ws.send(JSON.stringify({ type: 'subscribe', id: 'listeners', event_types: eventTypes }));
+	}
+
 	private scheduleReconnect(): void {
 		if (this.reconnectTimer) {
 			return;
@@ -128,6 +148,7 @@ class WebSocketEventManager {
 		if (!callbacks) {
 			callbacks = new Set();
 			this.listeners.set(eventName, callbacks);
+			this.sendSubscription();
 		}
 		callbacks.add(callback as EventCallback<unknown>);
 
@@ -144,6 +165,7 @@ class WebSocketEventManager {
 			callbacks.delete(callback as EventCallback<unknown>);
 			if (callbacks.size === 0) {
 				this.listeners.delete(eventName);
+				this.sendSubscription();
 			}
 		}
 
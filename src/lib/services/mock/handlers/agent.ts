@@ -16,7 +16,8 @@ export function launch_agent(args: Record<string, unknown>): AgentWorkspace {
 		branch_name: branchName,
 		review_count: 0,
 		attached_at: nowISO(),
-		completed_at: null
+		completed_at: null,
+		exit_reason: null
 	};
 	store.agentWorkspaces.push(workspace);
 
@@ -29,6 +30,14 @@ export function send_agent_input(_: Record<string, unknown>): void {
 	void _; // No-op in mock mode
 }
 
+export function write_agent_terminal(_: Record<string, unknown>): void {
+	void _; // No-op in mock mode
+}
+
+export function resize_agent_terminal(_: Record<string, unknown>): void {
+	void _; // No-op in mock mode
+}
+
 export function stop_agent(args: Record<string, unknown>): AgentWorkspace {
 	const store = getStore();
 	const workspaceId = args.workspaceId as string;
@@ -66,7 +75,8 @@ export function resume_agent(args: Record<string, unknown>): AgentWorkspace {
 		branch_name: oldWs?.branch_name ?? null,
 		review_count: 0,
 		attached_at: nowISO(),
-		completed_at: null
+		completed_at: null,
+		exit_reason: null
 	};
 	store.agentWorkspaces.push(workspace);
 	simulateMockOutput(workspace.id);
@@ -1,6 +1,27 @@
-import type { CardWithStatus } from '$lib/types/index.js';
+import type { CardWithStatus, CardDependencies, CardEvent, CardLog, LogLevel } from '$lib/types/index.js';
 import { getStore, newId, nowISO, enrichCard } from '../store.js';
 
+function recordEvent(
+	cardId: string,
+	eventType: string,
+	oldValue: unknown,
+	newValue: unknown
+): void {
+	const store = getStore();
+	const event: CardEvent = {
+		id: store.cardEvents.length + 1,
+		card_id: cardId,
+		event_type: eventType,
+		actor_type: 'user',
+		actor_id: null,
+		actor_name: null,
+		old_value: oldValue,
+		new_value: newValue,
+		created_at: nowISO()
+	};
+	store.cardEvents.push(event);
+}
+
 export function create_card(args: Record<string, unknown>): CardWithStatus {
 	const store = getStore();
 	const projectId = args.projectId as string;
@@ -33,6 +54,7 @@ export function create_card(args: Record<string, unknown>): CardWithStatus {
 		updated_at: now
 	};
 	store.cards.push(card);
+	recordEvent(card.id, 'created', null, { title: card.title, status_id: card.status_id });
 	return enrichCard(card, store.statuses);
 }
 
@@ -51,6 +73,9 @@ export function update_card(args: Record<string, unknown>): CardWithStatus {
 		(c) => c.id === args.id && c.project_id === args.projectId
 	);
 	if (!card) throw new Error(`Card not found: ${args.id}`);
+	if (args.title !== undefined && args.title !== card.title) {
+		recordEvent(card.id, 'updated', { title: card.title }, { title: args.title });
+	}
 	if (args.title !== undefined) card.title = args.title as string;
 	if (args.description !== undefined) card.description = args.description as string;
 	if (args.labels !== undefined) card.labels = args.labels as string[];
@@ -60,7 +85,11 @@ export function update_card(args: Record<string, unknown>): CardWithStatus {
 
 export function delete_card(args: Record<string, unknown>): void {
 	const store = getStore();
+	recordEvent(args.id as string, 'deleted', null, null);
 	store.cards = store.cards.filter((c) => c.id !== args.id);
+	store.cardDependencies = store.cardDependencies.filter(
+		(d) => d.card_id !== args.id && d.depends_on_id !== args.id
+	);
 }
 
 export function list_cards(args: Record<string, unknown>): CardWithStatus[] {
@@ -85,6 +114,9 @@ export function move_card(args: Record<string, unknown>): CardWithStatus {
 		(c) => c.id === args.id && c.project_id === args.projectId
 	);
 	if (!card) throw new Error(`Card not found: ${args.id}`);
+	if (card.status_id !== args.targetStatusId) {
+		recordEvent(card.id, 'moved', { status_id: card.status_id }, { status_id: args.targetStatusId });
+	}
 	card.status_id = args.targetStatusId as string;
 	card.sort_order = args.targetSortOrder as number;
 	card.updated_at = nowISO();
@@ -104,3 +136,88 @@ export function reorder_cards(args: Record<string, unknown>): CardWithStatus[] {
 		.sort((a, b) => a.sort_order - b.sort_order)
 		.map((c) => enrichCard(c, store.statuses));
 }
+
+function dependencies(cardId: string): CardDependencies {
+	const store = getStore();
+	const enriched = (ids: string[]) =>
+		store.cards
+			.filter((c) => ids.includes(c.id))
+			.sort((a, b) => a.title.localeCompare(b.title))
+			.map((c) => enrichCard(c, store.statuses));
+	return {
+		blocked_by: enriched(
+			store.cardDependencies.filter((d) => d.card_id === cardId).map((d) => d.depends_on_id)
+		),
+		blocks: enriched(
+			store.cardDependencies.filter((d) => d.depends_on_id === cardId).map((d) => d.card_id)
+		)
+	};
+}
+
+function dependsOn(from: string, to: string): boolean {
+	const store = getStore();
+	const seen = new Set<string>();
+	const stack = [from];
+	while (stack.length > 0) {
+		const id = stack.pop()!;
+		if (id === to) return true;
+		if (seen.has(id)) continue;
+		seen.add(id);
+		for (const d of store.cardDependencies) {
+			if (d.card_id === id) stack.push(d.depends_on_id);
+		}
+	}
+	return false;
+}
+
+export function list_card_dependencies(args: Record<string, unknown>): CardDependencies {
+	return dependencies(args.cardId as string);
+}
+
+export function add_card_dependency(args: Record<string, unknown>): CardDependencies {
+	const store = getStore();
+	const cardId = args.cardId as string;
+	const dependsOnId = args.dependsOnId as string;
+	if (cardId === dependsOnId) throw new Error('A card cannot depend on itself');
+	for (const id of [cardId, dependsOnId]) {
+		if (!store.cards.some((c) => c.id === id && c.project_id === args.projectId)) {
+			throw new Error(`Card ${id} not found in project`);
+		}
+	}
+	if (dependsOn(dependsOnId, cardId)) {
+		throw new Error('Adding this dependency would create a cycle');
+	}
+	if (!store.cardDependencies.some((d) => d.card_id === cardId && d.depends_on_id === dependsOnId)) {
+		store.cardDependencies.push({ card_id: cardId, depends_on_id: dependsOnId, created_at: nowISO() });
+	}
+	return dependencies(cardId);
+}
+
+export function remove_card_dependency(args: Record<string, unknown>): CardDependencies {
+	const store = getStore();
+	const cardId = args.cardId as string;
+	const before = store.cardDependencies.length;
+	store.cardDependencies = store.cardDependencies.filter(
+		(d) => !(d.card_id === cardId && d.depends_on_id === args.dependsOnId)
+	);
+	if (store.cardDependencies.length === before) {
+		throw new Error(`Card ${cardId} does not depend on ${args.dependsOnId}`);
+	}
+	return dependencies(cardId);
+}
+
+export function get_card_history(args: Record<string, unknown>): CardEvent[] {
+	return getStore().cardEvents.filter((e) => e.card_id === args.cardId);
+}
+
+const LOG_LEVELS: LogLevel[] = ['debug', 'info', 'warn', 'error'];
+
+export function list_card_logs(args: Record<string, unknown>): CardLog[] {
+	const minLevel = LOG_LEVELS.indexOf((args.minLevel as LogLevel | undefined) ?? 'debug');
+	return getStore().cardLogs.filter(
+		(l) =>
+			l.card_id === args.cardId &&
+			LOG_LEVELS.indexOf(l.level) >= minLevel &&
+			(!args.workspaceId || l.workspace_id === args.workspaceId)
+	);
+}
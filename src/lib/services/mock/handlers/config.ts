@@ -4,9 +4,12 @@ import type {
 	HttpServerConfigResponse,
 	AgentProfileInput,
 	StatusGroupConfigInput,
-	StatusGroupDefaultsResponse
+	StatusGroupDefaultsResponse,
+	ApiTokenInfo,
+	CreatedApiToken,
+	TokenScope
 } from '$lib/types/index.js';
-import { getStore } from '../store.js';
+import { getStore, newId } from '../store.js';
 
 export function get_global_config(): GlobalConfigResponse {
 	return { ...getStore().globalConfig };
@@ -121,7 +124,9 @@ const mockHttpConfig = {
 	bind_address: '127.0.0.1',
 	auth_token: 'mock-token-12345678',
 	requires_auth: false,
-	server_url: 'http://127.0.0.1:3456'
+	server_url: 'http://127.0.0.1:3456',
+	tls_enabled: false,
+	tls_fingerprint: null as string | null
 };
 
 export function get_http_server_config(): HttpServerConfigResponse {
@@ -129,7 +134,9 @@ export function get_http_server_config(): HttpServerConfigResponse {
 }
 
 export function update_http_server_config(args: Record<string, unknown>): HttpServerConfigResponse {
-	const update = args.update as { enabled?: boolean; port?: number; bind_address?: string } | undefined;
+	const update = args.update as
+		| { enabled?: boolean; port?: number; bind_address?: string; tls_enabled?: boolean }
+		| undefined;
 	if (update) {
 		if (update.port !== undefined) {
 			mockHttpConfig.port = update.port;
@@ -138,8 +145,15 @@ export function update_http_server_config(args: Record<string, unknown>): HttpSe
 			mockHttpConfig.bind_address = update.bind_address;
 			mockHttpConfig.requires_auth = update.bind_address !== '127.0.0.1' && update.bind_address !== 'localhost';
 		}
+		if (update.tls_enabled !== undefined) {
+			mockHttpConfig.tls_enabled = update.tls_enabled;
+			mockHttpConfig.tls_fingerprint = update.tls_enabled
+				? Array.from({ length: 32 }, (_, i) => ((i * 37) % 256).toString(16).padStart(2, '0').toUpperCase()).join(':')
+				: null;
+		}
 		const displayAddress = mockHttpConfig.bind_address === '0.0.0.0' ? '192.168.1.100' : mockHttpConfig.bind_address;
-		mockHttpConfig.server_url = `http://${displayAddress}:${mockHttpConfig.port}`;
+		const scheme = mockHttpConfig.tls_enabled ? 'https' : 'http';
+		mockHttpConfig.server_url = `${scheme}://${displayAddress}:${mockHttpConfig.port}`;
 	}
 	return { ...mockHttpConfig };
 }
@@ -150,6 +164,34 @@ export function regenerate_auth_token(): string {
 	return newToken;
 }
 
+let mockApiTokens: ApiTokenInfo[] = [];
+
+export function create_api_token(args: Record<string, unknown>): CreatedApiToken {
+	const now = new Date();
+	const expiresInDays = args.expiresInDays as number | undefined;
+	const info: ApiTokenInfo = {
+		id: newId(),
+		name: args.name as string,
+		scopes: args.scopes as TokenScope[],
+		projects: (args.projects as string[] | undefined) ?? [],
+		created_at: now.toISOString(),
+		expires_at: expiresInDays
+			? new Date(now.getTime() + expiresInDays * 24 * 60 * 60 * 1000).toISOString()
+			: null,
+		last_used_at: null
+	};
+	mockApiTokens.push(info);
+	return { ...info, token: 'mock-api-token-' + Math.random().toString(36).substring(2, 10) };
+}
+
+export function list_api_tokens(): ApiTokenInfo[] {
+	return mockApiTokens.map((t) => ({ ...t }));
+}
+
+export function revoke_api_token(args: Record<string, unknown>): void {
+	mockApiTokens = mockApiTokens.filter((t) => t.id !== args.id);
+}
+
 export function get_local_ip(): string {
 	return '192.168.1.100';
 }
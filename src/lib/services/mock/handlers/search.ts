@@ -0,0 +1,83 @@
+import type { SearchResult, SearchResultKind } from '$lib/types/index.js';
+import { getStore } from '../store.js';
+
+interface Document {
+	kind: SearchResultKind;
+	id: string;
+	card_id: string;
+	title: string;
+	body: string;
+}
+
+function snippet(text: string, term: string): string {
+	const index = text.toLowerCase().indexOf(term);
+	if (index < 0) return text.slice(0, 80);
+	const start = Math.max(0, index - 30);
+	const hit = text.slice(index, index + term.length);
+	const before = (start > 0 ? '…' : '') + text.slice(start, index);
+	return `${before}**${hit}**${text.slice(index + term.length, index + term.length + 40)}…`;
+}
+
+// Substring match standing in for the backend's full-text index.
+export function search(args: Record<string, unknown>): SearchResult[] {
+	const store = getStore();
+	const projectId = args.projectId as string;
+	const terms = ((args.query as string) ?? '').toLowerCase().split(/\s+/).filter(Boolean);
+	const limit = (args.limit as number | undefined) ?? 20;
+	if (terms.length === 0) return [];
+
+	const cards = new Map(
+		store.cards.filter((c) => c.project_id === projectId).map((c) => [c.id, c])
+	);
+	const conversationCards = new Map(store.conversations.map((c) => [c.id, c.card_id]));
+	const documents: Document[] = [
+		...[...cards.values()].map((c) => ({
+			kind: 'card' as const,
+			id: c.id,
+			card_id: c.id,
+			title: c.title,
+			body: c.description
+		})),
+		...store.questions.map((q) => ({
+			kind: 'question' as const,
+			id: q.id,
+			card_id: q.card_id,
+			title: q.question,
+			body: q.resolution ?? ''
+		})),
+		...store.artifacts.map((a) => ({
+			kind: 'artifact' as const,
+			id: a.id,
+			card_id: a.card_id,
+			title: a.name,
+			body: store.artifactContents.get(a.id) ?? ''
+		})),
+		...store.conversationMessages.map((m) => ({
+			kind: 'message' as const,
+			id: m.id,
+			card_id: conversationCards.get(m.conversation_id) ?? '',
+			title: '',
+			body: m.content
+		}))
+	];
+
+	const results: SearchResult[] = [];
+	for (const doc of documents) {
+		const card = cards.get(doc.card_id);
+		if (!card) continue;
+		const text = `${doc.title}\n${doc.body}`;
+		const lower = text.toLowerCase();
+		if (!terms.every((t) => lower.includes(t))) continue;
+		const titleHits = terms.filter((t) => doc.title.toLowerCase().includes(t)).length;
+		results.push({
+			kind: doc.kind,
+			id: doc.id,
+			card_id: doc.card_id,
+			card_title: card.title,
+			title: doc.title,
+			snippet: snippet(text, terms[0]),
+			rank: -titleHits
+		});
+	}
+	return results.sort((a, b) => a.rank - b.rank).slice(0, limit);
+}
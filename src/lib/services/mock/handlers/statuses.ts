@@ -1,4 +1,4 @@
-import type { Status } from '$lib/types/index.js';
+import type { AutoRunPolicy, Status } from '$lib/types/index.js';
 import type { StatusGroup } from '$lib/types/status.js';
 import { getStore, newId, nowISO } from '../store.js';
 
@@ -35,6 +35,7 @@ export function create_status(args: Record<string, unknown>): Status {
 		status_prompts:
 			(args.statusPrompts as string[]) ??
 			defaultStatusPromptsForGroup(args.group as string),
+		auto_run: null,
 		created_at: nowISO()
 	};
 	store.statuses.push(status);
@@ -53,6 +54,16 @@ export function update_status(args: Record<string, unknown>): Status {
 	return status;
 }
 
+export function set_status_auto_run(args: Record<string, unknown>): Status {
+	const store = getStore();
+	const status = store.statuses.find(
+		(s) => s.id === args.id && s.project_id === args.projectId
+	);
+	if (!status) throw new Error(`Status not found: ${args.id}`);
+	status.auto_run = (args.autoRun as AutoRunPolicy | null) ?? null;
+	return status;
+}
+
 export function delete_status(args: Record<string, unknown>): void {
 	const store = getStore();
 	store.statuses = store.statuses.filter((s) => s.id !== args.id);
@@ -9,6 +9,7 @@ import * as conversations from './handlers/conversations.js';
 import * as agent from './handlers/agent.js';
 import * as worktrees from './handlers/worktrees.js';
 import * as review from './handlers/review.js';
+import * as search from './handlers/search.js';
 
 type Handler = (args: Record<string, unknown>) => unknown;
 
@@ -22,6 +23,7 @@ const handlers: Record<string, Handler> = {
 	list_statuses: statuses.list_statuses,
 	create_status: statuses.create_status,
 	update_status: statuses.update_status,
+	set_status_auto_run: statuses.set_status_auto_run,
 	delete_status: statuses.delete_status,
 	reorder_statuses: statuses.reorder_statuses,
 
@@ -33,6 +35,11 @@ const handlers: Record<string, Handler> = {
 	list_sub_cards: cards.list_sub_cards,
 	move_card: cards.move_card,
 	reorder_cards: cards.reorder_cards,
+	list_card_dependencies: cards.list_card_dependencies,
+	add_card_dependency: cards.add_card_dependency,
+	remove_card_dependency: cards.remove_card_dependency,
+	get_card_history: cards.get_card_history,
+	list_card_logs: cards.list_card_logs,
 
 	get_global_config: config.get_global_config,
 	update_global_config: config.update_global_config,
@@ -47,6 +54,9 @@ const handlers: Record<string, Handler> = {
 	update_http_server_config: config.update_http_server_config,
 	regenerate_auth_token: config.regenerate_auth_token,
 	get_local_ip: config.get_local_ip,
+	create_api_token: config.create_api_token,
+	list_api_tokens: config.list_api_tokens,
+	revoke_api_token: config.revoke_api_token,
 
 	create_question: questions.create_question,
 	list_questions: questions.list_questions,
@@ -73,6 +83,8 @@ const handlers: Record<string, Handler> = {
 
 	launch_agent: agent.launch_agent,
 	send_agent_input: agent.send_agent_input,
+	write_agent_terminal: agent.write_agent_terminal,
+	resize_agent_terminal: agent.resize_agent_terminal,
 	stop_agent: agent.stop_agent,
 	resume_agent: agent.resume_agent,
 	list_workspaces: agent.list_workspaces,
@@ -92,7 +104,9 @@ const handlers: Record<string, Handler> = {
 	send_back_card: review.send_back_card,
 	approve_card: review.approve_card,
 	create_pr: review.create_pr,
-	get_review_count: review.get_review_count
+	get_review_count: review.get_review_count,
+
+	search: search.search
 };
 
 export function dispatchMockCommand<T>(command: string, args?: Record<string, unknown>): T {
@@ -1,4 +1,4 @@
-import type { Card, Status, Project, GlobalConfigResponse, OpenQuestion, Artifact, LinkedDirectory, Conversation, ConversationMessage, AgentWorkspace, StatusGroupConfigInput } from '$lib/types/index.js';
+import type { Card, CardEvent, CardLog, Status, Project, GlobalConfigResponse, OpenQuestion, Artifact, LinkedDirectory, Conversation, ConversationMessage, AgentWorkspace, StatusGroupConfigInput } from '$lib/types/index.js';
 import type { StatusGroup } from '$lib/types/status.js';
 
 export interface MockStore {
@@ -12,10 +12,19 @@ export interface MockStore {
 	conversations: Conversation[];
 	conversationMessages: ConversationMessage[];
 	agentWorkspaces: AgentWorkspace[];
+	cardDependencies: CardDependency[];
+	cardEvents: CardEvent[];
+	cardLogs: CardLog[];
 	globalConfig: GlobalConfigResponse;
 	statusGroupDefaults: Record<string, StatusGroupConfigInput>;
 }
 
+export interface CardDependency {
+	card_id: string;
+	depends_on_id: string;
+	created_at: string;
+}
+
 let store: MockStore | null = null;
 
 export function newId(): string {
@@ -65,6 +74,9 @@ function createSeedStore(): MockStore {
 		conversations: [],
 		conversationMessages: [],
 		agentWorkspaces: [],
+		cardDependencies: [],
+		cardEvents: [],
+		cardLogs: [],
 		globalConfig: {
 			storage_base_path: '/tmp/maestro',
 			default_agent: 'claude-code',
@@ -108,6 +120,7 @@ function createSeedStatuses(projectId: string, now: string): Status[] {
 		sort_order: i,
 		is_default: d.isDefault,
 		status_prompts: d.status_prompts,
+		auto_run: null,
 		created_at: now
 	}));
 }
@@ -220,9 +233,15 @@ export function enrichCard(
 	statuses: Status[]
 ): import('$lib/types/card.js').CardWithStatus {
 	const status = statuses.find((s) => s.id === card.status_id);
+	const blocked = (store?.cardDependencies ?? []).some((d) => {
+		if (d.card_id !== card.id) return false;
+		const blocker = store?.cards.find((c) => c.id === d.depends_on_id);
+		return statuses.find((s) => s.id === blocker?.status_id)?.group !== 'Completed';
+	});
 	return {
 		...card,
 		status_name: status?.name ?? 'Unknown',
-		status_group: status?.group ?? 'Backlog'
+		status_group: status?.group ?? 'Backlog',
+		blocked
 	};
 }
@@ -0,0 +1,10 @@
+import type { SearchResult } from '$lib/types/index.js';
+import { tauriInvoke } from './db.js';
+
+export async function search(
+	projectId: string,
+	query: string,
+	limit?: number
+): Promise<SearchResult[]> {
+	return tauriInvoke<SearchResult[]>('search', { projectId, query, limit });
+}
@@ -1,4 +1,4 @@
-import type { Status } from '$lib/types/index.js';
+import type { AutoRunPolicy, Status } from '$lib/types/index.js';
 import { tauriInvoke } from './db.js';
 
 export async function listStatuses(projectId: string): Promise<Status[]> {
@@ -31,6 +31,14 @@ export async function updateStatusPrompts(
 	return updateStatus(projectId, id, { statusPrompts });
 }
 
+export async function setStatusAutoRun(
+	projectId: string,
+	id: string,
+	autoRun: AutoRunPolicy | null
+): Promise<Status> {
+	return tauriInvoke<Status>('set_status_auto_run', { projectId, id, autoRun });
+}
+
 export async function deleteStatus(projectId: string, id: string): Promise<void> {
 	return tauriInvoke<void>('delete_status', { projectId, id });
 }
@@ -36,6 +36,7 @@ function makeCard(overrides: Partial<CardWithStatus> = {}): CardWithStatus {
 		updated_at: '2026-01-01T00:00:00Z',
 		status_name: 'Backlog',
 		status_group: 'Backlog',
+		blocked: false,
 		...overrides
 	};
 }
@@ -20,7 +20,8 @@ export async function switchProject(id: string): Promise<void> {
 	try {
 		const project = await getProject(id);
 		currentProject.set(project);
-		await setLastProject(id);
+		// Best effort: scoped API tokens may not change the global config.
+		await setLastProject(id).catch(() => {});
 	} finally {
 		isLoading.set(false);
 	}
@@ -13,9 +13,42 @@ export interface CardWithStatus {
 	updated_at: string;
 	status_name: string;
 	status_group: StatusGroup;
+	/** Some card this one depends on isn't Completed yet. */
+	blocked: boolean;
+}
+
+export interface CardDependencies {
+	blocked_by: CardWithStatus[];
+	blocks: CardWithStatus[];
 }
 
 export interface CardProgress {
 	completed: number;
 	total: number;
 }
+
+export type CardEventActor = 'user' | 'agent' | 'token';
+
+export interface CardEvent {
+	id: number;
+	card_id: string;
+	event_type: string;
+	actor_type: CardEventActor;
+	actor_id: string | null;
+	actor_name: string | null;
+	old_value: unknown;
+	new_value: unknown;
+	created_at: string;
+}
+
+export type LogLevel = 'debug' | 'info' | 'warn' | 'error';
+
+export interface CardLog {
+	id: number;
+	card_id: string;
+	workspace_id: string | null;
+	level: LogLevel;
+	message: string;
+	data: Record<string, unknown> | null;
+	created_at: string;
+}
@@ -1,6 +1,6 @@
 export type { StatusGroup } from './status.js';
 export { STATUS_GROUPS } from './status.js';
-export type { CardWithStatus, CardProgress } from './card.js';
+export type { CardWithStatus, CardProgress, CardDependencies, CardEvent, CardEventActor, CardLog, LogLevel } from './card.js';
 
 export interface Project {
 	id: string;
@@ -28,9 +28,16 @@ export interface Status {
 	sort_order: number;
 	is_default: boolean;
 	status_prompts: string[];
+	auto_run: AutoRunPolicy | null;
 	created_at: string;
 }
 
+export interface AutoRunPolicy {
+	agent: string | null;
+	create_worktree: boolean;
+	directory_id: string | null;
+}
+
 export interface Card {
 	id: string;
 	project_id: string;
@@ -71,7 +78,15 @@ export interface ConversationMessage {
 	timestamp: string;
 }
 
-export type AgentWorkspaceStatus = 'running' | 'paused' | 'reviewing' | 'completed' | 'failed';
+export type AgentWorkspaceStatus =
+	| 'queued'
+	| 'running'
+	| 'paused'
+	| 'reviewing'
+	| 'completed'
+	| 'failed'
+	| 'timed_out'
+	| 'killed';
 
 export interface AgentWorkspace {
 	id: string;
@@ -85,6 +100,7 @@ export interface AgentWorkspace {
 	review_count: number;
 	attached_at: string;
 	completed_at: string | null;
+	exit_reason: string | null;
 }
 
 export interface Artifact {
@@ -104,12 +120,40 @@ export interface ProjectSummary {
 	created_at: string;
 }
 
+export type AgentOutputFormat = 'text' | 'claude-stream-json';
+
+export interface AgentLimits {
+	max_runtime_secs?: number | null;
+	idle_timeout_secs?: number | null;
+	max_memory_mb?: number | null;
+	max_cpu_secs?: number | null;
+}
+
+export type AgentAdapterKind = 'claude-code' | 'codex' | 'aider' | 'generic';
+
+export type PromptDelivery = 'arg' | 'stdin' | 'file';
+
+export interface AgentAdapterTemplate {
+	prompt?: PromptDelivery;
+	prompt_args?: string[];
+	model_args?: string[];
+	resume_args?: string[];
+	worktree_args?: string[];
+	worktree_path?: string | null;
+}
+
 export interface AgentProfileConfig {
 	name: string;
 	binary: string;
 	flags: string[];
 	custom_command: string | null;
 	env_vars: Record<string, string> | null;
+	session_id_pattern?: string | null;
+	output_format?: AgentOutputFormat;
+	limits?: AgentLimits;
+	pty?: boolean;
+	adapter?: AgentAdapterKind | null;
+	template?: AgentAdapterTemplate | null;
 }
 
 export interface AgentProfileInput {
@@ -118,6 +162,12 @@ export interface AgentProfileInput {
 	flags: string[];
 	custom_command: string | null;
 	env_vars: Record<string, string> | null;
+	session_id_pattern?: string | null;
+	output_format?: AgentOutputFormat;
+	limits?: AgentLimits;
+	pty?: boolean;
+	adapter?: AgentAdapterKind | null;
+	template?: AgentAdapterTemplate | null;
 }
 
 export interface GlobalConfigUpdate {
@@ -189,10 +239,48 @@ export interface HttpServerConfigResponse {
 	auth_token: string;
 	requires_auth: boolean;
 	server_url: string;
+	tls_enabled: boolean;
+	tls_fingerprint: string | null;
 }
 
 export interface HttpServerConfigUpdate {
 	enabled?: boolean;
 	port?: number;
 	bind_address?: string;
+	tls_enabled?: boolean;
+}
+
+export type TokenScope = 'read' | 'write' | 'agent';
+
+export interface ApiTokenInfo {
+	id: string;
+	name: string;
+	scopes: TokenScope[];
+	projects: string[];
+	created_at: string;
+	expires_at: string | null;
+	last_used_at: string | null;
+}
+
+export interface CreatedApiToken extends ApiTokenInfo {
+	token: string;
+}
+
+export interface ApiTokenInput {
+	name: string;
+	scopes: TokenScope[];
+	projects?: string[];
+	expiresInDays?: number;
+}
+
+export type SearchResultKind = 'card' | 'artifact' | 'question' | 'message';
+
+export interface SearchResult {
+	kind: SearchResultKind;
+	id: string;
+	card_id: string;
+	card_title: string;
+	title: string;
+	snippet: string;
+	rank: number;
 }